@@ -345,6 +345,54 @@ impl StructField {
                     },
                 }
             }
+        } else if "Float" == self.attrs.value {
+            if is_option {
+                quote! {
+                    #field_ident: match m.get(&#t) {
+                        Some(v) => Some(
+                            match v.as_float() {
+                                Some(val) => val,
+                                None => return Err(format!("Failed to to process {} as a float", #field_ident_str))
+                            }),
+                        None => None,
+                    },
+                }
+            } else {
+                quote! {
+                    #field_ident: match m[&#t].as_float() {
+                        Some(val) => val,
+                        None => return Err(format!("Failed to to process {} as a float", #field_ident_str))
+                    },
+                }
+            }
+        } else if "Number" == self.attrs.value {
+            if is_option {
+                quote! {
+                    #field_ident: match m.get(&#t) {
+                        Some(v) => Some(
+                            match v {
+                                Value::Integer(i) => match i64::try_from(*i) {
+                                    Ok(val) => val.into(),
+                                    Err(e) => return Err(format!("Failed to to process {} with error: {}", #field_ident_str, e))
+                                },
+                                Value::Float(f) => (*f).into(),
+                                _ => return Err(format!("Failed to to process {} as a number", #field_ident_str))
+                            }),
+                        None => None,
+                    },
+                }
+            } else {
+                quote! {
+                    #field_ident: match &m[&#t] {
+                        Value::Integer(i) => match i64::try_from(*i) {
+                            Ok(val) => val.into(),
+                            Err(e) => return Err(format!("Failed to to process {} with error: {}", #field_ident_str, e))
+                        },
+                        Value::Float(f) => (*f).into(),
+                        _ => return Err(format!("Failed to to process {} as a number", #field_ident_str))
+                    },
+                }
+            }
         } else if "Bool" == self.attrs.value {
             if is_option {
                 quote! {
@@ -575,6 +623,52 @@ impl StructField {
                     },
                 }
             }
+        } else if "Float" == self.attrs.value {
+            if is_option {
+                quote! {
+                    #field_ident: match v.get(#index) {
+                        Some(v) => {
+                            match v.as_float() {
+                                Some(v) => Some(v),
+                                None => return Err("".to_string())
+                            }
+                        },
+                        None => None
+                    },
+                }
+            } else {
+                quote! {
+                    #field_ident: match v[#index].as_float() {
+                        Some(v) => v,
+                        None => return Err("".to_string())
+                    },
+                }
+            }
+        } else if "Number" == self.attrs.value {
+            if is_option {
+                quote! {
+                    #field_ident: match v.get(#index) {
+                        Some(Value::Integer(i)) => match i64::try_from(*i) {
+                            Ok(val) => Some(val.into()),
+                            Err(_) => return Err("".to_string())
+                        },
+                        Some(Value::Float(f)) => Some((*f).into()),
+                        Some(_) => return Err("".to_string()),
+                        None => None
+                    },
+                }
+            } else {
+                quote! {
+                    #field_ident: match &v[#index] {
+                        Value::Integer(i) => match i64::try_from(*i) {
+                            Ok(val) => val.into(),
+                            Err(_) => return Err("".to_string())
+                        },
+                        Value::Float(f) => (*f).into(),
+                        _ => return Err("".to_string())
+                    },
+                }
+            }
         } else if "Bool" == self.attrs.value {
             if is_option {
                 quote! {
@@ -37,8 +37,11 @@ use syn::{parse_macro_input, DeriveInput};
 /// The following values are used from the `cbor` field attribute:
 /// - `tag`: indicates the integer key used to identify the associated field. The value will be
 /// included as the first element in a `(Value, Value)` production
-/// - `value`: indicates the type of ciborium `Value` used to represent the field. This is omitted if
-/// a `Value` is not used to represent the field.
+/// - `value`: indicates the type of ciborium `Value` used to represent the field, i.e., one of
+/// `Bytes`, `Map`, `Array`, `Text`, `Integer`, `Float`, `Number` or `Bool`. `Float` fields must be
+/// `f64`. `Number` accepts an integer or a float, and fields must implement `From<i64>` and
+/// `From<f64>`, e.g., [Number](../common/enum.Number.html). This is omitted if a `Value` is not
+/// used to represent the field.
 /// - `cbor`: indicates the inner type structure should have a `Cbor` suffix appended when generating
 /// or parsing CBOR-encoded representation of the field.
 ///
@@ -58,8 +61,11 @@ pub fn derive_struct_to_map(input: TokenStream) -> TokenStream {
 /// - `TryFrom` implementations to move between alternative structure and `Vec<Value>`
 ///
 /// The following values are used from the `cbor` field attribute:
-/// - `value`: indicates the type of ciborium `Value` used to represent the field. This is omitted if
-/// a `Value` is not used to represent the field.
+/// - `value`: indicates the type of ciborium `Value` used to represent the field, i.e., one of
+/// `Bytes`, `Map`, `Array`, `Text`, `Integer`, `Float`, `Number` or `Bool`. `Float` fields must be
+/// `f64`. `Number` accepts an integer or a float, and fields must implement `From<i64>` and
+/// `From<f64>`, e.g., [Number](../common/enum.Number.html). This is omitted if a `Value` is not
+/// used to represent the field.
 /// - `cbor`: indicates the inner type structure should have a `Cbor` suffix appended when generating
 /// or parsing CBOR-encoded representation of the field.
 ///
@@ -106,7 +112,6 @@ pub fn derive_struct_to_map(input: TokenStream) -> TokenStream {
 /// use hex_literal::hex;
 ///
 /// use common::arrays::{HashEntry, HashEntryCbor};
-
 ///
 /// let some_bytes = hex!("a200c11a637cffdc01c11a637d0decffa200c11a637cffdc01c11a637d0decff");
 /// let scratch = HashEntryCbor {
@@ -68,7 +68,7 @@ impl DeriveStructToArray {
 
         let comment = format!("CBOR encoding/decoding of [{}]", self.ident);
 
-        for (_field_count, field) in (self.fields).iter().enumerate() {
+        for field in (self.fields).iter() {
             let name = &field.ident;
 
             let ty = field.field_type.clone();
@@ -249,12 +249,12 @@ impl DeriveStructToArray {
                     where
                         __S: serde::Serializer,
                 {
-                    let mut v :Vec<Value> = match self.try_into() {
+                    // absent optional fields are omitted by try_into, so any NULL that remains is
+                    // the value of a field (e.g., a detached COSE payload) and must be retained
+                    let v :Vec<Value> = match self.try_into() {
                         Ok(r) => r,
                         Err(e) => { return Err(__S::Error::custom(e)) },
                     };
-                    // todo - what about fields that are encoded as NULL?
-                    v.retain(|x| *x != Value::Null);
                     let m = Value::Array(v);
                     m.serialize(__serializer)
                 }
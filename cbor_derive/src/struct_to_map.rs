@@ -68,7 +68,7 @@ impl DeriveStructToMap {
 
         let comment = format!("Supports CBOR encoding/decoding of the corresponding map type, which is described in [{}]", self.ident);
 
-        for (_field_count, field) in (self.fields).iter().enumerate() {
+        for field in (self.fields).iter() {
             let name = &field.ident;
 
             let ty = field.field_type.clone();
@@ -33,9 +33,8 @@ fn cots_create(args: &CotsCreateSubcommand) {
     }
 
     let mut files = vec![];
-    match &args.template {
-        Some(f) => files.push(f.clone()),
-        None => {}
+    if let Some(f) = &args.template {
+        files.push(f.clone())
     };
 
     if let Some(f) = args.template_dir.as_ref() {
@@ -27,9 +27,8 @@ fn eat_create(args: &EatCreateSubcommand) {
     }
 
     let mut files = vec![];
-    match &args.template {
-        Some(f) => files.push(f.clone()),
-        None => {}
+    if let Some(f) = &args.template {
+        files.push(f.clone())
     };
 
     if let Some(f) = args.template_dir.as_ref() {
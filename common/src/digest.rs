@@ -0,0 +1,251 @@
+//! Hash algorithm registry and digest computation for `hash-entry` values
+
+use crate::arrays::{HashEntry, HashEntryCbor};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use num_enum::TryFromPrimitive;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+use subtle::ConstantTimeEq;
+
+/// Hash algorithms from the [IANA Named Information Hash Algorithm Registry], which is the registry
+/// used to interpret the `hash-alg-id` field of a `hash-entry`.
+///
+/// When serialized as JSON, the registry's hash name string (i.e., "sha-256") is used.
+///
+/// [IANA Named Information Hash Algorithm Registry]: https://www.iana.org/assignments/named-information/named-information.xhtml
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize, TryFromPrimitive)]
+#[repr(u64)]
+#[allow(missing_docs)]
+pub enum HashAlgorithm {
+    #[serde(rename = "sha-256")]
+    Sha256 = 1,
+    #[serde(rename = "sha-256-128")]
+    Sha256_128 = 2,
+    #[serde(rename = "sha-256-120")]
+    Sha256_120 = 3,
+    #[serde(rename = "sha-256-96")]
+    Sha256_96 = 4,
+    #[serde(rename = "sha-256-64")]
+    Sha256_64 = 5,
+    #[serde(rename = "sha-256-32")]
+    Sha256_32 = 6,
+    #[serde(rename = "sha-384")]
+    Sha384 = 7,
+    #[serde(rename = "sha-512")]
+    Sha512 = 8,
+    #[serde(rename = "sha3-224")]
+    Sha3_224 = 9,
+    #[serde(rename = "sha3-256")]
+    Sha3_256 = 10,
+    #[serde(rename = "sha3-384")]
+    Sha3_384 = 11,
+    #[serde(rename = "sha3-512")]
+    Sha3_512 = 12,
+}
+
+impl HashAlgorithm {
+    /// Returns the registry identifier for the algorithm, i.e., the value used as `hash-alg-id`.
+    pub fn id(&self) -> u64 {
+        *self as u64
+    }
+
+    /// Returns the algorithm with the given registry identifier.
+    pub fn from_id(id: u64) -> Result<Self, String> {
+        HashAlgorithm::try_from_primitive(id)
+            .map_err(|_| format!("Unrecognized hash algorithm identifier: {}", id))
+    }
+
+    /// Returns the registry hash name string for the algorithm.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha-256",
+            HashAlgorithm::Sha256_128 => "sha-256-128",
+            HashAlgorithm::Sha256_120 => "sha-256-120",
+            HashAlgorithm::Sha256_96 => "sha-256-96",
+            HashAlgorithm::Sha256_64 => "sha-256-64",
+            HashAlgorithm::Sha256_32 => "sha-256-32",
+            HashAlgorithm::Sha384 => "sha-384",
+            HashAlgorithm::Sha512 => "sha-512",
+            HashAlgorithm::Sha3_224 => "sha3-224",
+            HashAlgorithm::Sha3_256 => "sha3-256",
+            HashAlgorithm::Sha3_384 => "sha3-384",
+            HashAlgorithm::Sha3_512 => "sha3-512",
+        }
+    }
+
+    /// Returns the algorithm with the given registry hash name string.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name {
+            "sha-256" => Ok(HashAlgorithm::Sha256),
+            "sha-256-128" => Ok(HashAlgorithm::Sha256_128),
+            "sha-256-120" => Ok(HashAlgorithm::Sha256_120),
+            "sha-256-96" => Ok(HashAlgorithm::Sha256_96),
+            "sha-256-64" => Ok(HashAlgorithm::Sha256_64),
+            "sha-256-32" => Ok(HashAlgorithm::Sha256_32),
+            "sha-384" => Ok(HashAlgorithm::Sha384),
+            "sha-512" => Ok(HashAlgorithm::Sha512),
+            "sha3-224" => Ok(HashAlgorithm::Sha3_224),
+            "sha3-256" => Ok(HashAlgorithm::Sha3_256),
+            "sha3-384" => Ok(HashAlgorithm::Sha3_384),
+            "sha3-512" => Ok(HashAlgorithm::Sha3_512),
+            _ => Err(format!("Unrecognized hash algorithm name: {}", name)),
+        }
+    }
+
+    /// Returns the length in bytes of the (possibly truncated) hash value the algorithm produces.
+    pub fn output_len(&self) -> usize {
+        match self {
+            HashAlgorithm::Sha256 => 32,
+            HashAlgorithm::Sha256_128 => 16,
+            HashAlgorithm::Sha256_120 => 15,
+            HashAlgorithm::Sha256_96 => 12,
+            HashAlgorithm::Sha256_64 => 8,
+            HashAlgorithm::Sha256_32 => 4,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 => 64,
+            HashAlgorithm::Sha3_224 => 28,
+            HashAlgorithm::Sha3_256 => 32,
+            HashAlgorithm::Sha3_384 => 48,
+            HashAlgorithm::Sha3_512 => 64,
+        }
+    }
+
+    /// Computes the hash value of `data` using the algorithm.
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        let mut hasher = Hasher::new(*self);
+        hasher.update(data);
+        hasher.finalize().hash_value
+    }
+}
+
+enum HasherState {
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+    Sha3_224(Sha3_224),
+    Sha3_256(Sha3_256),
+    Sha3_384(Sha3_384),
+    Sha3_512(Sha3_512),
+}
+
+/// Incremental hasher that produces a [`HashEntry`] for data supplied in one or more chunks.
+pub struct Hasher {
+    alg: HashAlgorithm,
+    state: HasherState,
+}
+
+impl Hasher {
+    /// Creates a new hasher for the given algorithm.
+    pub fn new(alg: HashAlgorithm) -> Self {
+        let state = match alg {
+            HashAlgorithm::Sha256
+            | HashAlgorithm::Sha256_128
+            | HashAlgorithm::Sha256_120
+            | HashAlgorithm::Sha256_96
+            | HashAlgorithm::Sha256_64
+            | HashAlgorithm::Sha256_32 => HasherState::Sha256(Sha256::new()),
+            HashAlgorithm::Sha384 => HasherState::Sha384(Sha384::new()),
+            HashAlgorithm::Sha512 => HasherState::Sha512(Sha512::new()),
+            HashAlgorithm::Sha3_224 => HasherState::Sha3_224(Sha3_224::new()),
+            HashAlgorithm::Sha3_256 => HasherState::Sha3_256(Sha3_256::new()),
+            HashAlgorithm::Sha3_384 => HasherState::Sha3_384(Sha3_384::new()),
+            HashAlgorithm::Sha3_512 => HasherState::Sha3_512(Sha3_512::new()),
+        };
+        Hasher { alg, state }
+    }
+
+    /// Feeds `data` to the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.state {
+            HasherState::Sha256(h) => h.update(data),
+            HasherState::Sha384(h) => h.update(data),
+            HasherState::Sha512(h) => h.update(data),
+            HasherState::Sha3_224(h) => h.update(data),
+            HasherState::Sha3_256(h) => h.update(data),
+            HasherState::Sha3_384(h) => h.update(data),
+            HasherState::Sha3_512(h) => h.update(data),
+        }
+    }
+
+    /// Consumes the hasher and returns a [`HashEntry`] containing the algorithm identifier and the
+    /// hash value, truncated as required by the algorithm.
+    pub fn finalize(self) -> HashEntry {
+        let mut hash_value = match self.state {
+            HasherState::Sha256(h) => h.finalize().to_vec(),
+            HasherState::Sha384(h) => h.finalize().to_vec(),
+            HasherState::Sha512(h) => h.finalize().to_vec(),
+            HasherState::Sha3_224(h) => h.finalize().to_vec(),
+            HasherState::Sha3_256(h) => h.finalize().to_vec(),
+            HasherState::Sha3_384(h) => h.finalize().to_vec(),
+            HasherState::Sha3_512(h) => h.finalize().to_vec(),
+        };
+        hash_value.truncate(self.alg.output_len());
+        HashEntry {
+            hash_alg_id: self.alg.id(),
+            hash_value,
+        }
+    }
+}
+
+/// Compares the hash of `data` to `expected` in constant time. Returns an error if `hash_alg_id`
+/// is not a recognized algorithm.
+fn verify_hash(hash_alg_id: u64, expected: &[u8], data: &[u8]) -> Result<bool, String> {
+    let alg = HashAlgorithm::from_id(hash_alg_id)?;
+    let actual = alg.digest(data);
+    Ok(actual.ct_eq(expected).into())
+}
+
+impl HashEntry {
+    /// Computes a `hash-entry` over `data` using the given algorithm.
+    pub fn compute(alg: HashAlgorithm, data: &[u8]) -> HashEntry {
+        let mut hasher = Hasher::new(alg);
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Computes a `hash-entry` over the remaining contents of `reader` using the given algorithm.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn compute_reader<R: std::io::Read>(
+        alg: HashAlgorithm,
+        reader: &mut R,
+    ) -> std::io::Result<HashEntry> {
+        let mut hasher = Hasher::new(alg);
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = reader.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(hasher.finalize())
+    }
+
+    /// Returns the algorithm identified by `hash_alg_id`.
+    pub fn algorithm(&self) -> Result<HashAlgorithm, String> {
+        HashAlgorithm::from_id(self.hash_alg_id)
+    }
+
+    /// Returns true if `hash_value` matches the hash of `data`. The comparison is performed in
+    /// constant time. Returns an error if `hash_alg_id` is not a recognized algorithm.
+    pub fn verify(&self, data: &[u8]) -> Result<bool, String> {
+        verify_hash(self.hash_alg_id, &self.hash_value, data)
+    }
+}
+
+impl HashEntryCbor {
+    /// Returns the algorithm identified by `hash_alg_id`.
+    pub fn algorithm(&self) -> Result<HashAlgorithm, String> {
+        HashAlgorithm::from_id(self.hash_alg_id)
+    }
+
+    /// Returns true if `hash_value` matches the hash of `data`. The comparison is performed in
+    /// constant time. Returns an error if `hash_alg_id` is not a recognized algorithm.
+    pub fn verify(&self, data: &[u8]) -> Result<bool, String> {
+        verify_hash(self.hash_alg_id, &self.hash_value, data)
+    }
+}
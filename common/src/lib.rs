@@ -7,8 +7,11 @@
 extern crate alloc;
 pub mod arrays;
 pub mod choices;
+pub mod digest;
+pub mod named;
 pub mod tuple;
 pub mod tuple_map;
+pub mod version;
 
 pub use tuple::*;
 
@@ -47,13 +50,26 @@ impl TryFrom<Value> for BytesType {
 /// Uri type
 pub type Uri = String;
 
-///tagged-int-type = #6.551(int)
+/// tagged-int-type = #6.551(int)
 pub type TaggedIntType = Required<IntType, 551>;
 
-//todo the cocli tests use tag 600 here
-///tagged-int-type = #6.600(int)
+/// Legacy form of `tagged-int-type` emitted by the Veraison corim implementation (and expected by
+/// the cocli tests), i.e., #6.600(bytes).
 pub type TaggedIntType2 = Required<IntType, 600>;
 
+/// Selects which form of `tagged-int-type` is emitted when encoding.
+///
+/// The CoRIM spec defines `tagged-int-type` as `#6.551(int)`. The Veraison corim implementation
+/// emits `#6.600(bytes)` instead. Both forms are accepted when decoding.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TaggedIntMode {
+    /// Emit `#6.551(int)` per the spec
+    #[default]
+    Spec,
+    /// Emit `#6.600(bytes)` for interop with Veraison
+    Legacy,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[allow(missing_docs)]
@@ -76,20 +92,77 @@ impl TryFrom<&Value> for NonceType {
     }
 }
 
-//todo the corim code emits bytes, but the spec says int
 /// type to serve as target for TaggedIntType
+///
+/// The `Int` variant is the form defined by the spec. The `Bytes` variant accommodates artifacts
+/// produced by the Veraison corim implementation, which carry a byte string instead of an int.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[allow(missing_docs)]
 pub enum IntType {
+    Int(i64),
     #[serde(with = "serde_bytes")]
-    Int(Vec<u8>),
+    Bytes(Vec<u8>),
+}
+impl IntType {
+    /// Returns the value as an int. Values in the legacy byte form are interpreted as big-endian
+    /// unsigned integers and must fit in an i64.
+    pub fn to_int(&self) -> Result<i64, String> {
+        match self {
+            IntType::Int(i) => Ok(*i),
+            IntType::Bytes(b) => {
+                let sig = match b.iter().position(|x| *x != 0) {
+                    Some(p) => &b[p..],
+                    None => return Ok(0),
+                };
+                if sig.len() > 8 || (sig.len() == 8 && sig[0] & 0x80 != 0) {
+                    return Err(
+                        "Failed to convert IntType bytes to an int: value too large".to_string()
+                    );
+                }
+                Ok(sig.iter().fold(0i64, |acc, x| (acc << 8) | *x as i64))
+            }
+        }
+    }
+
+    /// Returns the value in the legacy byte form. Int values are encoded as minimal length big-endian
+    /// unsigned integers and must not be negative.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        match self {
+            IntType::Int(i) => {
+                if *i < 0 {
+                    return Err("Failed to convert negative IntType value to bytes".to_string());
+                }
+                let be = i.to_be_bytes();
+                let p = be.iter().position(|x| *x != 0).unwrap_or(be.len() - 1);
+                Ok(be[p..].to_vec())
+            }
+            IntType::Bytes(b) => Ok(b.clone()),
+        }
+    }
 }
 impl TryFrom<&Value> for IntType {
     type Error = String;
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Bytes(k) => Ok(Self::Int(k.clone())),
+            Value::Integer(i) => match Integer::try_into(*i) {
+                Ok(i) => Ok(Self::Int(i)),
+                Err(e) => Err(e.to_string()),
+            },
+            Value::Bytes(k) => Ok(Self::Bytes(k.clone())),
+            _ => Err("Failed to parse value as an IntType".to_string()),
+        }
+    }
+}
+impl TryFrom<Value> for IntType {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(i) => match Integer::try_into(i) {
+                Ok(i) => Ok(Self::Int(i)),
+                Err(e) => Err(e.to_string()),
+            },
+            Value::Bytes(k) => Ok(Self::Bytes(k)),
             _ => Err("Failed to parse value as an IntType".to_string()),
         }
     }
@@ -292,9 +365,10 @@ impl TryFrom<&Value> for TimeCbor {
     type Error = String;
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
-            Value::Tag(_i, k) => Ok(Self::T(Required(
-                k.as_integer().unwrap().try_into().unwrap(),
-            ))),
+            Value::Tag(1, k) => match k.as_integer().and_then(|i| i64::try_from(i).ok()) {
+                Some(i) => Ok(Self::T(Required(i))),
+                None => Err("Failed to parse value as a TimeCbor".to_string()),
+            },
             _ => Err("Failed to parse value as a TimeCbor".to_string()),
         }
     }
@@ -320,6 +394,62 @@ impl TryFrom<&i64> for TimeCbor {
     }
 }
 
+// ; CWT and EAT use the untagged form of time for numeric dates.
+// ~time
+#[allow(missing_docs)]
+pub type NumericDate = i64;
+
+/// CBOR encoding/decoding of the numeric dates used by CWT and EAT claims, i.e., `~time`, see
+/// [CWT Section 2]. Tagged times are accepted as well, since they are emitted by existing
+/// implementations. Each form is retained when re-encoded. Values converted from the JSON model
+/// are encoded in the tagged form, as with [TimeCbor].
+///
+/// [CWT Section 2]: https://datatracker.ietf.org/doc/html/rfc8392#section-2
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum NumericDateCbor {
+    T(Required<i64, 1>),
+    Untagged(i64),
+}
+impl TryFrom<&Value> for NumericDateCbor {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(i) => match i64::try_from(*i) {
+                Ok(i) => Ok(Self::Untagged(i)),
+                Err(_) => Err("Failed to parse value as a NumericDateCbor".to_string()),
+            },
+            Value::Tag(1, _) => match TimeCbor::try_from(value) {
+                Ok(TimeCbor::T(t)) => Ok(Self::T(t)),
+                Err(_) => Err("Failed to parse value as a NumericDateCbor".to_string()),
+            },
+            _ => Err("Failed to parse value as a NumericDateCbor".to_string()),
+        }
+    }
+}
+impl TryFrom<&NumericDateCbor> for i64 {
+    type Error = String;
+    fn try_from(value: &NumericDateCbor) -> Result<Self, Self::Error> {
+        match value {
+            NumericDateCbor::T(k) => Ok(k.0),
+            NumericDateCbor::Untagged(k) => Ok(*k),
+        }
+    }
+}
+impl TryFrom<i64> for NumericDateCbor {
+    type Error = String;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        Ok(Self::T(Required(value)))
+    }
+}
+impl TryFrom<&i64> for NumericDateCbor {
+    type Error = String;
+    fn try_from(value: &i64) -> Result<Self, Self::Error> {
+        Ok(Self::T(Required(*value)))
+    }
+}
+
 // ; binary data that works for both JSON and CBOR.
 // binary-data = bstr
 //
@@ -437,6 +567,53 @@ impl TryFrom<Value> for PkixCa {
     }
 }
 
+/// number = int / float
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum Number {
+    Int(i64),
+    Float(f64),
+}
+impl Number {
+    /// Returns the value as a float. Ints with a magnitude greater than 2^53 lose precision.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Number::Int(i) => *i as f64,
+            Number::Float(f) => *f,
+        }
+    }
+}
+impl From<i64> for Number {
+    fn from(value: i64) -> Self {
+        Number::Int(value)
+    }
+}
+impl From<f64> for Number {
+    fn from(value: f64) -> Self {
+        Number::Float(value)
+    }
+}
+impl TryFrom<&Value> for Number {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(k) => match i64::try_from(*k) {
+                Ok(i) => Ok(Self::Int(i)),
+                Err(_) => Err("Failed to parse value as a Number".to_string()),
+            },
+            Value::Float(f) => Ok(Self::Float(*f)),
+            _ => Err("Failed to parse value as a Number".to_string()),
+        }
+    }
+}
+impl TryFrom<Value> for Number {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        Number::try_from(&value)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[allow(missing_docs)]
@@ -0,0 +1,65 @@
+//! Helpers for maps with text keys whose entries are kept in order as name/value pairs
+//!
+//! Claims such as the EAT `submods` claim are maps from a name to a value where the order of the
+//! entries is preserved and names must be unique. These helpers serialize and deserialize such maps
+//! as `Vec<(String, T)>`.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use ciborium::value::Value;
+use core::{fmt, marker::PhantomData};
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Serialize};
+
+/// Serializes name/value pairs as a map with text keys, preserving order.
+pub fn serialize_named<S: serde::Serializer, T: Serialize>(
+    entries: &[(String, T)],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(entries.iter().map(|(k, v)| (k, v)))
+}
+
+/// Deserializes a map with text keys as name/value pairs, preserving order.
+pub struct NamedVisitor<T>(pub PhantomData<T>);
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NamedVisitor<T> {
+    type Value = Vec<(String, T)>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map with text keys")
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut retval = vec![];
+        while let Some((k, v)) = map.next_entry::<String, T>()? {
+            if retval.iter().any(|(n, _): &(String, T)| *n == k) {
+                return Err(A::Error::custom(format!("Duplicate key: {}", k)));
+            }
+            retval.push((k, v));
+        }
+        Ok(retval)
+    }
+}
+
+/// Parses a CBOR map with text keys as name/value pairs, preserving order.
+pub fn named_from_value<T, F: Fn(&Value) -> Result<T, String>>(
+    value: &Value,
+    f: F,
+) -> Result<Vec<(String, T)>, String> {
+    let m = match value.as_map() {
+        Some(m) => m,
+        None => return Err("Failed to parse value as a map with text keys".to_string()),
+    };
+    let mut retval: Vec<(String, T)> = vec![];
+    for (k, v) in m {
+        let name = match k.as_text() {
+            Some(t) => t.to_string(),
+            None => return Err(format!("Expected text key but found {:?}", k)),
+        };
+        if retval.iter().any(|(n, _)| *n == name) {
+            return Err(format!("Duplicate key: {}", name));
+        }
+        retval.push((name, f(v)?));
+    }
+    Ok(retval)
+}
@@ -100,27 +100,15 @@ impl TryFrom<&Value> for TupleCbor {
 impl TryFrom<&TupleCbor> for Vec<Value> {
     type Error = String;
     fn try_from(value: &TupleCbor) -> Result<Self, Self::Error> {
-        let mut v = ::alloc::vec::Vec::new();
-        v.push(
-            match {
-                #[allow(unused_imports)]
-                use ::ciborium::value::Value::Null as null;
-                ::ciborium::value::Value::serialized(&value.key)
-            } {
-                Ok(v) => v,
-                Err(_) => return Err("Failed to parse TupleCbor".to_string()),
-            },
-        );
-        v.push(
-            match {
-                #[allow(unused_imports)]
-                use ::ciborium::value::Value::Null as null;
-                ::ciborium::value::Value::serialized(&value.value)
-            } {
-                Ok(v) => v,
-                Err(_) => return Err("Failed to parse TupleCbor".to_string()),
-            },
-        );
+        let key = match ::ciborium::value::Value::serialized(&value.key) {
+            Ok(v) => v,
+            Err(_) => return Err("Failed to parse TupleCbor".to_string()),
+        };
+        let val = match ::ciborium::value::Value::serialized(&value.value) {
+            Ok(v) => v,
+            Err(_) => return Err("Failed to parse TupleCbor".to_string()),
+        };
+        let v = vec![key, val];
         Ok(v)
     }
 }
@@ -220,15 +208,10 @@ impl<'de> Deserialize<'de> for TupleCbor {
                     Some(t) => t,
                     None => return Err(__D::Error::custom("Failed to parse tag value")),
                 };
-                match Integer::try_from(t.0) {
-                    Ok(i) => {
-                        let v0 = Value::Integer(i);
-                        let vals = vec![v0, t.1.clone()];
-                        match TupleCbor::try_from(vals) {
-                            Ok(r) => Ok(r),
-                            Err(e) => Err(__D::Error::custom(e)),
-                        }
-                    }
+                let v0 = Value::Integer(Integer::from(t.0));
+                let vals = vec![v0, t.1.clone()];
+                match TupleCbor::try_from(vals) {
+                    Ok(r) => Ok(r),
                     Err(e) => Err(__D::Error::custom(e)),
                 }
             }
@@ -100,19 +100,11 @@ impl TryFrom<&TupleMapCbor> for Vec<(Value, Value)> {
     fn try_from(value: &TupleMapCbor) -> Result<Self, Self::Error> {
         let mut v = ::alloc::vec::Vec::new();
         for i in &value.tuples {
-            let v1 = match {
-                #[allow(unused_imports)]
-                use ::ciborium::value::Value::Null as null;
-                ::ciborium::value::Value::serialized(&i.key)
-            } {
+            let v1 = match ::ciborium::value::Value::serialized(&i.key) {
                 Ok(v) => v,
                 Err(_) => return Err("Failed to parse TupleCbor".to_string()),
             };
-            let v2 = match {
-                #[allow(unused_imports)]
-                use ::ciborium::value::Value::Null as null;
-                ::ciborium::value::Value::serialized(&i.value)
-            } {
+            let v2 = match ::ciborium::value::Value::serialized(&i.value) {
                 Ok(v) => v,
                 Err(_) => return Err("Failed to parse TupleCbor".to_string()),
             };
@@ -0,0 +1,318 @@
+//! Version parsing and comparison keyed on `$version-scheme`
+
+use crate::choices::{VersionScheme, VersionSchemeCbor, VersionSchemeKnown};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Parsed representation of a version string, as interpreted according to a [`VersionSchemeKnown`]
+/// value.
+///
+/// Values are normalized when parsed so that versions that compare as equal are equal, i.e.,
+/// `1.2` and `1.2.0` are equal when parsed as multipartnumeric versions and semver build metadata is
+/// discarded. Versions parsed using different schemes are unordered.
+#[derive(Clone, Debug, Eq)]
+pub enum Version {
+    /// Numbers separated by dots, i.e., `1.2.3`. Trailing zero parts are not retained.
+    Multipartnumeric(Vec<u64>),
+    /// Numbers separated by dots followed by an optional textual suffix, i.e., `1.2.3a`. The
+    /// numeric parts are compared first, then the suffixes are compared lexically (no suffix sorts
+    /// before any suffix).
+    MultipartnumericSuffix(Vec<u64>, String),
+    /// Opaque text compared lexically.
+    AlphaNumeric(String),
+    /// Decimal number, i.e., `1.25`.
+    Decimal(DecimalVersion),
+    /// [Semantic Version 2.0.0] value.
+    ///
+    /// [Semantic Version 2.0.0]: https://semver.org/spec/v2.0.0.html
+    Semver(SemverVersion),
+}
+
+/// Decimal number in a normalized form: no leading zeros in the integer part, no trailing zeros in
+/// the fractional part and no negative zero.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecimalVersion {
+    /// True if the value is less than zero
+    pub negative: bool,
+    /// Digits of the integer part
+    pub integer: String,
+    /// Digits of the fractional part
+    pub fraction: String,
+}
+
+/// Semantic version, less build metadata (which does not figure into precedence).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SemverVersion {
+    /// Major version
+    pub major: u64,
+    /// Minor version
+    pub minor: u64,
+    /// Patch version
+    pub patch: u64,
+    /// Dot-separated pre-release identifiers, if any
+    pub pre_release: Vec<String>,
+}
+
+impl Version {
+    /// Parses `version` according to `scheme`.
+    pub fn parse(scheme: &VersionSchemeKnown, version: &str) -> Result<Self, String> {
+        match scheme {
+            VersionSchemeKnown::Multipartnumeric => {
+                Ok(Version::Multipartnumeric(parse_multipart(version)?))
+            }
+            VersionSchemeKnown::MultipartnumericSuffix => {
+                let end = version
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .unwrap_or(version.len());
+                let (numeric, suffix) = version.split_at(end);
+                let numeric = numeric.strip_suffix('.').unwrap_or(numeric);
+                Ok(Version::MultipartnumericSuffix(
+                    parse_multipart(numeric)?,
+                    suffix.to_string(),
+                ))
+            }
+            VersionSchemeKnown::AlphaNumeric => Ok(Version::AlphaNumeric(version.to_string())),
+            VersionSchemeKnown::Decimal => Ok(Version::Decimal(parse_decimal(version)?)),
+            VersionSchemeKnown::Semver => Ok(Version::Semver(parse_semver(version)?)),
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Version::Multipartnumeric(a), Version::Multipartnumeric(b)) => Some(a.cmp(b)),
+            (Version::MultipartnumericSuffix(a, sa), Version::MultipartnumericSuffix(b, sb)) => {
+                Some(a.cmp(b).then_with(|| sa.cmp(sb)))
+            }
+            (Version::AlphaNumeric(a), Version::AlphaNumeric(b)) => Some(a.cmp(b)),
+            (Version::Decimal(a), Version::Decimal(b)) => Some(a.cmp(b)),
+            (Version::Semver(a), Version::Semver(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl Ord for DecimalVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let magnitude = self
+            .integer
+            .len()
+            .cmp(&other.integer.len())
+            .then_with(|| self.integer.cmp(&other.integer))
+            .then_with(|| self.fraction.cmp(&other.fraction));
+        match (self.negative, other.negative) {
+            (false, false) => magnitude,
+            (true, true) => magnitude.reverse(),
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for DecimalVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SemverVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.major
+            .cmp(&other.major)
+            .then_with(|| self.minor.cmp(&other.minor))
+            .then_with(|| self.patch.cmp(&other.patch))
+            .then_with(
+                || match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => {
+                        for (a, b) in self.pre_release.iter().zip(other.pre_release.iter()) {
+                            let o = compare_pre_release_identifier(a, b);
+                            if o != Ordering::Equal {
+                                return o;
+                            }
+                        }
+                        self.pre_release.len().cmp(&other.pre_release.len())
+                    }
+                },
+            )
+    }
+}
+
+impl PartialOrd for SemverVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares `a` and `b` after parsing each according to `scheme`.
+pub fn compare_versions(scheme: &VersionSchemeKnown, a: &str, b: &str) -> Result<Ordering, String> {
+    let a = Version::parse(scheme, a)?;
+    let b = Version::parse(scheme, b)?;
+    match a.partial_cmp(&b) {
+        Some(o) => Ok(o),
+        None => Err("Versions are not comparable".to_string()),
+    }
+}
+
+impl VersionScheme {
+    /// Returns the known version scheme corresponding to this value. Text values that match the
+    /// name of a known scheme (i.e., "semver") are accepted. Other values yield an error.
+    pub fn to_known(&self) -> Result<VersionSchemeKnown, String> {
+        match self {
+            VersionScheme::Known(k) => Ok(k.clone()),
+            VersionScheme::Text(t) => known_scheme_from_name(t),
+            VersionScheme::IntExtensions(i) => Err(format!("Unsupported version scheme: {}", i)),
+        }
+    }
+}
+
+impl VersionSchemeCbor {
+    /// Returns the known version scheme corresponding to this value. Text values that match the
+    /// name of a known scheme (i.e., "semver") are accepted. Other values yield an error.
+    pub fn to_known(&self) -> Result<VersionSchemeKnown, String> {
+        match self {
+            VersionSchemeCbor::Known(k) => VersionSchemeKnown::try_from(k),
+            VersionSchemeCbor::Text(t) => known_scheme_from_name(t),
+            VersionSchemeCbor::IntExtensions(i) => {
+                Err(format!("Unsupported version scheme: {}", i))
+            }
+        }
+    }
+}
+
+/// Returns the version scheme to use when comparing versions, where an absent version scheme is
+/// treated as multipartnumeric per ISO/IEC 19770-2.
+pub fn effective_version_scheme(
+    scheme: Option<&VersionScheme>,
+) -> Result<VersionSchemeKnown, String> {
+    match scheme {
+        Some(vs) => vs.to_known(),
+        None => Ok(VersionSchemeKnown::Multipartnumeric),
+    }
+}
+
+fn known_scheme_from_name(name: &str) -> Result<VersionSchemeKnown, String> {
+    match name {
+        "multipartnumeric" => Ok(VersionSchemeKnown::Multipartnumeric),
+        "multipartnumeric+suffix" | "multipartnumeric-suffix" => {
+            Ok(VersionSchemeKnown::MultipartnumericSuffix)
+        }
+        "alphanumeric" => Ok(VersionSchemeKnown::AlphaNumeric),
+        "decimal" => Ok(VersionSchemeKnown::Decimal),
+        "semver" => Ok(VersionSchemeKnown::Semver),
+        _ => Err(format!("Unsupported version scheme: {}", name)),
+    }
+}
+
+fn parse_number(part: &str, version: &str) -> Result<u64, String> {
+    if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Failed to parse version: {}", version));
+    }
+    part.parse::<u64>()
+        .map_err(|_| format!("Failed to parse version: {}", version))
+}
+
+fn parse_multipart(version: &str) -> Result<Vec<u64>, String> {
+    let mut parts = Vec::new();
+    for part in version.split('.') {
+        parts.push(parse_number(part, version)?);
+    }
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    Ok(parts)
+}
+
+fn parse_decimal(version: &str) -> Result<DecimalVersion, String> {
+    let (negative, unsigned) = match version.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, version.strip_prefix('+').unwrap_or(version)),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((i, f)) => (i, f),
+        None => (unsigned, ""),
+    };
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(format!("Failed to parse version: {}", version));
+    }
+    let integer = integer.trim_start_matches('0').to_string();
+    let fraction = fraction.trim_end_matches('0').to_string();
+    let negative = negative && !(integer.is_empty() && fraction.is_empty());
+    Ok(DecimalVersion {
+        negative,
+        integer,
+        fraction,
+    })
+}
+
+fn parse_semver(version: &str) -> Result<SemverVersion, String> {
+    let without_build = match version.split_once('+') {
+        Some((v, build)) => {
+            if build.split('.').any(|id| !is_semver_identifier(id)) {
+                return Err(format!("Failed to parse version: {}", version));
+            }
+            v
+        }
+        None => version,
+    };
+    let (core, pre_release) = match without_build.split_once('-') {
+        Some((c, p)) => {
+            let ids: Vec<String> = p.split('.').map(|s| s.to_string()).collect();
+            if ids.iter().any(|id| {
+                !is_semver_identifier(id)
+                    || (id.len() > 1
+                        && id.starts_with('0')
+                        && id.bytes().all(|b| b.is_ascii_digit()))
+            }) {
+                return Err(format!("Failed to parse version: {}", version));
+            }
+            (c, ids)
+        }
+        None => (without_build, Vec::new()),
+    };
+    let mut numbers = Vec::new();
+    for part in core.split('.') {
+        if part.len() > 1 && part.starts_with('0') {
+            return Err(format!("Failed to parse version: {}", version));
+        }
+        numbers.push(parse_number(part, version)?);
+    }
+    if numbers.len() != 3 {
+        return Err(format!("Failed to parse version: {}", version));
+    }
+    Ok(SemverVersion {
+        major: numbers[0],
+        minor: numbers[1],
+        patch: numbers[2],
+        pre_release,
+    })
+}
+
+fn is_semver_identifier(id: &str) -> bool {
+    !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+fn compare_pre_release_identifier(a: &str, b: &str) -> Ordering {
+    let a_numeric = a.bytes().all(|c| c.is_ascii_digit());
+    let b_numeric = b.bytes().all(|c| c.is_ascii_digit());
+    match (a_numeric, b_numeric) {
+        (true, true) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.cmp(b),
+    }
+}
@@ -63,3 +63,125 @@ fn tuple_map_test() {
     let _ = into_writer(&egl_d, &mut encoded_token);
     assert_eq!(encoded_token, encoded_token2);
 }
+
+#[test]
+fn int_type_test() {
+    use common::IntType;
+    assert_eq!(IntType::Int(0x0102).to_bytes().unwrap(), vec![0x01, 0x02]);
+    assert_eq!(IntType::Int(0).to_bytes().unwrap(), vec![0x00]);
+    assert!(IntType::Int(-5).to_bytes().is_err());
+    assert_eq!(
+        IntType::Bytes(vec![0x00, 0x01, 0x02]).to_int().unwrap(),
+        0x0102
+    );
+    assert_eq!(IntType::Bytes(vec![]).to_int().unwrap(), 0);
+    assert!(IntType::Bytes(vec![0xFF; 9]).to_int().is_err());
+    assert!(IntType::Bytes(vec![0x80, 0, 0, 0, 0, 0, 0, 0])
+        .to_int()
+        .is_err());
+
+    let i = IntType::try_from(&Value::Integer(Integer::from(-7))).unwrap();
+    assert_eq!(i, IntType::Int(-7));
+    let b = IntType::try_from(&Value::Bytes(vec![1, 2])).unwrap();
+    assert_eq!(b, IntType::Bytes(vec![1, 2]));
+    assert!(IntType::try_from(&Value::Text("1".to_string())).is_err());
+}
+
+#[test]
+fn time_test() {
+    use ciborium::tag::Required;
+    use common::{NumericDateCbor, TimeCbor};
+    let tagged = Value::Tag(1, Box::new(Value::Integer(Integer::from(1601424000))));
+    let untagged = Value::Integer(Integer::from(1601424000));
+    let other_tag = Value::Tag(0, Box::new(Value::Text("2020-09-30".to_string())));
+    let not_an_int = Value::Tag(1, Box::new(Value::Text("2020-09-30".to_string())));
+
+    // time is always tagged
+    assert_eq!(
+        TimeCbor::try_from(&tagged).unwrap(),
+        TimeCbor::T(Required(1601424000))
+    );
+    assert!(TimeCbor::try_from(&untagged).is_err());
+    assert!(TimeCbor::try_from(&other_tag).is_err());
+    assert!(TimeCbor::try_from(&not_an_int).is_err());
+
+    // numeric dates may be either tagged or untagged
+    assert_eq!(
+        NumericDateCbor::try_from(&tagged).unwrap(),
+        NumericDateCbor::T(Required(1601424000))
+    );
+    let n = NumericDateCbor::try_from(&untagged).unwrap();
+    assert_eq!(n, NumericDateCbor::Untagged(1601424000));
+    assert_eq!(i64::try_from(&n).unwrap(), 1601424000);
+    assert!(NumericDateCbor::try_from(&other_tag).is_err());
+    assert!(NumericDateCbor::try_from(&not_an_int).is_err());
+}
+
+mod numbers {
+    use ciborium::{cbor, value::Value};
+    use common::{Number, TupleCbor};
+    use core::{fmt, marker::PhantomData};
+    use serde::de::{Error, MapAccess, Visitor};
+    use serde::ser::Error as OtherError;
+    use serde::{Deserialize, Deserializer, Serialize, __private::size_hint};
+    use std::collections::BTreeMap;
+
+    use cbor_derive::{StructToArray, StructToMap};
+
+    #[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
+    pub struct NumberMap {
+        #[cbor(tag = "1", value = "Float")]
+        pub float: f64,
+        #[cbor(tag = "2", value = "Number")]
+        pub number: Number,
+        #[cbor(tag = "3", value = "Float")]
+        pub opt_float: Option<f64>,
+        #[cbor(tag = "4", value = "Number")]
+        pub opt_number: Option<Number>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, StructToArray, Serialize, Deserialize)]
+    pub struct NumberArray {
+        #[cbor(value = "Float")]
+        pub float: f64,
+        #[cbor(value = "Number")]
+        pub number: Number,
+    }
+}
+
+#[test]
+fn number_test() {
+    use ciborium::de::from_reader;
+    use ciborium::ser::into_writer;
+    use common::Number;
+    use hex_literal::hex;
+    use numbers::*;
+
+    // {1: -1.5, 2: -7, 4: 2.5}
+    let enc = hex!("a301f9be00022604f94100");
+    let m: NumberMapCbor = from_reader(enc.as_slice()).unwrap();
+    assert_eq!(m.float, -1.5);
+    assert_eq!(m.number, Number::Int(-7));
+    assert_eq!(m.opt_float, None);
+    assert_eq!(m.opt_number, Some(Number::Float(2.5)));
+    let mut encoded = vec![];
+    into_writer(&m, &mut encoded).unwrap();
+    assert_eq!(encoded, enc);
+
+    // integers are not floats
+    let bad = hex!("a201010226");
+    assert!(from_reader::<NumberMapCbor, _>(bad.as_slice()).is_err());
+
+    // [-1.5, 2.5]
+    let enc = hex!("82f9be00f94100");
+    let a: NumberArrayCbor = from_reader(enc.as_slice()).unwrap();
+    assert_eq!(a.number, Number::Float(2.5));
+    assert_eq!(a.number.as_f64(), 2.5);
+    let mut encoded = vec![];
+    into_writer(&a, &mut encoded).unwrap();
+    assert_eq!(encoded, enc);
+
+    let json: NumberMap = serde_json::from_str(r#"{"float": 1.0, "number": -3}"#).unwrap();
+    assert_eq!(json.number, Number::Int(-3));
+    assert!(Number::try_from(&Value::Text("1".to_string())).is_err());
+}
@@ -0,0 +1,112 @@
+use common::arrays::*;
+use common::digest::*;
+use hex_literal::hex;
+
+#[test]
+fn hash_algorithm_test() {
+    assert_eq!(HashAlgorithm::Sha256.id(), 1);
+    assert_eq!(HashAlgorithm::Sha3_512.id(), 12);
+    assert_eq!(HashAlgorithm::from_id(7).unwrap(), HashAlgorithm::Sha384);
+    assert!(HashAlgorithm::from_id(0).is_err());
+    assert!(HashAlgorithm::from_id(13).is_err());
+
+    assert_eq!(HashAlgorithm::Sha256_128.name(), "sha-256-128");
+    assert_eq!(
+        HashAlgorithm::from_name("sha3-256").unwrap(),
+        HashAlgorithm::Sha3_256
+    );
+    assert!(HashAlgorithm::from_name("md5").is_err());
+
+    let json = serde_json::to_string(&HashAlgorithm::Sha512).unwrap();
+    assert_eq!(json, "\"sha-512\"");
+    let alg: HashAlgorithm = serde_json::from_str("\"sha-256-32\"").unwrap();
+    assert_eq!(alg, HashAlgorithm::Sha256_32);
+}
+
+#[test]
+fn hash_entry_compute_test() {
+    let he = HashEntry::compute(HashAlgorithm::Sha256, b"abc");
+    assert_eq!(he.hash_alg_id, 1);
+    assert_eq!(
+        he.hash_value,
+        hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(he.algorithm().unwrap(), HashAlgorithm::Sha256);
+
+    let he = HashEntry::compute(HashAlgorithm::Sha256_128, b"abc");
+    assert_eq!(he.hash_alg_id, 2);
+    assert_eq!(he.hash_value, hex!("ba7816bf8f01cfea414140de5dae2223"));
+
+    let he = HashEntry::compute(HashAlgorithm::Sha384, b"abc");
+    assert_eq!(
+        he.hash_value,
+        hex!("cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7")
+    );
+
+    let he = HashEntry::compute(HashAlgorithm::Sha512, b"abc");
+    assert_eq!(
+        he.hash_value,
+        hex!("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")
+    );
+
+    let he = HashEntry::compute(HashAlgorithm::Sha3_256, b"abc");
+    assert_eq!(
+        he.hash_value,
+        hex!("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+    );
+
+    for alg in [
+        HashAlgorithm::Sha256_120,
+        HashAlgorithm::Sha256_96,
+        HashAlgorithm::Sha256_64,
+        HashAlgorithm::Sha256_32,
+        HashAlgorithm::Sha3_224,
+        HashAlgorithm::Sha3_384,
+        HashAlgorithm::Sha3_512,
+    ] {
+        let he = HashEntry::compute(alg, b"abc");
+        assert_eq!(he.hash_value.len(), alg.output_len());
+        assert_eq!(he.hash_value, alg.digest(b"abc"));
+    }
+}
+
+#[test]
+fn hash_entry_streaming_test() {
+    let mut hasher = Hasher::new(HashAlgorithm::Sha256);
+    hasher.update(b"a");
+    hasher.update(b"bc");
+    assert_eq!(
+        hasher.finalize(),
+        HashEntry::compute(HashAlgorithm::Sha256, b"abc")
+    );
+
+    let data = vec![0x5au8; 10000];
+    let mut reader = data.as_slice();
+    let he = HashEntry::compute_reader(HashAlgorithm::Sha384, &mut reader).unwrap();
+    assert_eq!(he, HashEntry::compute(HashAlgorithm::Sha384, &data));
+}
+
+#[test]
+fn hash_entry_verify_test() {
+    let he = HashEntry::compute(HashAlgorithm::Sha256, b"abc");
+    assert!(he.verify(b"abc").unwrap());
+    assert!(!he.verify(b"abd").unwrap());
+
+    let hec = HashEntryCbor::try_from(&he).unwrap();
+    assert_eq!(hec.algorithm().unwrap(), HashAlgorithm::Sha256);
+    assert!(hec.verify(b"abc").unwrap());
+    assert!(!hec.verify(b"").unwrap());
+
+    // truncated value with the wrong length does not match
+    let short = HashEntry {
+        hash_alg_id: 1,
+        hash_value: he.hash_value[..16].to_vec(),
+    };
+    assert!(!short.verify(b"abc").unwrap());
+
+    let unknown = HashEntry {
+        hash_alg_id: 99,
+        hash_value: he.hash_value,
+    };
+    assert!(unknown.verify(b"abc").is_err());
+}
@@ -0,0 +1,42 @@
+use ciborium::value::Value;
+use common::named::*;
+use serde::Deserializer;
+use std::marker::PhantomData;
+
+#[test]
+fn named_test() {
+    let entries = vec![("b".to_string(), 1u8), ("a".to_string(), 2u8)];
+
+    // order is preserved in both directions
+    let mut json = vec![];
+    serialize_named(&entries, &mut serde_json::Serializer::new(&mut json)).unwrap();
+    assert_eq!(json, br#"{"b":1,"a":2}"#);
+    let decoded: Vec<(String, u8)> = serde_json::Deserializer::from_slice(&json)
+        .deserialize_map(NamedVisitor(PhantomData))
+        .unwrap();
+    assert_eq!(decoded, entries);
+    assert!(serde_json::Deserializer::from_str(r#"{"a":1,"a":2}"#)
+        .deserialize_map(NamedVisitor::<u8>(PhantomData))
+        .is_err());
+
+    let value = Value::Map(vec![
+        (Value::Text("b".to_string()), Value::Integer(1.into())),
+        (Value::Text("a".to_string()), Value::Integer(2.into())),
+    ]);
+    let to_u8 = |v: &Value| -> Result<u8, String> {
+        v.as_integer()
+            .and_then(|i| u8::try_from(i).ok())
+            .ok_or_else(|| "not a u8".to_string())
+    };
+    assert_eq!(named_from_value(&value, to_u8).unwrap(), entries);
+
+    // duplicate names, non-text keys and non-maps are rejected
+    let duplicate = Value::Map(vec![
+        (Value::Text("a".to_string()), Value::Integer(1.into())),
+        (Value::Text("a".to_string()), Value::Integer(2.into())),
+    ]);
+    assert!(named_from_value(&duplicate, to_u8).is_err());
+    let int_key = Value::Map(vec![(Value::Integer(1.into()), Value::Integer(1.into()))]);
+    assert!(named_from_value(&int_key, to_u8).is_err());
+    assert!(named_from_value(&Value::Array(vec![]), to_u8).is_err());
+}
@@ -0,0 +1,145 @@
+use common::choices::*;
+use common::version::*;
+use std::cmp::Ordering;
+
+#[test]
+fn multipartnumeric_test() {
+    let s = VersionSchemeKnown::Multipartnumeric;
+    assert_eq!(
+        compare_versions(&s, "1.2.3", "1.2.3").unwrap(),
+        Ordering::Equal
+    );
+    assert_eq!(
+        compare_versions(&s, "1.2", "1.2.0.0").unwrap(),
+        Ordering::Equal
+    );
+    assert_eq!(
+        compare_versions(&s, "1.10", "1.9").unwrap(),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions(&s, "1.2.3", "1.2.3.1").unwrap(),
+        Ordering::Less
+    );
+    assert!(Version::parse(&s, "1.2a").is_err());
+    assert!(Version::parse(&s, "1..2").is_err());
+    assert!(Version::parse(&s, "").is_err());
+}
+
+#[test]
+fn multipartnumeric_suffix_test() {
+    let s = VersionSchemeKnown::MultipartnumericSuffix;
+    assert_eq!(
+        compare_versions(&s, "1.2.3a", "1.2.3b").unwrap(),
+        Ordering::Less
+    );
+    assert_eq!(
+        compare_versions(&s, "1.2.3", "1.2.3a").unwrap(),
+        Ordering::Less
+    );
+    assert_eq!(
+        compare_versions(&s, "1.3a", "1.2.9z").unwrap(),
+        Ordering::Greater
+    );
+    assert_eq!(
+        Version::parse(&s, "1.2.3-beta").unwrap(),
+        Version::MultipartnumericSuffix(vec![1, 2, 3], "-beta".to_string())
+    );
+}
+
+#[test]
+fn alphanumeric_test() {
+    let s = VersionSchemeKnown::AlphaNumeric;
+    assert_eq!(compare_versions(&s, "abc", "abd").unwrap(), Ordering::Less);
+    assert_eq!(compare_versions(&s, "B", "A").unwrap(), Ordering::Greater);
+}
+
+#[test]
+fn decimal_test() {
+    let s = VersionSchemeKnown::Decimal;
+    assert_eq!(
+        compare_versions(&s, "1.25", "1.250").unwrap(),
+        Ordering::Equal
+    );
+    assert_eq!(
+        compare_versions(&s, "1.3", "1.25").unwrap(),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions(&s, "10", "9.99").unwrap(),
+        Ordering::Greater
+    );
+    assert_eq!(
+        compare_versions(&s, "007.1", "7.1").unwrap(),
+        Ordering::Equal
+    );
+    assert_eq!(
+        compare_versions(&s, "-1.5", "-1.25").unwrap(),
+        Ordering::Less
+    );
+    assert_eq!(compare_versions(&s, "-0", "0.0").unwrap(), Ordering::Equal);
+    assert!(Version::parse(&s, "1.2.3").is_err());
+    assert!(Version::parse(&s, ".").is_err());
+}
+
+#[test]
+fn semver_test() {
+    let s = VersionSchemeKnown::Semver;
+    // precedence example from semver 2.0.0 section 11
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+        "2.0.0",
+        "2.1.0",
+        "2.1.1",
+    ];
+    for pair in ordered.windows(2) {
+        assert_eq!(
+            compare_versions(&s, pair[0], pair[1]).unwrap(),
+            Ordering::Less,
+            "{} < {}",
+            pair[0],
+            pair[1]
+        );
+    }
+    assert_eq!(
+        compare_versions(&s, "1.0.0+build.1", "1.0.0+build.2").unwrap(),
+        Ordering::Equal
+    );
+    assert!(Version::parse(&s, "1.0").is_err());
+    assert!(Version::parse(&s, "01.0.0").is_err());
+    assert!(Version::parse(&s, "1.0.0-01").is_err());
+    assert!(Version::parse(&s, "1.0.0-").is_err());
+}
+
+#[test]
+fn version_scheme_test() {
+    assert_eq!(
+        VersionScheme::Text("semver".to_string())
+            .to_known()
+            .unwrap(),
+        VersionSchemeKnown::Semver
+    );
+    assert_eq!(
+        VersionSchemeCbor::Known(VersionSchemeKnownCbor::Decimal)
+            .to_known()
+            .unwrap(),
+        VersionSchemeKnown::Decimal
+    );
+    assert!(VersionScheme::IntExtensions(-1).to_known().is_err());
+    assert_eq!(
+        effective_version_scheme(None).unwrap(),
+        VersionSchemeKnown::Multipartnumeric
+    );
+
+    let a = Version::parse(&VersionSchemeKnown::Multipartnumeric, "1.0").unwrap();
+    let b = Version::parse(&VersionSchemeKnown::AlphaNumeric, "1.0").unwrap();
+    assert_eq!(a.partial_cmp(&b), None);
+    assert!(a != b);
+}
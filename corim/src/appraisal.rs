@@ -0,0 +1,441 @@
+//! Appraisal of evidence against the reference values and endorsed values conveyed by CoMIDs
+//!
+//! The matching rules follow [CoRIM Section 3.1.4.1]: an environment in a reference or endorsed
+//! value triple acts as a template that matches an evidence environment when each field that is
+//! present in the template is present and equal in the evidence. Measurements are paired by
+//! `mkey`, then each field present in the reference `measurement-values-map` is compared to the
+//! corresponding field of the evidence. Raw values are compared under the reference mask, if any.
+//!
+//! [CoRIM Section 3.1.4.1]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+use crate::arrays::*;
+use crate::choices::*;
+use crate::maps::*;
+use crate::validity::*;
+use common::arrays::HashEntry;
+
+/// Measurements observed for an environment, i.e., as conveyed by an attester in evidence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvidenceRecord {
+    /// Environment the measurements pertain to
+    pub environment: EnvironmentMap,
+    /// Measurements observed for the environment
+    pub measurements: Vec<MeasurementMap>,
+}
+
+impl From<ReferenceTripleRecord> for EvidenceRecord {
+    fn from(r: ReferenceTripleRecord) -> Self {
+        EvidenceRecord {
+            environment: r.environment_map,
+            measurements: r.measurement_map,
+        }
+    }
+}
+
+impl From<EndorsedTripleRecord> for EvidenceRecord {
+    fn from(r: EndorsedTripleRecord) -> Self {
+        EvidenceRecord {
+            environment: r.environment_map,
+            measurements: r.measurement_map,
+        }
+    }
+}
+
+/// Outcome of comparing the reference values for a claim to evidence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClaimStatus {
+    /// Evidence for the environment and measured element was found and each evidence measurement
+    /// matched one of the reference values
+    Matched,
+    /// Evidence for the environment and measured element was found but an evidence measurement
+    /// matched none of the reference values. The string describes the first difference
+    /// encountered.
+    Mismatched(String),
+    /// No evidence was found for the environment and measured element
+    NotFound,
+}
+
+/// Result of appraising the `measurement-map`s that share an `mkey` within a reference value
+/// triple. Such `measurement-map`s are alternative reference values for the same claim.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferenceClaimResult {
+    /// Tag ID of the CoMID that contained the reference value, if available
+    pub tag_id: Option<TagIdTypeChoice>,
+    /// Environment from the reference value triple
+    pub environment: EnvironmentMap,
+    /// Measured element identifier from the reference measurements
+    pub mkey: Option<MeasuredElementTypeChoice>,
+    /// Outcome of the comparison
+    pub status: ClaimStatus,
+}
+
+/// Results of appraising evidence against a set of CoMIDs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AppraisalResult {
+    /// One result per distinct `mkey` in each reference value triple
+    pub reference_results: Vec<ReferenceClaimResult>,
+    /// Indices of the evidence records that have a measurement that matched none of the reference
+    /// values for its claim
+    pub failed_evidence: Vec<usize>,
+    /// Endorsed value triples whose environments matched evidence for which no reference value
+    /// comparison failed
+    pub endorsements: Vec<EndorsedTripleRecord>,
+    /// CoRIMs that were not valid at the time of appraisal and whose contents were not used
+    pub stale_rims: Vec<RimValidity>,
+}
+
+impl AppraisalResult {
+    /// Returns true if at least one reference value was compared and no evidence record failed a
+    /// reference value comparison.
+    pub fn is_success(&self) -> bool {
+        self.reference_results
+            .iter()
+            .any(|r| r.status == ClaimStatus::Matched)
+            && self.failed_evidence.is_empty()
+            && !self
+                .reference_results
+                .iter()
+                .any(|r| matches!(r.status, ClaimStatus::Mismatched(_)))
+    }
+
+    /// Returns the results that did not match.
+    pub fn mismatches(&self) -> Vec<&ReferenceClaimResult> {
+        self.reference_results
+            .iter()
+            .filter(|r| matches!(r.status, ClaimStatus::Mismatched(_)))
+            .collect()
+    }
+}
+
+/// Appraises `evidence` against the reference value and endorsed value triples found in `comids`,
+/// which are assumed to have been verified by the caller.
+///
+/// The `measurement-map`s of a reference value triple that share an `mkey` are alternative
+/// reference values for one claim, and a result is emitted for each such claim. Each evidence
+/// measurement whose environment matches and whose `mkey` is equal must match at least one of the
+/// alternatives. The claim is mismatched if any such evidence measurement matches none of them,
+/// matched if evidence was found and all of it matched and not found otherwise. Evidence records
+/// with a mismatched measurement are listed in the `failed_evidence` field of the result.
+///
+/// An endorsed value triple is returned as an endorsement when its environment matches the
+/// environment of an evidence record and no measurement of that evidence record mismatched a
+/// reference value.
+pub fn appraise(comids: &[ConciseMidTag], evidence: &[EvidenceRecord]) -> AppraisalResult {
+    let mut retval = AppraisalResult::default();
+    let mut failed_evidence = vec![false; evidence.len()];
+
+    for comid in comids {
+        let tag_id = comid.tag_identity.as_ref().map(|t| t.tag_id.clone());
+        let rvs = match &comid.triples.reference_triples {
+            Some(rvs) => rvs,
+            None => continue,
+        };
+        for rv in rvs {
+            let mut mkeys: Vec<&Option<MeasuredElementTypeChoice>> = vec![];
+            for ref_m in &rv.measurement_map {
+                if !mkeys.contains(&&ref_m.mkey) {
+                    mkeys.push(&ref_m.mkey);
+                }
+            }
+            for mkey in mkeys {
+                let alternatives: Vec<&MeasurementMap> = rv
+                    .measurement_map
+                    .iter()
+                    .filter(|m| &m.mkey == mkey)
+                    .collect();
+                let mut status = ClaimStatus::NotFound;
+                for (i, ev) in evidence.iter().enumerate() {
+                    if !environment_matches(&rv.environment_map, &ev.environment) {
+                        continue;
+                    }
+                    for ev_m in ev.measurements.iter().filter(|m| &m.mkey == mkey) {
+                        match compare_alternatives(&alternatives, &ev_m.value) {
+                            Ok(()) => {
+                                if status == ClaimStatus::NotFound {
+                                    status = ClaimStatus::Matched
+                                }
+                            }
+                            Err(e) => {
+                                failed_evidence[i] = true;
+                                if !matches!(status, ClaimStatus::Mismatched(_)) {
+                                    status = ClaimStatus::Mismatched(e)
+                                }
+                            }
+                        }
+                    }
+                }
+                retval.reference_results.push(ReferenceClaimResult {
+                    tag_id: tag_id.clone(),
+                    environment: rv.environment_map.clone(),
+                    mkey: mkey.clone(),
+                    status,
+                });
+            }
+        }
+    }
+    retval.failed_evidence = failed_evidence
+        .iter()
+        .enumerate()
+        .filter(|(_, failed)| **failed)
+        .map(|(i, _)| i)
+        .collect();
+
+    for comid in comids {
+        let evs = match &comid.triples.endorsed_triples {
+            Some(evs) => evs,
+            None => continue,
+        };
+        for ev_triple in evs {
+            let applies = evidence.iter().enumerate().any(|(i, ev)| {
+                !failed_evidence[i]
+                    && environment_matches(&ev_triple.environment_map, &ev.environment)
+            });
+            if applies {
+                retval.endorsements.push(ev_triple.clone());
+            }
+        }
+    }
+    retval
+}
+
+/// Returns Ok if `evidence` matches any of `alternatives`, otherwise the error from comparison with
+/// the first alternative.
+fn compare_alternatives(
+    alternatives: &[&MeasurementMap],
+    evidence: &MeasurementValuesMap,
+) -> Result<(), String> {
+    let mut first_err = None;
+    for alt in alternatives {
+        match compare_measurement_values(&alt.value, evidence) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                if first_err.is_none() {
+                    first_err = Some(e)
+                }
+            }
+        }
+    }
+    Err(first_err.unwrap_or_else(|| "no reference value".to_string()))
+}
+
+/// A CoRIM whose signature, if any, has been verified by the caller.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifiedCorim {
+    /// The CoRIM
+    pub corim: CorimMap,
+    /// Validity period of the signature that covered the CoRIM, if any
+    pub signer_validity: Option<ValidityMap>,
+}
+
+impl From<CorimMap> for VerifiedCorim {
+    fn from(corim: CorimMap) -> Self {
+        VerifiedCorim {
+            corim,
+            signer_validity: None,
+        }
+    }
+}
+
+/// Appraises `evidence` against the CoMIDs found in those `corims` that are valid at the time
+/// returned by `clock` (see [evaluate_rim]). CoRIMs that are not valid are listed in the
+/// `stale_rims` field of the result and their reference values and endorsed values are not used.
+/// Returns an error if a tag in a valid CoRIM cannot be parsed.
+pub fn appraise_corims<C: Clock + ?Sized>(
+    corims: &[VerifiedCorim],
+    evidence: &[EvidenceRecord],
+    clock: &C,
+) -> Result<AppraisalResult, String> {
+    let mut comids = vec![];
+    let mut stale_rims = vec![];
+    for c in corims {
+        let validity = evaluate_rim(&c.corim, c.signer_validity.as_ref(), clock);
+        if validity.is_valid() {
+            comids.append(&mut c.corim.comids()?);
+        } else {
+            stale_rims.push(validity);
+        }
+    }
+    let mut retval = appraise(&comids, evidence);
+    retval.stale_rims = stale_rims;
+    Ok(retval)
+}
+
+/// Returns true if each field present in `reference` is present and equal in `evidence`.
+pub fn environment_matches(reference: &EnvironmentMap, evidence: &EnvironmentMap) -> bool {
+    if let Some(rc) = &reference.class {
+        match &evidence.class {
+            Some(ec) => {
+                if !class_matches(rc, ec) {
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+    if reference.instance.is_some() && reference.instance != evidence.instance {
+        return false;
+    }
+    if reference.group.is_some() && reference.group != evidence.group {
+        return false;
+    }
+    true
+}
+
+/// Returns true if each field present in `reference` is present and equal in `evidence`.
+/// Class IDs expressed using either form of `tagged-int-type` are compared by value.
+pub fn class_matches(reference: &ClassMap, evidence: &ClassMap) -> bool {
+    if let Some(rid) = &reference.id {
+        match &evidence.id {
+            Some(eid) => {
+                if !class_id_matches(rid, eid) {
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+    (reference.vendor.is_none() || reference.vendor == evidence.vendor)
+        && (reference.model.is_none() || reference.model == evidence.model)
+        && (reference.layer.is_none() || reference.layer == evidence.layer)
+        && (reference.index.is_none() || reference.index == evidence.index)
+}
+
+fn class_id_matches(reference: &ClassIdTypeChoice, evidence: &ClassIdTypeChoice) -> bool {
+    match (reference, evidence) {
+        (
+            ClassIdTypeChoice::int(r) | ClassIdTypeChoice::legacy_int(r),
+            ClassIdTypeChoice::int(e) | ClassIdTypeChoice::legacy_int(e),
+        ) => match (r.to_int(), e.to_int()) {
+            (Ok(ri), Ok(ei)) => ri == ei,
+            _ => r == e,
+        },
+        _ => reference == evidence,
+    }
+}
+
+/// Compares each field present in `reference` to the corresponding field in `evidence`. Returns an
+/// error describing the first field that is absent from or does not match the evidence.
+pub fn compare_measurement_values(
+    reference: &MeasurementValuesMap,
+    evidence: &MeasurementValuesMap,
+) -> Result<(), String> {
+    if let Some(rv) = &reference.version {
+        match &evidence.version {
+            Some(ev) => {
+                let equal = match rv.compare(ev) {
+                    Ok(o) => o == Ordering::Equal,
+                    Err(_) => rv == ev,
+                };
+                if !equal {
+                    return Err(format!(
+                        "version mismatch: expected {}, found {}",
+                        rv.version, ev.version
+                    ));
+                }
+            }
+            None => return Err("version absent from evidence".to_string()),
+        }
+    }
+    if let Some(rs) = &reference.svn {
+        let es = match &evidence.svn {
+            Some(SvnTypeChoice::TaggedSvn(s)) => s.0,
+            Some(SvnTypeChoice::TaggedMinSvn(_)) => {
+                return Err("min-svn is not valid in evidence".to_string())
+            }
+            None => return Err("svn absent from evidence".to_string()),
+        };
+        match rs {
+            SvnTypeChoice::TaggedSvn(s) => {
+                if s.0 != es {
+                    return Err(format!("svn mismatch: expected {}, found {}", s.0, es));
+                }
+            }
+            SvnTypeChoice::TaggedMinSvn(s) => {
+                if es < s.0 {
+                    return Err(format!("svn {} is less than min-svn {}", es, s.0));
+                }
+            }
+        }
+    }
+    if let Some(rd) = &reference.digests {
+        match &evidence.digests {
+            Some(ed) => compare_digests(rd, ed)?,
+            None => return Err("digests absent from evidence".to_string()),
+        }
+    }
+    if reference.raw_value.is_some() {
+        match &evidence.raw_value {
+            Some(er) => {
+                if !reference.raw_value_matches(er.value())? {
+                    return Err("raw-value mismatch".to_string());
+                }
+            }
+            None => return Err("raw-value absent from evidence".to_string()),
+        }
+    }
+    if let Some(rf) = &reference.flags {
+        match &evidence.flags {
+            Some(ef) => rf.to_flags_map().compare(&ef.to_flags_map())?,
+            None => return Err("flags absent from evidence".to_string()),
+        }
+    }
+    if reference.mac_addr.is_some() && reference.mac_addr != evidence.mac_addr {
+        return Err("mac-addr mismatch".to_string());
+    }
+    if reference.ip_addr.is_some() && reference.ip_addr != evidence.ip_addr {
+        return Err("ip-addr mismatch".to_string());
+    }
+    if reference.serial_number.is_some() && reference.serial_number != evidence.serial_number {
+        return Err("serial-number mismatch".to_string());
+    }
+    if reference.ueid.is_some() && reference.ueid != evidence.ueid {
+        return Err("ueid mismatch".to_string());
+    }
+    if reference.uuid.is_some() && reference.uuid != evidence.uuid {
+        return Err("uuid mismatch".to_string());
+    }
+    if reference.name.is_some() && reference.name != evidence.name {
+        return Err("name mismatch".to_string());
+    }
+    if let Some(ro) = &reference.other {
+        for r in ro {
+            let found = match &evidence.other {
+                Some(eo) => eo.iter().any(|e| e == r),
+                None => false,
+            };
+            if !found {
+                return Err(format!("extension {:?} mismatch", r.key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Digests match when the reference and evidence have at least one algorithm in common and, for
+/// each algorithm in common, the evidence value equals a reference value.
+fn compare_digests(reference: &[HashEntry], evidence: &[HashEntry]) -> Result<(), String> {
+    let mut common_alg = false;
+    for e in evidence {
+        let refs: Vec<&HashEntry> = reference
+            .iter()
+            .filter(|r| r.hash_alg_id == e.hash_alg_id)
+            .collect();
+        if refs.is_empty() {
+            continue;
+        }
+        common_alg = true;
+        if !refs.iter().any(|r| r.hash_value == e.hash_value) {
+            return Err(format!("digest mismatch for algorithm {}", e.hash_alg_id));
+        }
+    }
+    if common_alg {
+        Ok(())
+    } else {
+        Err("no digest algorithm in common with evidence".to_string())
+    }
+}
@@ -15,8 +15,8 @@ use alloc::string::{String, ToString};
 use cbor_derive::StructToArray;
 use common::TextOrBinary;
 
-// todo - switch to CryptoKeyTypeChoice when corim repo catches up to the spec
-/// The `attest-key-triple-record` type is defined in [CoRIM Section 3.1.4.5].
+/// The `attest-key-triple-record` type is defined in [CoRIM Section 3.1.4.5]. Keys expressed as a
+/// `verification-key-map`, as in draft-birkholz-rats-corim-03 inputs, are also accepted.
 ///
 /// ```text
 /// attest-key-triple-record = [
@@ -32,8 +32,64 @@ pub struct AttestKeyTripleRecord {
     #[cbor(value = "Map", cbor = "true")]
     pub environment_map: EnvironmentMap,
     #[cbor(value = "Array", cbor = "true")]
-    pub crypto_keys: Vec<VerificationKeyMap>,
-    // pub crypto_keys: Option<Vec<CryptoKeyTypeChoice>>,
+    pub crypto_keys: Vec<VerificationKeyTypeChoice>,
+}
+
+/// The `conditional-endorsement-series-triple-record` type is defined in the current [CoRIM spec].
+///
+/// ```text
+/// conditional-endorsement-series-triple-record = [
+///   condition: stateful-environment-record
+///   series: [ + conditional-series-record ]
+/// ]
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, StructToArray, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ConditionalEndorsementSeriesTripleRecord {
+    #[cbor(cbor = "true")]
+    pub condition: StatefulEnvironmentRecord,
+    #[cbor(value = "Array", cbor = "true")]
+    pub series: Vec<ConditionalSeriesRecord>,
+}
+
+/// The `conditional-endorsement-triple-record` type is defined in the current [CoRIM spec].
+///
+/// ```text
+/// conditional-endorsement-triple-record = [
+///   conditions: [ + stateful-environment-record ]
+///   endorsements: [ + endorsed-triple-record ]
+/// ]
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, StructToArray, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ConditionalEndorsementTripleRecord {
+    #[cbor(value = "Array", cbor = "true")]
+    pub conditions: Vec<StatefulEnvironmentRecord>,
+    #[cbor(value = "Array", cbor = "true")]
+    pub endorsements: Vec<EndorsedTripleRecord>,
+}
+
+/// The `conditional-series-record` type is defined in the current [CoRIM spec].
+///
+/// ```text
+/// conditional-series-record = [
+///   selection: [ + measurement-map ]
+///   addition: [ + measurement-map ]
+/// ]
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, StructToArray, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct ConditionalSeriesRecord {
+    #[cbor(value = "Array", cbor = "true")]
+    pub selection: Vec<MeasurementMap>,
+    #[cbor(value = "Array", cbor = "true")]
+    pub addition: Vec<MeasurementMap>,
 }
 
 /// The `coswid-triple-record` type is defined in [CoRIM Section 3.1.4.8].
@@ -55,7 +111,8 @@ pub struct CoswidTripleRecord {
     pub coswid_tags: Vec<TextOrBinary>,
 }
 
-/// The `domain-dependency-triple-record` type is defined in [CoRIM Section 3.1.4.6].
+/// The `domain-dependency-triple-record` type is defined in [CoRIM Section 3.1.4.6]. It is also used
+/// for `domain-membership-triple-record`, which has the same structure.
 ///
 /// ```text
 /// domain-dependency-triple-record = [
@@ -68,8 +125,9 @@ pub struct CoswidTripleRecord {
 #[derive(Clone, Debug, PartialEq, StructToArray, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct DomainDependencyTripleRecord {
+    #[cbor(cbor = "true")]
     pub domain_type_choice: DomainTypeChoice,
-    #[cbor(value = "Array")]
+    #[cbor(value = "Array", cbor = "true")]
     pub domain_type_choices: Vec<DomainTypeChoice>,
 }
 
@@ -92,8 +150,8 @@ pub struct EndorsedTripleRecord {
     pub measurement_map: Vec<MeasurementMap>,
 }
 
-// todo - switch to CryptoKeyTypeChoice when corim repo catches up to the spec
-/// The `identity-triple-record` type is defined in [CoRIM Section 3.1.4.4].
+/// The `identity-triple-record` type is defined in [CoRIM Section 3.1.4.4]. Keys expressed as a
+/// `verification-key-map`, as in draft-birkholz-rats-corim-03 inputs, are also accepted.
 ///
 /// ```text
 /// identity-triple-record = [
@@ -109,8 +167,7 @@ pub struct IdentityTripleRecord {
     #[cbor(value = "Map", cbor = "true")]
     pub environment_map: EnvironmentMap,
     #[cbor(value = "Array", cbor = "true")]
-    pub crypto_keys: Vec<VerificationKeyMap>,
-    // pub crypto_keys: Vec<CryptoKeyTypeChoice>,
+    pub crypto_keys: Vec<VerificationKeyTypeChoice>,
 }
 
 /// The `reference-triple-record` type is defined in [CoRIM Section 3.1.4.2].
@@ -133,3 +190,22 @@ pub struct ReferenceTripleRecord {
     #[serde(rename = "measurements")]
     pub measurement_map: Vec<MeasurementMap>,
 }
+
+/// The `stateful-environment-record` type is defined in the current [CoRIM spec].
+///
+/// ```text
+/// stateful-environment-record = [
+///   environment: environment-map
+///   claims-list: [ + measurement-map ]
+/// ]
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, StructToArray, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct StatefulEnvironmentRecord {
+    #[cbor(value = "Map", cbor = "true")]
+    pub environment: EnvironmentMap,
+    #[cbor(value = "Array", cbor = "true")]
+    pub claims_list: Vec<MeasurementMap>,
+}
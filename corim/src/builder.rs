@@ -0,0 +1,806 @@
+//! Builders for CoMID and CoRIM structures
+//!
+//! The builders assemble the JSON-friendly structs (i.e., [ConciseMidTag]) and enforce required
+//! fields and non-empty constraints when `build` is called. Each builder also offers a
+//! `build_cbor` function that yields the corresponding `...Cbor` struct.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use ciborium::ser::into_writer;
+use ciborium::tag::Required;
+
+use crate::arrays::*;
+use crate::choices::*;
+use crate::maps::*;
+use crate::TaggedBytes;
+use common::arrays::HashEntry;
+use common::choices::VersionScheme;
+use common::*;
+use coswid::maps::ConciseSwidTag;
+
+/// Returns a random (version 4) UUID suitable for use as a tag ID or CoRIM ID.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn generate_uuid() -> UuidType {
+    UuidType::Uuid(uuid::Uuid::new_v4().as_bytes().to_vec())
+}
+
+/// Builds an [EnvironmentMap]. At least one of class, instance or group is required.
+#[derive(Clone, Debug, Default)]
+pub struct EnvironmentMapBuilder {
+    class: Option<ClassMap>,
+    instance: Option<InstanceIdTypeChoice>,
+    group: Option<GroupIdTypeChoice>,
+}
+
+impl EnvironmentMapBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn class_map(&mut self) -> &mut ClassMap {
+        self.class.get_or_insert(ClassMap {
+            id: None,
+            vendor: None,
+            model: None,
+            layer: None,
+            index: None,
+        })
+    }
+
+    /// Sets the class, replacing any class fields set previously.
+    pub fn class(mut self, class: ClassMap) -> Self {
+        self.class = Some(class);
+        self
+    }
+
+    /// Sets the class ID.
+    pub fn class_id(mut self, id: ClassIdTypeChoice) -> Self {
+        self.class_map().id = Some(id);
+        self
+    }
+
+    /// Sets the class vendor.
+    pub fn vendor(mut self, vendor: &str) -> Self {
+        self.class_map().vendor = Some(vendor.to_string());
+        self
+    }
+
+    /// Sets the class model.
+    pub fn model(mut self, model: &str) -> Self {
+        self.class_map().model = Some(model.to_string());
+        self
+    }
+
+    /// Sets the class layer.
+    pub fn layer(mut self, layer: u128) -> Self {
+        self.class_map().layer = Some(layer);
+        self
+    }
+
+    /// Sets the class index.
+    pub fn index(mut self, index: u128) -> Self {
+        self.class_map().index = Some(index);
+        self
+    }
+
+    /// Sets the instance ID.
+    pub fn instance(mut self, instance: InstanceIdTypeChoice) -> Self {
+        self.instance = Some(instance);
+        self
+    }
+
+    /// Sets the instance ID to the given UEID.
+    pub fn instance_ueid(self, ueid: &[u8]) -> Self {
+        self.instance(InstanceIdTypeChoice::Ueid(Required(UeidType::Ueid(
+            ueid.to_vec(),
+        ))))
+    }
+
+    /// Sets the group ID.
+    pub fn group(mut self, group: GroupIdTypeChoice) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    /// Returns the environment or an error if the environment or class would be empty.
+    pub fn build(self) -> Result<EnvironmentMap, String> {
+        if let Some(c) = &self.class {
+            if c.id.is_none()
+                && c.vendor.is_none()
+                && c.model.is_none()
+                && c.layer.is_none()
+                && c.index.is_none()
+            {
+                return Err("class-map must not be empty".to_string());
+            }
+        }
+        if self.class.is_none() && self.instance.is_none() && self.group.is_none() {
+            return Err("environment-map must not be empty".to_string());
+        }
+        Ok(EnvironmentMap {
+            class: self.class,
+            instance: self.instance,
+            group: self.group,
+        })
+    }
+
+    /// Returns the environment as an [EnvironmentMapCbor].
+    pub fn build_cbor(self) -> Result<EnvironmentMapCbor, String> {
+        EnvironmentMapCbor::try_from(self.build()?)
+    }
+}
+
+/// Builds a [MeasurementMap]. At least one measurement value is required.
+#[derive(Clone, Debug)]
+pub struct MeasurementMapBuilder {
+    mkey: Option<MeasuredElementTypeChoice>,
+    value: MeasurementValuesMap,
+}
+
+impl Default for MeasurementMapBuilder {
+    fn default() -> Self {
+        MeasurementMapBuilder {
+            mkey: None,
+            value: MeasurementValuesMap {
+                version: None,
+                svn: None,
+                digests: None,
+                flags: None,
+                raw_value: None,
+                raw_value_mask: None,
+                mac_addr: None,
+                ip_addr: None,
+                serial_number: None,
+                ueid: None,
+                uuid: None,
+                name: None,
+                cryptokeys: None,
+                integrity_registers: None,
+                other: None,
+            },
+        }
+    }
+}
+
+impl MeasurementMapBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the measured element identifier.
+    pub fn mkey(mut self, mkey: MeasuredElementTypeChoice) -> Self {
+        self.mkey = Some(mkey);
+        self
+    }
+
+    /// Sets the version and, optionally, the version scheme.
+    pub fn version(mut self, version: &str, version_scheme: Option<VersionScheme>) -> Self {
+        self.value.version = Some(VersionMap {
+            version: version.to_string(),
+            version_scheme,
+        });
+        self
+    }
+
+    /// Sets the security version number.
+    pub fn svn(mut self, svn: u64) -> Self {
+        self.value.svn = Some(SvnTypeChoice::TaggedSvn(Required(svn)));
+        self
+    }
+
+    /// Sets the minimum security version number.
+    pub fn min_svn(mut self, min_svn: u64) -> Self {
+        self.value.svn = Some(SvnTypeChoice::TaggedMinSvn(Required(min_svn)));
+        self
+    }
+
+    /// Adds a digest.
+    pub fn digest(mut self, digest: HashEntry) -> Self {
+        self.value.digests.get_or_insert_with(Vec::new).push(digest);
+        self
+    }
+
+    /// Sets the operational flags.
+    pub fn flags(mut self, flags: FlagsMap) -> Self {
+        self.value.flags = Some(FlagsTypeChoice::Map(flags));
+        self
+    }
+
+    /// Sets the raw value.
+    pub fn raw_value(mut self, raw_value: &[u8]) -> Self {
+        self.value.raw_value = Some(RawValueTypeChoice::Bytes(TaggedBytes {
+            0: BytesType::Bytes(raw_value.to_vec()),
+        }));
+        self
+    }
+
+    /// Sets the raw value mask. A raw value is required when a mask is present.
+    pub fn raw_value_mask(mut self, mask: &[u8]) -> Self {
+        self.value.raw_value_mask = Some(mask.to_vec());
+        self
+    }
+
+    /// Sets the MAC address.
+    pub fn mac_addr(mut self, mac_addr: &[u8]) -> Self {
+        self.value.mac_addr = Some(mac_addr.to_vec());
+        self
+    }
+
+    /// Sets the IP address.
+    pub fn ip_addr(mut self, ip_addr: &[u8]) -> Self {
+        self.value.ip_addr = Some(ip_addr.to_vec());
+        self
+    }
+
+    /// Sets the serial number.
+    pub fn serial_number(mut self, serial_number: &str) -> Self {
+        self.value.serial_number = Some(serial_number.to_string());
+        self
+    }
+
+    /// Sets the UEID.
+    pub fn ueid(mut self, ueid: &[u8]) -> Self {
+        self.value.ueid = Some(UeidType::Ueid(ueid.to_vec()));
+        self
+    }
+
+    /// Sets the UUID.
+    pub fn uuid(mut self, uuid: &[u8]) -> Self {
+        self.value.uuid = Some(UuidType::Uuid(uuid.to_vec()));
+        self
+    }
+
+    /// Sets the name.
+    pub fn name(mut self, name: &str) -> Self {
+        self.value.name = Some(name.to_string());
+        self
+    }
+
+    /// Adds a cryptographic key.
+    pub fn cryptokey(mut self, key: CryptoKeyTypeChoice) -> Self {
+        self.value.cryptokeys.get_or_insert_with(Vec::new).push(key);
+        self
+    }
+
+    /// Adds a digest to the given integrity register.
+    pub fn integrity_register(
+        mut self,
+        id: IntegrityRegisterIdTypeChoice,
+        digest: HashEntry,
+    ) -> Self {
+        self.value
+            .integrity_registers
+            .get_or_insert_with(IntegrityRegisters::default)
+            .0
+            .entry(id)
+            .or_default()
+            .push(digest);
+        self
+    }
+
+    /// Adds an extension.
+    pub fn extension(mut self, extension: Tuple) -> Self {
+        self.value
+            .other
+            .get_or_insert_with(Vec::new)
+            .push(extension);
+        self
+    }
+
+    /// Returns the measurement or an error if no measurement values were set or if a raw value mask
+    /// was set without a raw value.
+    pub fn build(self) -> Result<MeasurementMap, String> {
+        let v = &self.value;
+        if v.raw_value_mask.is_some() && v.raw_value.is_none() {
+            return Err("raw-value-mask requires raw-value".to_string());
+        }
+        if v.version.is_none()
+            && v.svn.is_none()
+            && v.digests.is_none()
+            && v.flags.is_none()
+            && v.raw_value.is_none()
+            && v.mac_addr.is_none()
+            && v.ip_addr.is_none()
+            && v.serial_number.is_none()
+            && v.ueid.is_none()
+            && v.uuid.is_none()
+            && v.name.is_none()
+            && v.cryptokeys.is_none()
+            && v.integrity_registers.is_none()
+            && v.other.is_none()
+        {
+            return Err("measurement-values-map must not be empty".to_string());
+        }
+        Ok(MeasurementMap {
+            mkey: self.mkey,
+            value: self.value,
+        })
+    }
+
+    /// Returns the measurement as a [MeasurementMapCbor].
+    pub fn build_cbor(self) -> Result<MeasurementMapCbor, String> {
+        MeasurementMapCbor::try_from(self.build()?)
+    }
+}
+
+/// Builds a [TriplesMap]. At least one triple is required and each triple must contain at least
+/// one measurement, key or other target as appropriate.
+#[derive(Clone, Debug)]
+pub struct TriplesMapBuilder {
+    triples: TriplesMap,
+}
+
+impl Default for TriplesMapBuilder {
+    fn default() -> Self {
+        TriplesMapBuilder {
+            triples: TriplesMap {
+                reference_triples: None,
+                endorsed_triples: None,
+                identity_triples: None,
+                attest_key_triples: None,
+                dependency_triples: None,
+                membership_triples: None,
+                coswid_triples: None,
+                conditional_endorsement_series_triples: None,
+                conditional_endorsement_triples: None,
+                other: None,
+            },
+        }
+    }
+}
+
+fn non_empty<T>(v: &[T], name: &str) -> Result<(), String> {
+    if v.is_empty() {
+        Err(format!("{} must not be empty", name))
+    } else {
+        Ok(())
+    }
+}
+
+impl TriplesMapBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a reference value triple.
+    pub fn reference_value(
+        mut self,
+        env: EnvironmentMap,
+        measurements: Vec<MeasurementMap>,
+    ) -> Self {
+        self.triples
+            .reference_triples
+            .get_or_insert_with(Vec::new)
+            .push(ReferenceTripleRecord {
+                environment_map: env,
+                measurement_map: measurements,
+            });
+        self
+    }
+
+    /// Adds an endorsed value triple.
+    pub fn endorsed_value(
+        mut self,
+        env: EnvironmentMap,
+        measurements: Vec<MeasurementMap>,
+    ) -> Self {
+        self.triples
+            .endorsed_triples
+            .get_or_insert_with(Vec::new)
+            .push(EndorsedTripleRecord {
+                environment_map: env,
+                measurement_map: measurements,
+            });
+        self
+    }
+
+    /// Adds an identity triple.
+    pub fn identity_keys(mut self, env: EnvironmentMap, keys: Vec<CryptoKeyTypeChoice>) -> Self {
+        self.triples
+            .identity_triples
+            .get_or_insert_with(Vec::new)
+            .push(IdentityTripleRecord {
+                environment_map: env,
+                crypto_keys: keys
+                    .into_iter()
+                    .map(VerificationKeyTypeChoice::Key)
+                    .collect(),
+            });
+        self
+    }
+
+    /// Adds an attest key triple.
+    pub fn attest_keys(mut self, env: EnvironmentMap, keys: Vec<CryptoKeyTypeChoice>) -> Self {
+        self.triples
+            .attest_key_triples
+            .get_or_insert_with(Vec::new)
+            .push(AttestKeyTripleRecord {
+                environment_map: env,
+                crypto_keys: keys
+                    .into_iter()
+                    .map(VerificationKeyTypeChoice::Key)
+                    .collect(),
+            });
+        self
+    }
+
+    /// Adds a domain dependency triple.
+    pub fn dependency(mut self, domain: DomainTypeChoice, trustees: Vec<DomainTypeChoice>) -> Self {
+        self.triples
+            .dependency_triples
+            .get_or_insert_with(Vec::new)
+            .push(DomainDependencyTripleRecord {
+                domain_type_choice: domain,
+                domain_type_choices: trustees,
+            });
+        self
+    }
+
+    /// Adds a domain membership triple.
+    pub fn membership(mut self, domain: DomainTypeChoice, members: Vec<DomainTypeChoice>) -> Self {
+        self.triples
+            .membership_triples
+            .get_or_insert_with(Vec::new)
+            .push(DomainDependencyTripleRecord {
+                domain_type_choice: domain,
+                domain_type_choices: members,
+            });
+        self
+    }
+
+    /// Adds a CoSWID triple.
+    pub fn coswid(mut self, env: EnvironmentMap, tags: Vec<TextOrBinary>) -> Self {
+        self.triples
+            .coswid_triples
+            .get_or_insert_with(Vec::new)
+            .push(CoswidTripleRecord {
+                environment_map: env,
+                coswid_tags: tags,
+            });
+        self
+    }
+
+    /// Adds a conditional endorsement triple.
+    pub fn conditional_endorsement(
+        mut self,
+        conditions: Vec<StatefulEnvironmentRecord>,
+        endorsements: Vec<EndorsedTripleRecord>,
+    ) -> Self {
+        self.triples
+            .conditional_endorsement_triples
+            .get_or_insert_with(Vec::new)
+            .push(ConditionalEndorsementTripleRecord {
+                conditions,
+                endorsements,
+            });
+        self
+    }
+
+    /// Adds a conditional endorsement series triple.
+    pub fn conditional_endorsement_series(
+        mut self,
+        condition: StatefulEnvironmentRecord,
+        series: Vec<ConditionalSeriesRecord>,
+    ) -> Self {
+        self.triples
+            .conditional_endorsement_series_triples
+            .get_or_insert_with(Vec::new)
+            .push(ConditionalEndorsementSeriesTripleRecord { condition, series });
+        self
+    }
+
+    /// Adds an extension.
+    pub fn extension(mut self, extension: Tuple) -> Self {
+        self.triples
+            .other
+            .get_or_insert_with(Vec::new)
+            .push(extension);
+        self
+    }
+
+    /// Returns the triples or an error if no triples were added or if a triple is missing its
+    /// measurements, keys or other targets. Extensions alone do not make a valid `triples-map`.
+    pub fn build(self) -> Result<TriplesMap, String> {
+        let t = &self.triples;
+        let mut empty = true;
+        if let Some(v) = &t.reference_triples {
+            for r in v {
+                non_empty(&r.measurement_map, "reference-triple-record measurements")?;
+            }
+            empty = false;
+        }
+        if let Some(v) = &t.endorsed_triples {
+            for r in v {
+                non_empty(&r.measurement_map, "endorsed-triple-record measurements")?;
+            }
+            empty = false;
+        }
+        if let Some(v) = &t.identity_triples {
+            for r in v {
+                non_empty(&r.crypto_keys, "identity-triple-record keys")?;
+            }
+            empty = false;
+        }
+        if let Some(v) = &t.attest_key_triples {
+            for r in v {
+                non_empty(&r.crypto_keys, "attest-key-triple-record keys")?;
+            }
+            empty = false;
+        }
+        for v in [&t.dependency_triples, &t.membership_triples]
+            .into_iter()
+            .flatten()
+        {
+            for r in v {
+                non_empty(&r.domain_type_choices, "domain triple-record targets")?;
+            }
+            empty = false;
+        }
+        if let Some(v) = &t.coswid_triples {
+            for r in v {
+                non_empty(&r.coswid_tags, "coswid-triple-record tags")?;
+            }
+            empty = false;
+        }
+        if let Some(v) = &t.conditional_endorsement_triples {
+            for r in v {
+                non_empty(
+                    &r.conditions,
+                    "conditional-endorsement-triple-record conditions",
+                )?;
+                non_empty(
+                    &r.endorsements,
+                    "conditional-endorsement-triple-record endorsements",
+                )?;
+            }
+            empty = false;
+        }
+        if let Some(v) = &t.conditional_endorsement_series_triples {
+            for r in v {
+                non_empty(
+                    &r.series,
+                    "conditional-endorsement-series-triple-record series",
+                )?;
+            }
+            empty = false;
+        }
+        if empty {
+            return Err("triples-map must contain at least one triple".to_string());
+        }
+        Ok(self.triples)
+    }
+
+    /// Returns the triples as a [TriplesMapCbor].
+    pub fn build_cbor(self) -> Result<TriplesMapCbor, String> {
+        TriplesMapCbor::try_from(self.build()?)
+    }
+}
+
+/// Builds a [ConciseMidTag]. A tag identity and triples are required.
+#[derive(Clone, Debug, Default)]
+pub struct ConciseMidTagBuilder {
+    language: Option<String>,
+    tag_id: Option<TagIdTypeChoice>,
+    tag_version: Option<u64>,
+    entities: Option<Vec<EntityMap>>,
+    linked_tags: Option<Vec<LinkedTagMap>>,
+    triples: Option<TriplesMap>,
+    other: Option<Vec<Tuple>>,
+}
+
+impl ConciseMidTagBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the language.
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Sets the tag ID.
+    pub fn tag_id(mut self, tag_id: TagIdTypeChoice) -> Self {
+        self.tag_id = Some(tag_id);
+        self
+    }
+
+    /// Sets the tag ID to a random UUID.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn generate_tag_id(self) -> Self {
+        self.tag_id(TagIdTypeChoice::Uuid(generate_uuid()))
+    }
+
+    /// Sets the tag version.
+    pub fn tag_version(mut self, tag_version: u64) -> Self {
+        self.tag_version = Some(tag_version);
+        self
+    }
+
+    /// Adds an entity.
+    pub fn entity(mut self, entity: EntityMap) -> Self {
+        self.entities.get_or_insert_with(Vec::new).push(entity);
+        self
+    }
+
+    /// Adds a linked tag.
+    pub fn linked_tag(mut self, linked_tag: LinkedTagMap) -> Self {
+        self.linked_tags
+            .get_or_insert_with(Vec::new)
+            .push(linked_tag);
+        self
+    }
+
+    /// Sets the triples.
+    pub fn triples(mut self, triples: TriplesMap) -> Self {
+        self.triples = Some(triples);
+        self
+    }
+
+    /// Adds an extension.
+    pub fn extension(mut self, extension: Tuple) -> Self {
+        self.other.get_or_insert_with(Vec::new).push(extension);
+        self
+    }
+
+    /// Returns the CoMID or an error if the tag ID or triples are absent.
+    pub fn build(self) -> Result<ConciseMidTag, String> {
+        let tag_id = match self.tag_id {
+            Some(t) => t,
+            None => return Err("concise-mid-tag requires a tag-id".to_string()),
+        };
+        let triples = match self.triples {
+            Some(t) => t,
+            None => return Err("concise-mid-tag requires triples".to_string()),
+        };
+        Ok(ConciseMidTag {
+            language: self.language,
+            tag_identity: Some(TagIdentityMap {
+                tag_id,
+                tag_version: self.tag_version.map(TagVersionType::U64),
+            }),
+            entities: self.entities,
+            linked_tags: self.linked_tags,
+            triples,
+            other: self.other,
+        })
+    }
+
+    /// Returns the CoMID as a [ConciseMidTagCbor].
+    pub fn build_cbor(self) -> Result<ConciseMidTagCbor, String> {
+        ConciseMidTagCbor::try_from(self.build()?)
+    }
+}
+
+/// Builds a [CorimMap]. An ID and at least one tag are required.
+#[derive(Clone, Debug, Default)]
+pub struct CorimMapBuilder {
+    id: Option<CorimIdTypeChoice>,
+    tags: Vec<ConciseTagTypeChoice>,
+    dependent_rims: Option<Vec<CorimLocatorMap>>,
+    profile: Option<Vec<ProfileTypeChoice>>,
+    rim_validity: Option<ValidityMap>,
+    entities: Option<Vec<EntityMap>>,
+    error: Option<String>,
+}
+
+impl CorimMapBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the CoRIM ID.
+    pub fn id(mut self, id: CorimIdTypeChoice) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the CoRIM ID to a random UUID.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn generate_id(self) -> Self {
+        self.id(CorimIdTypeChoice::Uuid(generate_uuid()))
+    }
+
+    /// Adds an encoded tag, i.e., a #6.506-tagged CoMID or #6.505-tagged CoSWID.
+    pub fn tag(mut self, tag: ConciseTagTypeChoice) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Encodes `comid` as a #6.506-tagged CoMID and adds it to the tags.
+    pub fn comid(mut self, comid: &ConciseMidTag) -> Self {
+        match ConciseMidTagCbor::try_from(comid) {
+            Ok(c) => {
+                let mut encoded = vec![];
+                match into_writer(&TaggedComidCbor(Required(c)), &mut encoded) {
+                    Ok(()) => self.tags.push(BytesType::Bytes(encoded)),
+                    Err(e) => self.set_error(format!("Failed to encode CoMID: {:?}", e)),
+                }
+            }
+            Err(e) => self.set_error(e),
+        }
+        self
+    }
+
+    /// Encodes `coswid` as a #6.505-tagged CoSWID and adds it to the tags.
+    pub fn coswid(mut self, coswid: &ConciseSwidTag) -> Self {
+        match coswid::maps::ConciseSwidTagCbor::try_from(coswid) {
+            Ok(c) => {
+                let mut encoded = vec![];
+                match into_writer(&TaggedCoswidCbor(Required(c)), &mut encoded) {
+                    Ok(()) => self.tags.push(BytesType::Bytes(encoded)),
+                    Err(e) => self.set_error(format!("Failed to encode CoSWID: {:?}", e)),
+                }
+            }
+            Err(e) => self.set_error(e),
+        }
+        self
+    }
+
+    fn set_error(&mut self, e: String) {
+        if self.error.is_none() {
+            self.error = Some(e);
+        }
+    }
+
+    /// Adds a dependent RIM locator.
+    pub fn dependent_rim(mut self, locator: CorimLocatorMap) -> Self {
+        self.dependent_rims
+            .get_or_insert_with(Vec::new)
+            .push(locator);
+        self
+    }
+
+    /// Adds a profile.
+    pub fn profile(mut self, profile: ProfileTypeChoice) -> Self {
+        self.profile.get_or_insert_with(Vec::new).push(profile);
+        self
+    }
+
+    /// Sets the validity period.
+    pub fn validity(mut self, validity: ValidityMap) -> Self {
+        self.rim_validity = Some(validity);
+        self
+    }
+
+    /// Adds an entity.
+    pub fn entity(mut self, entity: EntityMap) -> Self {
+        self.entities.get_or_insert_with(Vec::new).push(entity);
+        self
+    }
+
+    /// Returns the CoRIM or an error if the ID is absent, no tags were added or a tag could not
+    /// be encoded.
+    pub fn build(self) -> Result<CorimMap, String> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let id = match self.id {
+            Some(id) => id,
+            None => return Err("corim-map requires an id".to_string()),
+        };
+        non_empty(&self.tags, "corim-map tags")?;
+        Ok(CorimMap {
+            id,
+            tags: self.tags,
+            dependent_rims: self.dependent_rims,
+            profile: self.profile,
+            rim_validity: self.rim_validity,
+            entities: self.entities,
+        })
+    }
+
+    /// Returns the CoRIM as a [CorimMapCbor].
+    pub fn build_cbor(self) -> Result<CorimMapCbor, String> {
+        CorimMapCbor::try_from(self.build()?)
+    }
+}
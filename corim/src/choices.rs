@@ -3,6 +3,7 @@
 use alloc::format;
 use alloc::string::{String, ToString};
 use ciborium::value::{Integer, Value};
+use common::arrays::HashEntryCbor;
 use common::*;
 use serde::__private::de::Content;
 use serde::{Deserialize, Serialize};
@@ -12,6 +13,7 @@ use serde_repr::Serialize_repr;
 use ciborium::tag::Required;
 
 use crate::maps::*;
+use crate::*;
 use coswid::maps::*;
 
 /// $concise-tag-type-choice /= #6.505(bytes .cbor concise-swid-tag)
@@ -223,6 +225,9 @@ pub type ConciseTagTypeChoice = BytesType;
 /// $class-id-type-choice /= tagged-int-type
 /// ```
 ///
+/// The `legacy_int` variant corresponds to the #6.600 form emitted by the Veraison corim
+/// implementation (see [TaggedIntMode]).
+///
 /// [CoRIM Section 3.1.4.1.2]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.2
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[allow(missing_docs)]
@@ -232,6 +237,7 @@ pub enum ClassIdTypeChoice {
     oid(OidType),
     uuid(UuidType),
     int(IntType),
+    legacy_int(IntType),
 }
 
 impl TryFrom<ClassIdTypeChoiceCbor> for ClassIdTypeChoice {
@@ -243,7 +249,7 @@ impl TryFrom<ClassIdTypeChoiceCbor> for ClassIdTypeChoice {
                 UuidType::Uuid(v) => common::UuidType::Uuid(v.clone()),
             })),
             ClassIdTypeChoiceCbor::Int(b) => Ok(Self::int(b.0)),
-            ClassIdTypeChoiceCbor::Int2(b) => Ok(Self::int(b.0)),
+            ClassIdTypeChoiceCbor::Int2(b) => Ok(Self::legacy_int(b.0)),
         }
     }
 }
@@ -256,7 +262,7 @@ impl TryFrom<&ClassIdTypeChoiceCbor> for ClassIdTypeChoice {
                 UuidType::Uuid(v) => common::UuidType::Uuid(v.clone()),
             })),
             ClassIdTypeChoiceCbor::Int(b) => Ok(Self::int(b.0.clone())),
-            ClassIdTypeChoiceCbor::Int2(b) => Ok(Self::int(b.0.clone())),
+            ClassIdTypeChoiceCbor::Int2(b) => Ok(Self::legacy_int(b.0.clone())),
         }
     }
 }
@@ -270,6 +276,26 @@ pub enum ClassIdTypeChoiceCbor {
     Int(TaggedIntType),
     Int2(TaggedIntType2),
 }
+impl ClassIdTypeChoiceCbor {
+    /// Returns a copy of this value with any `tagged-int-type` expressed in the form indicated by
+    /// `mode`. Other choices are returned unchanged. An error is returned when the value cannot be
+    /// represented in the requested form (i.e., bytes longer than an int or a negative int).
+    pub fn with_tagged_int_mode(&self, mode: TaggedIntMode) -> Result<Self, String> {
+        let i = match self {
+            ClassIdTypeChoiceCbor::Int(i) => &i.0,
+            ClassIdTypeChoiceCbor::Int2(i) => &i.0,
+            _ => return Ok(self.clone()),
+        };
+        match mode {
+            TaggedIntMode::Spec => Ok(Self::Int(TaggedIntType {
+                0: IntType::Int(i.to_int()?),
+            })),
+            TaggedIntMode::Legacy => Ok(Self::Int2(TaggedIntType2 {
+                0: IntType::Bytes(i.to_bytes()?),
+            })),
+        }
+    }
+}
 impl TryFrom<ClassIdTypeChoice> for ClassIdTypeChoiceCbor {
     type Error = String;
     fn try_from(value: ClassIdTypeChoice) -> Result<Self, Self::Error> {
@@ -281,6 +307,7 @@ impl TryFrom<ClassIdTypeChoice> for ClassIdTypeChoiceCbor {
             })),
             ClassIdTypeChoice::uuid(b) => Ok(Self::Uuid(TaggedUuidType { 0: b })),
             ClassIdTypeChoice::int(b) => Ok(Self::Int(TaggedIntType { 0: b })),
+            ClassIdTypeChoice::legacy_int(b) => Ok(Self::Int2(TaggedIntType2 { 0: b })),
         }
     }
 }
@@ -295,40 +322,14 @@ impl TryFrom<&ClassIdTypeChoice> for ClassIdTypeChoiceCbor {
             })),
             ClassIdTypeChoice::uuid(b) => Ok(Self::Uuid(TaggedUuidType { 0: b.clone() })),
             ClassIdTypeChoice::int(b) => Ok(Self::Int(TaggedIntType { 0: b.clone() })),
+            ClassIdTypeChoice::legacy_int(b) => Ok(Self::Int2(TaggedIntType2 { 0: b.clone() })),
         }
     }
 }
-//todo the cocli tests use tag 600 here
 impl TryFrom<Value> for ClassIdTypeChoiceCbor {
     type Error = String;
     fn try_from(value: Value) -> Result<Self, Self::Error> {
-        match value {
-            Value::Tag(111, b) => Ok(Self::Oid(TaggedOidTypeCbor {
-                0: OidType::Oid(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => return Err("Failed to parse OID value as bytes".to_string()),
-                }),
-            })),
-            Value::Tag(37, b) => Ok(Self::Uuid(TaggedUuidType {
-                0: UuidType::Uuid(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => return Err("Failed to parse UUID value as bytes".to_string()),
-                }),
-            })),
-            Value::Tag(551, b) => Ok(Self::Int(TaggedIntType {
-                0: IntType::Int(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => return Err("Failed to parse int value as bytes".to_string()),
-                }),
-            })),
-            Value::Tag(600, b) => Ok(Self::Int2(TaggedIntType2 {
-                0: IntType::Int(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => return Err("Failed to parse int value as bytes".to_string()),
-                }),
-            })),
-            _ => Err("Failed to parse value as a ClassIdTypeChoiceCbor".to_string()),
-        }
+        ClassIdTypeChoiceCbor::try_from(&value)
     }
 }
 impl TryFrom<&Value> for ClassIdTypeChoiceCbor {
@@ -347,17 +348,12 @@ impl TryFrom<&Value> for ClassIdTypeChoiceCbor {
                     None => return Err("Failed to parse UUID value as bytes".to_string()),
                 }),
             })),
+            // both the int form from the spec and the legacy bytes form are accepted for either tag
             Value::Tag(551, b) => Ok(Self::Int(TaggedIntType {
-                0: IntType::Int(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => return Err("Failed to parse int value as bytes".to_string()),
-                }),
+                0: IntType::try_from(b.as_ref())?,
             })),
             Value::Tag(600, b) => Ok(Self::Int2(TaggedIntType2 {
-                0: IntType::Int(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => return Err("Failed to parse int value as bytes".to_string()),
-                }),
+                0: IntType::try_from(b.as_ref())?,
             })),
             _ => Err("Failed to parse value as a ClassIdTypeChoiceCbor".to_string()),
         }
@@ -637,139 +633,163 @@ pub enum CorimRoleTypeChoiceKnownCbor {
     Maintainer = 2,
 }
 
-/// The `crypto-key-type-choice` socket is defined in [CoRIM Section 3.1.4.1.6].
+/// The `crypto-key-type-choice` socket is defined in [CoRIM Section 3.1.4.1.6]. The choices from
+/// `tagged-key-thumbprint-type` onward were added in later revisions of the spec.
 ///
 /// ```text
 /// $crypto-key-type-choice /= tagged-pkix-base64-key-type
 /// $crypto-key-type-choice /= tagged-pkix-base64-cert-type
 /// $crypto-key-type-choice /= tagged-pkix-base64-cert-path-type
+/// $crypto-key-type-choice /= tagged-key-thumbprint-type
+/// $crypto-key-type-choice /= tagged-cose-key-type
+/// $crypto-key-type-choice /= tagged-cert-thumbprint-type
+/// $crypto-key-type-choice /= tagged-bytes
+/// $crypto-key-type-choice /= tagged-cert-path-thumbprint-type
+/// $crypto-key-type-choice /= tagged-pkix-asn1der-cert-type
 /// ```
 ///
 /// [CoRIM Section 3.1.4.1.6]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.6
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[allow(missing_docs)]
 pub enum CryptoKeyTypeChoice {
     Key(TaggedPkixBase64KeyType),
     Cert(TaggedPkixBase64CertType),
     Path(TaggedPkixBase64CertPathType),
+    KeyThumbprint(TaggedKeyThumbprintType),
+    CoseKey(TaggedCoseKeyType),
+    CertThumbprint(TaggedCertThumbprintType),
+    Bytes(TaggedBytes),
+    CertPathThumbprint(TaggedCertPathThumbprintType),
+    Asn1DerCert(TaggedPkixAsn1DerCertType),
 }
 impl TryFrom<Value> for CryptoKeyTypeChoice {
     type Error = String;
     fn try_from(value: Value) -> Result<Self, Self::Error> {
-        match value {
-            Value::Tag(554, b) => Ok(Self::Key(TaggedPkixBase64KeyType {
-                0: match b.as_text() {
-                    Some(t) => t.to_string(),
-                    None => {
-                        return Err(
-                            "Failed to parse CryptoKeyTypeChoice as a text value".to_string()
-                        )
-                    }
-                },
-            })),
-            Value::Tag(555, b) => Ok(Self::Cert(TaggedPkixBase64CertType {
-                0: match b.as_text() {
-                    Some(t) => t.to_string(),
-                    None => {
-                        return Err(
-                            "Failed to parse CryptoKeyTypeChoice as a text value".to_string()
-                        )
-                    }
-                },
-            })),
-            Value::Tag(556, b) => Ok(Self::Path(TaggedPkixBase64CertPathType {
-                0: match b.as_text() {
-                    Some(t) => t.to_string(),
-                    None => {
-                        return Err(
-                            "Failed to parse CryptoKeyTypeChoice as a text value".to_string()
-                        )
-                    }
-                },
-            })),
-            _ => Err("Failed to parse value as a CryptoKeyTypeChoice".to_string()),
-        }
+        CryptoKeyTypeChoice::try_from(&value)
     }
 }
 impl TryFrom<&Value> for CryptoKeyTypeChoice {
     type Error = String;
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let text = |b: &Value| match b.as_text() {
+            Some(t) => Ok(t.to_string()),
+            None => Err("Failed to parse CryptoKeyTypeChoice as a text value".to_string()),
+        };
+        let bytes = |b: &Value| match b.as_bytes() {
+            Some(b) => Ok(BytesType::Bytes(b.clone())),
+            None => Err("Failed to parse CryptoKeyTypeChoice as a bytes value".to_string()),
+        };
         match value {
-            Value::Tag(554, b) => Ok(Self::Key(TaggedPkixBase64KeyType {
-                0: match b.as_text() {
-                    Some(t) => t.to_string(),
-                    None => {
-                        return Err(
-                            "Failed to parse CryptoKeyTypeChoice as a text value".to_string()
-                        )
-                    }
-                },
+            Value::Tag(554, b) => Ok(Self::Key(TaggedPkixBase64KeyType { 0: text(b)? })),
+            Value::Tag(555, b) => Ok(Self::Cert(TaggedPkixBase64CertType { 0: text(b)? })),
+            Value::Tag(556, b) => Ok(Self::Path(TaggedPkixBase64CertPathType { 0: text(b)? })),
+            Value::Tag(557, b) => Ok(Self::KeyThumbprint(TaggedKeyThumbprintType {
+                0: HashEntryCbor::try_from(b.as_ref())?,
             })),
-            Value::Tag(555, b) => Ok(Self::Cert(TaggedPkixBase64CertType {
-                0: match b.as_text() {
-                    Some(t) => t.to_string(),
-                    None => {
-                        return Err(
-                            "Failed to parse CryptoKeyTypeChoice as a text value".to_string()
-                        )
-                    }
-                },
+            Value::Tag(558, b) => match b.as_ref() {
+                Value::Map(_) => Ok(Self::CoseKey(TaggedCoseKeyType { 0: *b.clone() })),
+                _ => Err("Failed to parse CryptoKeyTypeChoice as a COSE_Key".to_string()),
+            },
+            Value::Tag(559, b) => Ok(Self::CertThumbprint(TaggedCertThumbprintType {
+                0: HashEntryCbor::try_from(b.as_ref())?,
             })),
-            Value::Tag(556, b) => Ok(Self::Path(TaggedPkixBase64CertPathType {
-                0: match b.as_text() {
-                    Some(t) => t.to_string(),
-                    None => {
-                        return Err(
-                            "Failed to parse CryptoKeyTypeChoice as a text value".to_string()
-                        )
-                    }
-                },
+            Value::Tag(560, b) => Ok(Self::Bytes(TaggedBytes { 0: bytes(b)? })),
+            Value::Tag(561, b) => Ok(Self::CertPathThumbprint(TaggedCertPathThumbprintType {
+                0: HashEntryCbor::try_from(b.as_ref())?,
+            })),
+            Value::Tag(562, b) => Ok(Self::Asn1DerCert(TaggedPkixAsn1DerCertType {
+                0: bytes(b)?,
             })),
             _ => Err("Failed to parse value as a CryptoKeyTypeChoice".to_string()),
         }
     }
 }
 
-/// The `domain-type-choice` socket is defined in [CoRIM Section 3.1.4.1.7].
+/// The `domain-type-choice` socket is defined in [CoRIM Section 3.1.4.1.7]. The current [CoRIM spec]
+/// identifies domains using `environment-map`, which is also accepted.
 ///
 /// ```text
 /// $domain-type-choice /= uint
 /// $domain-type-choice /= text
 /// $domain-type-choice /= tagged-uuid-type
+/// $domain-type-choice /= environment-map
 /// ```
 ///
 /// [CoRIM Section 3.1.4.1.7]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.7
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 #[allow(missing_docs)]
 pub enum DomainTypeChoice {
     U64(u64),
     Text(String),
     Uuid(TaggedUuidType),
+    Environment(EnvironmentMap),
+}
+impl TryFrom<DomainTypeChoiceCbor> for DomainTypeChoice {
+    type Error = String;
+    fn try_from(value: DomainTypeChoiceCbor) -> Result<Self, Self::Error> {
+        DomainTypeChoice::try_from(&value)
+    }
+}
+impl TryFrom<&DomainTypeChoiceCbor> for DomainTypeChoice {
+    type Error = String;
+    fn try_from(value: &DomainTypeChoiceCbor) -> Result<Self, Self::Error> {
+        match value {
+            DomainTypeChoiceCbor::U64(u) => Ok(Self::U64(*u)),
+            DomainTypeChoiceCbor::Text(s) => Ok(Self::Text(s.clone())),
+            DomainTypeChoiceCbor::Uuid(u) => Ok(Self::Uuid(u.clone())),
+            DomainTypeChoiceCbor::Environment(e) => Ok(Self::Environment(e.try_into()?)),
+        }
+    }
 }
 impl TryFrom<Value> for DomainTypeChoice {
     type Error = String;
     fn try_from(value: Value) -> Result<Self, Self::Error> {
+        DomainTypeChoice::try_from(&DomainTypeChoiceCbor::try_from(&value)?)
+    }
+}
+impl TryFrom<&Value> for DomainTypeChoice {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        DomainTypeChoice::try_from(&DomainTypeChoiceCbor::try_from(value)?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum DomainTypeChoiceCbor {
+    U64(u64),
+    Text(String),
+    Uuid(TaggedUuidType),
+    Environment(EnvironmentMapCbor),
+}
+impl TryFrom<DomainTypeChoice> for DomainTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: DomainTypeChoice) -> Result<Self, Self::Error> {
+        DomainTypeChoiceCbor::try_from(&value)
+    }
+}
+impl TryFrom<&DomainTypeChoice> for DomainTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: &DomainTypeChoice) -> Result<Self, Self::Error> {
         match value {
-            Value::Integer(i) => Ok(Self::U64(match Integer::try_into(i) {
-                Ok(i) => i,
-                Err(e) => return Err(e.to_string()),
-            })),
-            Value::Text(s) => Ok(Self::Text(s)),
-            Value::Tag(37, b) => Ok(Self::Uuid(TaggedUuidType {
-                0: UuidType::Uuid(match b.as_bytes() {
-                    Some(b) => b.clone(),
-                    None => {
-                        return Err("Failed to parse UUID value as a DomainTypeChoice".to_string())
-                    }
-                }),
-            })),
-            _ => Err("Failed to parse value as a DomainTypeChoice".to_string()),
+            DomainTypeChoice::U64(u) => Ok(Self::U64(*u)),
+            DomainTypeChoice::Text(s) => Ok(Self::Text(s.clone())),
+            DomainTypeChoice::Uuid(u) => Ok(Self::Uuid(u.clone())),
+            DomainTypeChoice::Environment(e) => Ok(Self::Environment(e.try_into()?)),
         }
     }
 }
-impl TryFrom<&Value> for DomainTypeChoice {
+impl TryFrom<Value> for DomainTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        DomainTypeChoiceCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for DomainTypeChoiceCbor {
     type Error = String;
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
@@ -784,7 +804,8 @@ impl TryFrom<&Value> for DomainTypeChoice {
                     None => return Err("Failed to parse UUID as a DomainTypeChoice".to_string()),
                 }),
             })),
-            _ => Err("Failed to parse value as a DomainTypeChoice".to_string()),
+            Value::Map(_) => Ok(Self::Environment(EnvironmentMapCbor::try_from(value)?)),
+            _ => Err("Failed to parse value as a DomainTypeChoiceCbor".to_string()),
         }
     }
 }
@@ -812,6 +833,153 @@ impl TryFrom<&Value> for EntityNameTypeChoice {
     }
 }
 
+/// The `flags-map` type defined in [CoRIM Section 3.1.4.1.5.5] replaced the `flags-type` bit field
+/// from earlier drafts, which some implementations still emit as an integer (i.e., the Veraison
+/// comid implementation). Both forms are accepted when decoding.
+///
+/// ```text
+/// flags-type = bytes .bits operational-flags
+///
+/// operational-flags = &(
+///   not-configured: 0
+///   not-secure: 1
+///   recovery: 2
+///   debug: 3
+/// )
+/// ```
+///
+/// [CoRIM Section 3.1.4.1.5.5]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.5.5
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum FlagsTypeChoice {
+    Map(FlagsMap),
+    Legacy(i64),
+}
+impl FlagsTypeChoice {
+    /// Returns the flags as a [FlagsMap]. The legacy bit field form yields a map with each of the
+    /// configured, secure, recovery and debug flags present.
+    pub fn to_flags_map(&self) -> FlagsMap {
+        match self {
+            FlagsTypeChoice::Map(m) => m.clone(),
+            FlagsTypeChoice::Legacy(bits) => FlagsMap {
+                configured: Some(bits & 0x01 == 0),
+                secure: Some(bits & 0x02 == 0),
+                recovery: Some(bits & 0x04 != 0),
+                debug: Some(bits & 0x08 != 0),
+                replay_protected: None,
+                integrity_protected: None,
+                other: None,
+            },
+        }
+    }
+}
+impl TryFrom<FlagsTypeChoiceCbor> for FlagsTypeChoice {
+    type Error = String;
+    fn try_from(value: FlagsTypeChoiceCbor) -> Result<Self, Self::Error> {
+        FlagsTypeChoice::try_from(&value)
+    }
+}
+impl TryFrom<&FlagsTypeChoiceCbor> for FlagsTypeChoice {
+    type Error = String;
+    fn try_from(value: &FlagsTypeChoiceCbor) -> Result<Self, Self::Error> {
+        match value {
+            FlagsTypeChoiceCbor::Map(m) => Ok(Self::Map(m.try_into()?)),
+            FlagsTypeChoiceCbor::Legacy(i) => Ok(Self::Legacy(*i)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum FlagsTypeChoiceCbor {
+    Map(FlagsMapCbor),
+    Legacy(i64),
+}
+impl FlagsTypeChoiceCbor {
+    /// Returns these flags expressed per `draft`. Legacy bit fields are expressed as a `flags-map`
+    /// for the current draft. A `flags-map` can be expressed as a bit field only when exactly the
+    /// configured, secure, recovery and debug flags are present.
+    pub fn to_draft(&self, draft: CorimDraft) -> Result<Self, String> {
+        match (self, draft) {
+            (Self::Map(_), CorimDraft::Current) => Ok(self.clone()),
+            (Self::Legacy(bits), CorimDraft::Current) => {
+                if bits & !0x0f != 0 {
+                    return Err(format!(
+                        "Failed to convert flags value {} to a flags-map",
+                        bits
+                    ));
+                }
+                let m = FlagsTypeChoice::try_from(self)?.to_flags_map();
+                Ok(Self::Map(FlagsMapCbor::try_from(&m)?))
+            }
+            (Self::Legacy(_), CorimDraft::Legacy03) => Ok(self.clone()),
+            (Self::Map(m), CorimDraft::Legacy03) => match m {
+                FlagsMapCbor {
+                    configured: Some(c),
+                    secure: Some(s),
+                    recovery: Some(r),
+                    debug: Some(d),
+                    replay_protected: None,
+                    integrity_protected: None,
+                    other: None,
+                } => {
+                    let mut bits = 0;
+                    if !c {
+                        bits |= 0x01;
+                    }
+                    if !s {
+                        bits |= 0x02;
+                    }
+                    if *r {
+                        bits |= 0x04;
+                    }
+                    if *d {
+                        bits |= 0x08;
+                    }
+                    Ok(Self::Legacy(bits))
+                }
+                _ => Err("Failed to convert flags-map to a legacy flags value".to_string()),
+            },
+        }
+    }
+}
+impl TryFrom<FlagsTypeChoice> for FlagsTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: FlagsTypeChoice) -> Result<Self, Self::Error> {
+        FlagsTypeChoiceCbor::try_from(&value)
+    }
+}
+impl TryFrom<&FlagsTypeChoice> for FlagsTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: &FlagsTypeChoice) -> Result<Self, Self::Error> {
+        match value {
+            FlagsTypeChoice::Map(m) => Ok(Self::Map(m.try_into()?)),
+            FlagsTypeChoice::Legacy(i) => Ok(Self::Legacy(*i)),
+        }
+    }
+}
+impl TryFrom<Value> for FlagsTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        FlagsTypeChoiceCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for FlagsTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(_) => Ok(Self::Map(FlagsMapCbor::try_from(value)?)),
+            Value::Integer(i) => match i64::try_from(*i) {
+                Ok(i) => Ok(Self::Legacy(i)),
+                Err(e) => Err(e.to_string()),
+            },
+            _ => Err("Failed to parse value as a FlagsTypeChoiceCbor".to_string()),
+        }
+    }
+}
+
 /// $group-id-type-choice /= tagged-uuid-type
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -924,6 +1092,40 @@ impl TryFrom<&Value> for InstanceIdTypeChoice {
     }
 }
 
+/// The `integrity-register-id-type-choice` type is defined in the current [CoRIM spec].
+///
+/// ```text
+/// integrity-register-id-type-choice = uint / text
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum IntegrityRegisterIdTypeChoice {
+    Uint(u64),
+    Text(String),
+}
+impl TryFrom<Value> for IntegrityRegisterIdTypeChoice {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        IntegrityRegisterIdTypeChoice::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for IntegrityRegisterIdTypeChoice {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(i) => match u64::try_from(*i) {
+                Ok(i) => Ok(Self::Uint(i)),
+                Err(e) => Err(e.to_string()),
+            },
+            Value::Text(t) => Ok(Self::Text(t.clone())),
+            _ => Err("Failed to parse value as an IntegrityRegisterIdTypeChoice".to_string()),
+        }
+    }
+}
+
 /// The `measured-element-type-choice` socket is defined in [CoRIM Section 3.1.4.1.5.1].
 ///
 /// ```text
@@ -1302,6 +1504,105 @@ impl TryFrom<&Value> for ProfileTypeChoiceCbor {
     }
 }
 
+/// The `raw-value-type-choice` socket is defined in [CoRIM Section 3.1.4.1.5.2]. The
+/// `tagged-masked-raw-value` choice was added in later revisions of the spec.
+///
+/// ```text
+/// $raw-value-type-choice /= tagged-bytes
+/// $raw-value-type-choice /= tagged-masked-raw-value
+/// ```
+///
+/// [CoRIM Section 3.1.4.1.5.2]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.5.2
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum RawValueTypeChoice {
+    Bytes(TaggedBytes),
+    MaskedRawValue(TaggedMaskedRawValue),
+}
+impl RawValueTypeChoice {
+    /// Returns the reference value, i.e., the tagged bytes or the value from a masked raw value.
+    pub fn value(&self) -> &[u8] {
+        match self {
+            RawValueTypeChoice::Bytes(b) => match &b.0 {
+                BytesType::Bytes(v) => v,
+            },
+            RawValueTypeChoice::MaskedRawValue(m) => &m.0 .0,
+        }
+    }
+
+    /// Returns the mask carried by a masked raw value, if any.
+    pub fn mask(&self) -> Option<&[u8]> {
+        match self {
+            RawValueTypeChoice::Bytes(_) => None,
+            RawValueTypeChoice::MaskedRawValue(m) => Some(&m.0 .1),
+        }
+    }
+
+    /// Compares `evidence` to this reference value. The mask carried by a masked raw value takes
+    /// precedence over `mask`, which is the `raw-value-mask` from the enclosing
+    /// `measurement-values-map`. See [masked_equal].
+    pub fn matches(&self, mask: Option<&[u8]>, evidence: &[u8]) -> bool {
+        match self.mask() {
+            Some(m) => masked_equal(self.value(), evidence, Some(m)),
+            None => masked_equal(self.value(), evidence, mask),
+        }
+    }
+}
+impl TryFrom<Value> for RawValueTypeChoice {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        RawValueTypeChoice::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for RawValueTypeChoice {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Tag(560, b) => match b.as_bytes() {
+                Some(b) => Ok(Self::Bytes(TaggedBytes {
+                    0: BytesType::Bytes(b.clone()),
+                })),
+                None => {
+                    Err("Failed to parse tagged bytes value as a RawValueTypeChoice".to_string())
+                }
+            },
+            Value::Tag(563, b) => match b.as_array() {
+                Some(a) if a.len() == 2 => match (a[0].as_bytes(), a[1].as_bytes()) {
+                    (Some(v), Some(m)) => Ok(Self::MaskedRawValue(TaggedMaskedRawValue {
+                        0: MaskedRawValue(v.clone(), m.clone()),
+                    })),
+                    _ => {
+                        Err("Failed to parse masked raw value as a RawValueTypeChoice".to_string())
+                    }
+                },
+                _ => Err("Failed to parse masked raw value as a RawValueTypeChoice".to_string()),
+            },
+            _ => Err("Failed to parse value as a RawValueTypeChoice".to_string()),
+        }
+    }
+}
+
+/// Returns true if `reference` and `evidence` are equal after applying `mask` to each, i.e.,
+/// `reference & mask == evidence & mask`. When `mask` is absent, the values are compared directly.
+/// Values of differing lengths, or a mask whose length differs from the values, never match.
+pub fn masked_equal(reference: &[u8], evidence: &[u8], mask: Option<&[u8]>) -> bool {
+    if reference.len() != evidence.len() {
+        return false;
+    }
+    match mask {
+        Some(mask) => {
+            mask.len() == reference.len()
+                && reference
+                    .iter()
+                    .zip(evidence.iter())
+                    .zip(mask.iter())
+                    .all(|((r, e), m)| r & m == e & m)
+        }
+        None => reference == evidence,
+    }
+}
+
 /// The `svn-type-choice` socket is defined in [CoRIM Section 3.1.4.1.5.4].
 ///
 /// ```text
@@ -1622,3 +1923,101 @@ impl TryFrom<&Value> for TagVersionType {
         }
     }
 }
+
+/// Keys in identity and attest key triples are expressed using `$crypto-key-type-choice` in the
+/// current [CoRIM spec]. Inputs generated per draft-birkholz-rats-corim-03 (i.e., by the Veraison
+/// corim implementation) use `verification-key-map` instead. Both forms are accepted when decoding.
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum VerificationKeyTypeChoice {
+    Key(CryptoKeyTypeChoice),
+    Legacy(VerificationKeyMap),
+}
+impl TryFrom<VerificationKeyTypeChoiceCbor> for VerificationKeyTypeChoice {
+    type Error = String;
+    fn try_from(value: VerificationKeyTypeChoiceCbor) -> Result<Self, Self::Error> {
+        VerificationKeyTypeChoice::try_from(&value)
+    }
+}
+impl TryFrom<&VerificationKeyTypeChoiceCbor> for VerificationKeyTypeChoice {
+    type Error = String;
+    fn try_from(value: &VerificationKeyTypeChoiceCbor) -> Result<Self, Self::Error> {
+        match value {
+            VerificationKeyTypeChoiceCbor::Key(k) => Ok(Self::Key(k.clone())),
+            VerificationKeyTypeChoiceCbor::Legacy(m) => Ok(Self::Legacy(m.try_into()?)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+#[allow(missing_docs)]
+pub enum VerificationKeyTypeChoiceCbor {
+    Key(CryptoKeyTypeChoice),
+    Legacy(VerificationKeyMapCbor),
+}
+impl VerificationKeyTypeChoiceCbor {
+    /// Returns this key expressed per `draft`. A `verification-key-map` without a keychain is
+    /// equivalent to a `tagged-pkix-base64-key-type`. Other keys cannot be expressed in both
+    /// forms and yield an error.
+    pub fn to_draft(&self, draft: CorimDraft) -> Result<Self, String> {
+        match (self, draft) {
+            (Self::Key(_), CorimDraft::Current) | (Self::Legacy(_), CorimDraft::Legacy03) => {
+                Ok(self.clone())
+            }
+            (Self::Legacy(m), CorimDraft::Current) => match &m.keychain {
+                None => Ok(Self::Key(CryptoKeyTypeChoice::Key(
+                    TaggedPkixBase64KeyType { 0: m.key.clone() },
+                ))),
+                Some(_) => Err(
+                    "Failed to convert verification-key-map with a keychain to a crypto-key-type-choice"
+                        .to_string(),
+                ),
+            },
+            (Self::Key(CryptoKeyTypeChoice::Key(k)), CorimDraft::Legacy03) => {
+                Ok(Self::Legacy(VerificationKeyMapCbor {
+                    key: k.0.clone(),
+                    keychain: None,
+                }))
+            }
+            (Self::Key(_), CorimDraft::Legacy03) => Err(
+                "Only tagged-pkix-base64-key-type values can be expressed as a verification-key-map"
+                    .to_string(),
+            ),
+        }
+    }
+}
+impl TryFrom<VerificationKeyTypeChoice> for VerificationKeyTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: VerificationKeyTypeChoice) -> Result<Self, Self::Error> {
+        VerificationKeyTypeChoiceCbor::try_from(&value)
+    }
+}
+impl TryFrom<&VerificationKeyTypeChoice> for VerificationKeyTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: &VerificationKeyTypeChoice) -> Result<Self, Self::Error> {
+        match value {
+            VerificationKeyTypeChoice::Key(k) => Ok(Self::Key(k.clone())),
+            VerificationKeyTypeChoice::Legacy(m) => Ok(Self::Legacy(m.try_into()?)),
+        }
+    }
+}
+impl TryFrom<Value> for VerificationKeyTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        VerificationKeyTypeChoiceCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for VerificationKeyTypeChoiceCbor {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(_) => Ok(Self::Legacy(VerificationKeyMapCbor::try_from(value)?)),
+            Value::Tag(_, _) => Ok(Self::Key(CryptoKeyTypeChoice::try_from(value)?)),
+            _ => Err("Failed to parse value as a VerificationKeyTypeChoiceCbor".to_string()),
+        }
+    }
+}
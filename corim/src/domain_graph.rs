@@ -0,0 +1,291 @@
+//! Graph of the domains described by the domain dependency and domain membership triples of a set
+//! of CoMIDs
+//!
+//! A domain membership triple names a domain and the domains or environments that are members of it
+//! (i.e., the components of a composite device). A domain dependency triple names a domain and the
+//! domains upon which it depends (see [CoRIM Section 3.1.4.6]). Each distinct `domain-type-choice`
+//! value is a node in a [DomainGraph]. Triples from different CoMIDs that name the same domain
+//! contribute edges to the same node, so a platform described by several vendors can be assembled
+//! from the CoMIDs of each vendor.
+//!
+//! [CoRIM Section 3.1.4.6]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.6
+
+use alloc::collections::VecDeque;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use crate::arrays::DomainDependencyTripleRecord;
+use crate::choices::*;
+use crate::maps::*;
+use common::{UeidType, UuidType};
+
+/// Relationship represented by an edge in a [DomainGraph].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DomainEdgeKind {
+    /// The target is a member of the source
+    Membership,
+    /// The source depends on the target
+    Dependency,
+}
+
+/// An edge in a [DomainGraph]. Nodes are identified by their index in [DomainGraph::nodes].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainEdge {
+    /// Index of the domain named first in the triple
+    pub from: usize,
+    /// Index of a member or trustee named in the triple
+    pub to: usize,
+    /// Type of triple that contributed the edge
+    pub kind: DomainEdgeKind,
+    /// Tag ID of the CoMID that contained the triple, if available
+    pub tag_id: Option<TagIdTypeChoice>,
+}
+
+/// Graph of domains and the membership and dependency relationships between them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainGraph {
+    nodes: Vec<DomainTypeChoice>,
+    edges: Vec<DomainEdge>,
+}
+
+impl DomainGraph {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a graph from the domain triples of each of the given CoMIDs.
+    pub fn from_comids(comids: &[ConciseMidTag]) -> Self {
+        let mut retval = Self::new();
+        for comid in comids {
+            retval.add_comid(comid);
+        }
+        retval
+    }
+
+    /// Adds the domain membership and domain dependency triples from `comid` to the graph.
+    pub fn add_comid(&mut self, comid: &ConciseMidTag) {
+        let tag_id = comid.tag_identity.as_ref().map(|t| t.tag_id.clone());
+        let triples = [
+            (
+                &comid.triples.membership_triples,
+                DomainEdgeKind::Membership,
+            ),
+            (
+                &comid.triples.dependency_triples,
+                DomainEdgeKind::Dependency,
+            ),
+        ];
+        for (records, kind) in triples {
+            for record in records.iter().flatten() {
+                self.add_triple(record, kind, tag_id.clone());
+            }
+        }
+    }
+
+    /// Adds the edges described by a single triple to the graph.
+    pub fn add_triple(
+        &mut self,
+        record: &DomainDependencyTripleRecord,
+        kind: DomainEdgeKind,
+        tag_id: Option<TagIdTypeChoice>,
+    ) {
+        let from = self.add_node(&record.domain_type_choice);
+        for target in &record.domain_type_choices {
+            let to = self.add_node(target);
+            let edge = DomainEdge {
+                from,
+                to,
+                kind,
+                tag_id: tag_id.clone(),
+            };
+            // the same relationship may be asserted by more than one CoMID
+            if !self
+                .edges
+                .iter()
+                .any(|e| e.from == from && e.to == to && e.kind == kind)
+            {
+                self.edges.push(edge);
+            }
+        }
+    }
+
+    fn add_node(&mut self, domain: &DomainTypeChoice) -> usize {
+        match self.index_of(domain) {
+            Some(i) => i,
+            None => {
+                self.nodes.push(domain.clone());
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Returns the index of `domain` in [DomainGraph::nodes], if present.
+    pub fn index_of(&self, domain: &DomainTypeChoice) -> Option<usize> {
+        self.nodes.iter().position(|n| n == domain)
+    }
+
+    /// Returns the nodes of the graph.
+    pub fn nodes(&self) -> &[DomainTypeChoice] {
+        &self.nodes
+    }
+
+    /// Returns the edges of the graph.
+    pub fn edges(&self) -> &[DomainEdge] {
+        &self.edges
+    }
+
+    /// Returns the direct members of `domain`.
+    pub fn members(&self, domain: &DomainTypeChoice) -> Vec<&DomainTypeChoice> {
+        self.neighbors(domain, DomainEdgeKind::Membership, false)
+    }
+
+    /// Returns the domains of which `member` is a direct member.
+    pub fn member_of(&self, member: &DomainTypeChoice) -> Vec<&DomainTypeChoice> {
+        self.neighbors(member, DomainEdgeKind::Membership, true)
+    }
+
+    /// Returns the domains upon which `domain` directly depends.
+    pub fn trustees(&self, domain: &DomainTypeChoice) -> Vec<&DomainTypeChoice> {
+        self.neighbors(domain, DomainEdgeKind::Dependency, false)
+    }
+
+    /// Returns the domains that directly depend upon `domain`.
+    pub fn dependents(&self, domain: &DomainTypeChoice) -> Vec<&DomainTypeChoice> {
+        self.neighbors(domain, DomainEdgeKind::Dependency, true)
+    }
+
+    /// Returns the environments that make up `domain`, i.e., the environments that are members of
+    /// `domain` or, recursively, of a domain that is a member of `domain`.
+    pub fn environments(&self, domain: &DomainTypeChoice) -> Vec<&EnvironmentMap> {
+        self.reachable(domain, DomainEdgeKind::Membership)
+            .into_iter()
+            .filter_map(|i| match &self.nodes[i] {
+                DomainTypeChoice::Environment(e) => Some(e),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the domains upon which `domain` depends directly or indirectly.
+    pub fn all_trustees(&self, domain: &DomainTypeChoice) -> Vec<&DomainTypeChoice> {
+        self.reachable(domain, DomainEdgeKind::Dependency)
+            .into_iter()
+            .map(|i| &self.nodes[i])
+            .collect()
+    }
+
+    fn neighbors(
+        &self,
+        domain: &DomainTypeChoice,
+        kind: DomainEdgeKind,
+        reverse: bool,
+    ) -> Vec<&DomainTypeChoice> {
+        let i = match self.index_of(domain) {
+            Some(i) => i,
+            None => return vec![],
+        };
+        self.edges
+            .iter()
+            .filter(|e| e.kind == kind)
+            .filter_map(|e| match reverse {
+                false if e.from == i => Some(&self.nodes[e.to]),
+                true if e.to == i => Some(&self.nodes[e.from]),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the indices of the nodes reachable from `domain` via edges of the given kind, in
+    /// breadth first order. `domain` itself is not included unless it lies on a cycle.
+    fn reachable(&self, domain: &DomainTypeChoice, kind: DomainEdgeKind) -> Vec<usize> {
+        let start = match self.index_of(domain) {
+            Some(i) => i,
+            None => return vec![],
+        };
+        let mut visited = vec![false; self.nodes.len()];
+        let mut retval = vec![];
+        let mut queue = VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            for e in self.edges.iter().filter(|e| e.kind == kind && e.from == i) {
+                if !visited[e.to] {
+                    visited[e.to] = true;
+                    retval.push(e.to);
+                    queue.push_back(e.to);
+                }
+            }
+        }
+        retval
+    }
+
+    /// Returns the graph in the Graphviz DOT language. Membership edges are solid and dependency
+    /// edges are dashed. Environments are drawn as boxes.
+    pub fn to_dot(&self) -> String {
+        let mut retval = "digraph domains {\n".to_string();
+        for (i, n) in self.nodes.iter().enumerate() {
+            let shape = match n {
+                DomainTypeChoice::Environment(_) => "box",
+                _ => "ellipse",
+            };
+            retval.push_str(&format!(
+                "  n{} [label=\"{}\", shape={}];\n",
+                i,
+                dot_escape(&domain_label(n)),
+                shape
+            ));
+        }
+        for e in &self.edges {
+            let attrs = match e.kind {
+                DomainEdgeKind::Membership => "label=\"member\"",
+                DomainEdgeKind::Dependency => "label=\"depends on\", style=dashed",
+            };
+            retval.push_str(&format!("  n{} -> n{} [{}];\n", e.from, e.to, attrs));
+        }
+        retval.push_str("}\n");
+        retval
+    }
+}
+
+/// Returns a short human readable label for `domain`.
+pub fn domain_label(domain: &DomainTypeChoice) -> String {
+    match domain {
+        DomainTypeChoice::U64(u) => u.to_string(),
+        DomainTypeChoice::Text(s) => s.clone(),
+        DomainTypeChoice::Uuid(u) => {
+            let UuidType::Uuid(b) = &u.0;
+            hex(b)
+        }
+        DomainTypeChoice::Environment(e) => {
+            let mut parts = vec![];
+            if let Some(c) = &e.class {
+                parts.extend(c.vendor.iter().cloned());
+                parts.extend(c.model.iter().cloned());
+            }
+            match &e.instance {
+                Some(InstanceIdTypeChoice::Ueid(u)) => {
+                    let UeidType::Ueid(b) = &u.0;
+                    parts.push(format!("ueid {}", hex(b)));
+                }
+                Some(InstanceIdTypeChoice::Uuid(u)) => {
+                    let UuidType::Uuid(b) = &u.0;
+                    parts.push(format!("uuid {}", hex(b)));
+                }
+                None => {}
+            }
+            if parts.is_empty() {
+                "environment".to_string()
+            } else {
+                parts.join(" ")
+            }
+        }
+    }
+}
+
+fn hex(b: &[u8]) -> String {
+    String::from_utf8(subtle_encoding::hex::encode(b)).unwrap_or_default()
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,351 @@
+//! Extraction of verification keys from the identity and attest key triples of CoMIDs
+//!
+//! Keys conveyed as a `tagged-pkix-base64-key-type`, `tagged-pkix-base64-cert-type`,
+//! `tagged-pkix-base64-cert-path-type` or `tagged-pkix-asn1der-cert-type` (see [CoRIM Section
+//! 3.1.4.1.6]) and keys conveyed as a `verification-key-map` are parsed into a [VerificationKey].
+//! Base64 values may be PEM encoded or bare base64. Thumbprints and COSE keys do not yield a
+//! [VerificationKey].
+//!
+//! Certificates from a certification path are ordered by matching the issuer of each certificate to
+//! the subject of the next, starting with the certificate that contains the key. Signatures on the
+//! certificates are not verified, i.e., path validation remains the responsibility of the caller.
+//!
+//! [CoRIM Section 3.1.4.1.6]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.6
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use der::{Decode, Encode, SliceReader};
+use spki::{ObjectIdentifier, SubjectPublicKeyInfoOwned};
+use x509_cert::Certificate;
+
+use crate::appraisal::environment_matches;
+use crate::choices::*;
+use crate::maps::*;
+use common::PkixBase64Type;
+
+/// A public key from a CoMID along with the certificates that were conveyed with it, if any.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerificationKey {
+    /// DER-encoded `SubjectPublicKeyInfo`
+    pub spki: Vec<u8>,
+    /// DER-encoded certificates, starting with the certificate that contains the key and ending
+    /// with the certificate closest to the trust anchor. Empty for bare keys.
+    pub certificates: Vec<Vec<u8>>,
+}
+
+impl VerificationKey {
+    /// Creates a key from a DER-encoded `SubjectPublicKeyInfo`.
+    pub fn from_spki_der(spki: &[u8]) -> Result<Self, String> {
+        parse_spki(spki)?;
+        Ok(VerificationKey {
+            spki: spki.to_vec(),
+            certificates: vec![],
+        })
+    }
+
+    /// Creates a key from one or more DER-encoded certificates, which may be presented in any
+    /// order. The key is taken from the certificate that did not issue any of the others.
+    pub fn from_certificates(certificates: &[Vec<u8>]) -> Result<Self, String> {
+        let parsed = certificates
+            .iter()
+            .map(|c| {
+                Certificate::from_der(c).map_err(|e| format!("Failed to parse certificate: {}", e))
+            })
+            .collect::<Result<Vec<Certificate>, String>>()?;
+        let order = order_path(&parsed)?;
+        let spki = parsed[order[0]]
+            .tbs_certificate
+            .subject_public_key_info
+            .to_der()
+            .map_err(|e| format!("Failed to encode SubjectPublicKeyInfo: {}", e))?;
+        Ok(VerificationKey {
+            spki,
+            certificates: order.iter().map(|i| certificates[*i].clone()).collect(),
+        })
+    }
+
+    /// Returns the algorithm OID from the `SubjectPublicKeyInfo`, i.e., id-ecPublicKey or
+    /// id-Ed25519.
+    pub fn algorithm(&self) -> Result<ObjectIdentifier, String> {
+        Ok(parse_spki(&self.spki)?.algorithm.oid)
+    }
+
+    /// Returns the named curve from the algorithm parameters, if the parameters are an OID.
+    pub fn curve(&self) -> Result<Option<ObjectIdentifier>, String> {
+        let spki = parse_spki(&self.spki)?;
+        Ok(spki
+            .algorithm
+            .parameters
+            .and_then(|p| p.decode_as::<ObjectIdentifier>().ok()))
+    }
+
+    /// Returns the contents of the `subjectPublicKey` field, i.e., an uncompressed elliptic curve
+    /// point or an Ed25519 public key.
+    pub fn public_key_bytes(&self) -> Result<Vec<u8>, String> {
+        Ok(parse_spki(&self.spki)?
+            .subject_public_key
+            .raw_bytes()
+            .to_vec())
+    }
+}
+
+impl TryFrom<&CryptoKeyTypeChoice> for VerificationKey {
+    type Error = String;
+    fn try_from(value: &CryptoKeyTypeChoice) -> Result<Self, Self::Error> {
+        match value {
+            CryptoKeyTypeChoice::Key(k) => {
+                let mut blocks = decode_pkix_base64(&k.0)?;
+                if blocks.len() != 1 {
+                    return Err("Expected a single key in tagged-pkix-base64-key-type".to_string());
+                }
+                VerificationKey::from_spki_der(&blocks.remove(0))
+            }
+            CryptoKeyTypeChoice::Cert(c) => {
+                let blocks = decode_pkix_base64(&c.0)?;
+                if blocks.len() != 1 {
+                    return Err(
+                        "Expected a single certificate in tagged-pkix-base64-cert-type".to_string(),
+                    );
+                }
+                VerificationKey::from_certificates(&blocks)
+            }
+            CryptoKeyTypeChoice::Path(p) => {
+                VerificationKey::from_certificates(&decode_pkix_base64(&p.0)?)
+            }
+            CryptoKeyTypeChoice::Asn1DerCert(c) => {
+                let common::BytesType::Bytes(b) = &c.0;
+                VerificationKey::from_certificates(core::slice::from_ref(b))
+            }
+            _ => Err("Key type does not convey a public key".to_string()),
+        }
+    }
+}
+
+impl TryFrom<&VerificationKeyMap> for VerificationKey {
+    type Error = String;
+    fn try_from(value: &VerificationKeyMap) -> Result<Self, Self::Error> {
+        let mut blocks = decode_pkix_base64(&value.key)?;
+        if blocks.len() != 1 {
+            return Err("Expected a single key in verification-key-map".to_string());
+        }
+        let key = VerificationKey::from_spki_der(&blocks.remove(0))?;
+        let keychain = match &value.keychain {
+            Some(keychain) => keychain,
+            None => return Ok(key),
+        };
+        let mut certificates = vec![];
+        for PkixBase64Type::Base64(c) in keychain {
+            certificates.append(&mut decode_pkix_base64(c)?);
+        }
+        let path = VerificationKey::from_certificates(&certificates)?;
+        if path.spki != key.spki {
+            return Err("Key does not match the first certificate in the keychain".to_string());
+        }
+        Ok(path)
+    }
+}
+
+impl TryFrom<&VerificationKeyTypeChoice> for VerificationKey {
+    type Error = String;
+    fn try_from(value: &VerificationKeyTypeChoice) -> Result<Self, Self::Error> {
+        match value {
+            VerificationKeyTypeChoice::Key(k) => VerificationKey::try_from(k),
+            VerificationKeyTypeChoice::Legacy(m) => VerificationKey::try_from(m),
+        }
+    }
+}
+
+/// Triple from which a key was taken.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyPurpose {
+    /// Identity triple, i.e., a key used to authenticate the environment
+    Identity,
+    /// Attest key triple, i.e., a key used to sign evidence produced by the environment
+    AttestKey,
+}
+
+/// A key from an identity or attest key triple along with the environment it pertains to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyEntry {
+    /// Environment from the triple
+    pub environment: EnvironmentMap,
+    /// Triple from which the key was taken
+    pub purpose: KeyPurpose,
+    /// The key
+    pub key: VerificationKey,
+    /// Tag ID of the CoMID that contained the triple, if available
+    pub tag_id: Option<TagIdTypeChoice>,
+}
+
+/// Keys from the identity and attest key triples of a set of CoMIDs, indexed by environment.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyIndex {
+    /// Keys that were extracted
+    pub entries: Vec<KeyEntry>,
+    /// Descriptions of keys that could not be extracted, e.g., thumbprints
+    pub issues: Vec<String>,
+}
+
+impl KeyIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an index from the identity and attest key triples of each of the given CoMIDs.
+    pub fn from_comids(comids: &[ConciseMidTag]) -> Self {
+        let mut retval = Self::new();
+        for comid in comids {
+            retval.add_comid(comid);
+        }
+        retval
+    }
+
+    /// Adds the keys from the identity and attest key triples of `comid` to the index. Keys that
+    /// cannot be extracted are recorded in `issues`.
+    pub fn add_comid(&mut self, comid: &ConciseMidTag) {
+        let tag_id = comid.tag_identity.as_ref().map(|t| t.tag_id.clone());
+        let identity = comid.triples.identity_triples.iter().flatten();
+        let attest = comid.triples.attest_key_triples.iter().flatten();
+        let triples = identity
+            .map(|t| (&t.environment_map, &t.crypto_keys, KeyPurpose::Identity))
+            .chain(attest.map(|t| (&t.environment_map, &t.crypto_keys, KeyPurpose::AttestKey)));
+        for (environment, keys, purpose) in triples {
+            for k in keys {
+                match VerificationKey::try_from(k) {
+                    Ok(key) => self.entries.push(KeyEntry {
+                        environment: environment.clone(),
+                        purpose,
+                        key,
+                        tag_id: tag_id.clone(),
+                    }),
+                    Err(e) => self
+                        .issues
+                        .push(format!("{:?} key in {:?}: {}", purpose, tag_id, e)),
+                }
+            }
+        }
+    }
+
+    /// Returns the keys whose environment matches `environment` (see
+    /// [environment_matches](crate::appraisal::environment_matches)).
+    pub fn keys_for(&self, environment: &EnvironmentMap) -> Vec<&KeyEntry> {
+        self.entries
+            .iter()
+            .filter(|e| environment_matches(&e.environment, environment))
+            .collect()
+    }
+
+    /// Returns the keys that may have signed evidence produced by `environment`.
+    pub fn attest_keys_for(&self, environment: &EnvironmentMap) -> Vec<&VerificationKey> {
+        self.keys_with_purpose(environment, KeyPurpose::AttestKey)
+    }
+
+    /// Returns the keys that may be used to authenticate `environment`.
+    pub fn identity_keys_for(&self, environment: &EnvironmentMap) -> Vec<&VerificationKey> {
+        self.keys_with_purpose(environment, KeyPurpose::Identity)
+    }
+
+    fn keys_with_purpose(
+        &self,
+        environment: &EnvironmentMap,
+        purpose: KeyPurpose,
+    ) -> Vec<&VerificationKey> {
+        self.keys_for(environment)
+            .into_iter()
+            .filter(|e| e.purpose == purpose)
+            .map(|e| &e.key)
+            .collect()
+    }
+}
+
+fn parse_spki(spki: &[u8]) -> Result<SubjectPublicKeyInfoOwned, String> {
+    SubjectPublicKeyInfoOwned::from_der(spki)
+        .map_err(|e| format!("Failed to parse SubjectPublicKeyInfo: {}", e))
+}
+
+/// Returns the indices of `certificates` ordered from the certificate that did not issue any of the
+/// others to the certificate closest to the trust anchor.
+fn order_path(certificates: &[Certificate]) -> Result<Vec<usize>, String> {
+    let issued_another = |i: usize| {
+        let subject = &certificates[i].tbs_certificate.subject;
+        certificates.iter().enumerate().any(|(j, c)| {
+            j != i && c.tbs_certificate.issuer == *subject && c.tbs_certificate.subject != *subject
+        })
+    };
+    let leaves: Vec<usize> = (0..certificates.len())
+        .filter(|i| !issued_another(*i))
+        .collect();
+    if leaves.len() != 1 {
+        return Err(format!(
+            "Expected certification path with one end entity certificate, found {}",
+            leaves.len()
+        ));
+    }
+    let mut retval = vec![leaves[0]];
+    loop {
+        let current = &certificates[retval[retval.len() - 1]].tbs_certificate;
+        if current.issuer == current.subject {
+            break;
+        }
+        match (0..certificates.len()).find(|j| {
+            !retval.contains(j) && certificates[*j].tbs_certificate.subject == current.issuer
+        }) {
+            Some(j) => retval.push(j),
+            None => break,
+        }
+    }
+    if retval.len() != certificates.len() {
+        return Err("Certificates do not form a single certification path".to_string());
+    }
+    Ok(retval)
+}
+
+/// Decodes PEM or bare base64 text. Each PEM block yields one element. Bare base64 may contain one
+/// or more concatenated DER-encoded values, each of which yields one element.
+fn decode_pkix_base64(text: &str) -> Result<Vec<Vec<u8>>, String> {
+    if !text.contains("-----BEGIN ") {
+        let der = decode_base64(text)?;
+        return split_der(&der);
+    }
+    let mut retval = vec![];
+    let mut block: Option<String> = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("-----BEGIN ") {
+            block = Some(String::new());
+        } else if line.starts_with("-----END ") {
+            match block.take() {
+                Some(b) => retval.push(decode_base64(&b)?),
+                None => return Err("Unexpected PEM end boundary".to_string()),
+            }
+        } else if let Some(b) = block.as_mut() {
+            b.push_str(line);
+        }
+    }
+    if block.is_some() {
+        return Err("Missing PEM end boundary".to_string());
+    }
+    Ok(retval)
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let compact: String = text.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    base64::decode(compact).map_err(|e| format!("Failed to decode base64: {}", e))
+}
+
+/// Splits concatenated DER-encoded values.
+fn split_der(der: &[u8]) -> Result<Vec<Vec<u8>>, String> {
+    let mut retval = vec![];
+    let mut reader =
+        SliceReader::new(der).map_err(|e| format!("Failed to read DER-encoded value: {}", e))?;
+    while !der::Reader::is_finished(&reader) {
+        let any = der::asn1::AnyRef::decode(&mut reader)
+            .map_err(|e| format!("Failed to read DER-encoded value: {}", e))?;
+        retval.push(
+            any.to_der()
+                .map_err(|e| format!("Failed to encode DER value: {}", e))?,
+        );
+    }
+    Ok(retval)
+}
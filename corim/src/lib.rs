@@ -7,12 +7,23 @@
 
 extern crate alloc;
 
+pub mod appraisal;
 pub mod arrays;
+pub mod builder;
 pub mod choices;
+pub mod domain_graph;
+pub mod keys;
 pub mod maps;
+pub mod resolver;
+pub mod tag_store;
+pub mod validity;
 
 use alloc::vec::Vec;
 use ciborium::tag::Required;
+use ciborium::value::Value;
+use common::arrays::HashEntryCbor;
+use common::BytesType;
+use serde::{Deserialize, Serialize};
 
 // corim = #6.500($concise-reference-integrity-manifest-type-choice)
 //
@@ -32,12 +43,73 @@ use ciborium::tag::Required;
 //   signature: bstr
 // ]
 
-/// $raw-value-type-choice /= #6.560(bytes)
-pub type RawValueTypeChoice = Required<Vec<u8>, 560>;
+/// Content type of an unsigned CoRIM as defined in the current [CoRIM spec].
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+pub const CORIM_CONTENT_TYPE: &str = "application/rim+cbor";
+
+/// Content type of an unsigned CoRIM as defined in draft-birkholz-rats-corim-03.
+pub const LEGACY_CORIM_CONTENT_TYPE: &str = "application/corim-unsigned+cbor";
+
+/// Identifies the revision of the CoRIM spec whose encoding conventions are used when converting a
+/// CoMID with [ConciseMidTagCbor::convert_to](maps::ConciseMidTagCbor::convert_to).
+///
+/// Both forms are accepted when decoding. The legacy form corresponds to draft-birkholz-rats-corim-03
+/// as emitted by the Veraison corim implementation, i.e., `verification-key-map` key lists,
+/// `#6.600` class IDs and integer flags.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CorimDraft {
+    /// Current `draft-ietf-rats-corim` data model
+    #[default]
+    Current,
+    /// draft-birkholz-rats-corim-03 data model
+    Legacy03,
+}
+
+/// tagged-bytes = #6.560(bytes)
+pub type TaggedBytes = Required<BytesType, 560>;
+
+/// tagged-key-thumbprint-type = #6.557(digest)
+pub type TaggedKeyThumbprintType = Required<HashEntryCbor, 557>;
+
+/// tagged-cose-key-type = #6.558(COSE_Key)
+pub type TaggedCoseKeyType = Required<Value, 558>;
+
+/// tagged-cert-thumbprint-type = #6.559(digest)
+pub type TaggedCertThumbprintType = Required<HashEntryCbor, 559>;
+
+/// tagged-cert-path-thumbprint-type = #6.561(digest)
+pub type TaggedCertPathThumbprintType = Required<HashEntryCbor, 561>;
+
+/// tagged-pkix-asn1der-cert-type = #6.562(bytes)
+pub type TaggedPkixAsn1DerCertType = Required<BytesType, 562>;
+
+/// tagged-masked-raw-value = #6.563(masked-raw-value)
+pub type TaggedMaskedRawValue = Required<MaskedRawValue, 563>;
 
 /// raw-value-mask-type = bytes
 pub type RawValueMaskType = Vec<u8>;
 
+/// The `masked-raw-value` type is defined in later revisions of the [CoRIM spec].
+///
+/// ```text
+/// masked-raw-value = [
+///   value: bytes
+///   mask : bytes
+/// ]
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MaskedRawValue(
+    /// Reference value
+    #[serde(with = "serde_bytes")]
+    pub Vec<u8>,
+    /// Mask applied to the reference value and to evidence prior to comparison
+    #[serde(with = "serde_bytes")]
+    pub Vec<u8>,
+);
+
 //    ip-addr-type-choice = ip4-addr-type / ip6-addr-type
 //    ip4-addr-type = bytes .size 4
 //    ip6-addr-type = bytes .size 16
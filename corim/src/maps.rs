@@ -4,8 +4,9 @@ use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::{vec, vec::Vec};
-use ciborium::value::Integer;
+use ciborium::de::from_reader;
 use ciborium::{cbor, value::Value};
+use core::cmp::Ordering;
 use core::{fmt, marker::PhantomData};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde::{
@@ -15,9 +16,12 @@ use serde::{
 
 use crate::arrays::*;
 use crate::choices::*;
+use crate::validity::ValidityStatus;
+use crate::{CorimDraft, RawValueMaskType};
 use cbor_derive::StructToMap;
 use common::arrays::*;
 use common::choices::*;
+use common::version::*;
 use common::*;
 use serde::ser::Error as OtherError;
 
@@ -81,6 +85,166 @@ pub struct ConciseMidTag {
     pub other: Option<Vec<Tuple>>,
 }
 
+impl ConciseMidTagCbor {
+    /// Rewrites each `tagged-int-type` class ID in the triples of this CoMID using the form
+    /// indicated by `mode`, i.e., #6.551(int) per the spec or #6.600(bytes) for interop with
+    /// the Veraison corim implementation.
+    pub fn set_tagged_int_mode(&mut self, mode: TaggedIntMode) -> Result<(), String> {
+        for env in self.environments_mut() {
+            if let Some(ClassMapCbor { id: Some(id), .. }) = &mut env.class {
+                *id = id.with_tagged_int_mode(mode)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn environments_mut(&mut self) -> Vec<&mut EnvironmentMapCbor> {
+        let t = &mut self.triples;
+        let mut envs: Vec<&mut EnvironmentMapCbor> = vec![];
+        if let Some(v) = &mut t.reference_triples {
+            envs.extend(v.iter_mut().map(|r| &mut r.environment_map));
+        }
+        if let Some(v) = &mut t.endorsed_triples {
+            envs.extend(v.iter_mut().map(|r| &mut r.environment_map));
+        }
+        if let Some(v) = &mut t.identity_triples {
+            envs.extend(v.iter_mut().map(|r| &mut r.environment_map));
+        }
+        if let Some(v) = &mut t.attest_key_triples {
+            envs.extend(v.iter_mut().map(|r| &mut r.environment_map));
+        }
+        if let Some(v) = &mut t.coswid_triples {
+            envs.extend(v.iter_mut().map(|r| &mut r.environment_map));
+        }
+        for v in [&mut t.dependency_triples, &mut t.membership_triples]
+            .into_iter()
+            .flatten()
+        {
+            for r in v.iter_mut() {
+                let domains = core::iter::once(&mut r.domain_type_choice)
+                    .chain(r.domain_type_choices.iter_mut());
+                envs.extend(domains.filter_map(|d| match d {
+                    DomainTypeChoiceCbor::Environment(e) => Some(e),
+                    _ => None,
+                }));
+            }
+        }
+        if let Some(v) = &mut t.conditional_endorsement_series_triples {
+            envs.extend(v.iter_mut().map(|r| &mut r.condition.environment));
+        }
+        if let Some(v) = &mut t.conditional_endorsement_triples {
+            for r in v.iter_mut() {
+                envs.extend(r.conditions.iter_mut().map(|c| &mut c.environment));
+                envs.extend(r.endorsements.iter_mut().map(|e| &mut e.environment_map));
+            }
+        }
+        envs
+    }
+
+    /// Returns the measurement maps of each triple that carries measurements.
+    fn measurements_mut(&mut self) -> Vec<&mut MeasurementMapCbor> {
+        let t = &mut self.triples;
+        let mut measurements: Vec<&mut MeasurementMapCbor> = vec![];
+        if let Some(v) = &mut t.reference_triples {
+            measurements.extend(v.iter_mut().flat_map(|r| r.measurement_map.iter_mut()));
+        }
+        if let Some(v) = &mut t.endorsed_triples {
+            measurements.extend(v.iter_mut().flat_map(|r| r.measurement_map.iter_mut()));
+        }
+        if let Some(v) = &mut t.conditional_endorsement_series_triples {
+            for r in v.iter_mut() {
+                measurements.extend(r.condition.claims_list.iter_mut());
+                for s in r.series.iter_mut() {
+                    measurements.extend(s.selection.iter_mut().chain(s.addition.iter_mut()));
+                }
+            }
+        }
+        if let Some(v) = &mut t.conditional_endorsement_triples {
+            for r in v.iter_mut() {
+                measurements.extend(
+                    r.conditions
+                        .iter_mut()
+                        .flat_map(|c| c.claims_list.iter_mut()),
+                );
+                measurements.extend(
+                    r.endorsements
+                        .iter_mut()
+                        .flat_map(|e| e.measurement_map.iter_mut()),
+                );
+            }
+        }
+        measurements
+    }
+
+    /// Rewrites this CoMID using the encoding conventions of `draft`. Class IDs, keys in identity
+    /// and attest key triples and operational flags are converted to the form used by `draft`.
+    /// Class IDs that cannot be expressed in the form used by `draft`, i.e., `#6.600` class IDs
+    /// that are too large for an int, are left as is and returned.
+    ///
+    /// An error is returned if the CoMID cannot be expressed per `draft` without loss, i.e., when
+    /// converting a CoMID that uses conditional endorsement triples, `cryptokeys` or
+    /// `integrity-registers` measurements to [CorimDraft::Legacy03]. The CoMID is not modified
+    /// when an error is returned.
+    pub fn convert_to(&mut self, draft: CorimDraft) -> Result<Vec<ClassIdTypeChoiceCbor>, String> {
+        let mut converted = self.clone();
+        if draft == CorimDraft::Legacy03 {
+            let t = &converted.triples;
+            if t.conditional_endorsement_series_triples.is_some()
+                || t.conditional_endorsement_triples.is_some()
+            {
+                return Err(
+                    "Conditional endorsement triples cannot be expressed per draft-birkholz-rats-corim-03"
+                        .to_string(),
+                );
+            }
+            if converted
+                .measurements_mut()
+                .iter()
+                .any(|m| m.value.cryptokeys.is_some() || m.value.integrity_registers.is_some())
+            {
+                return Err(
+                    "cryptokeys and integrity-registers measurements cannot be expressed per draft-birkholz-rats-corim-03"
+                        .to_string(),
+                );
+            }
+        }
+
+        let t = &mut converted.triples;
+        let mut keys: Vec<&mut VerificationKeyTypeChoiceCbor> = vec![];
+        if let Some(v) = &mut t.identity_triples {
+            keys.extend(v.iter_mut().flat_map(|r| r.crypto_keys.iter_mut()));
+        }
+        if let Some(v) = &mut t.attest_key_triples {
+            keys.extend(v.iter_mut().flat_map(|r| r.crypto_keys.iter_mut()));
+        }
+        for key in keys {
+            *key = key.to_draft(draft)?;
+        }
+
+        for m in converted.measurements_mut() {
+            if let Some(f) = &mut m.value.flags {
+                *f = f.to_draft(draft)?;
+            }
+        }
+
+        let mode = match draft {
+            CorimDraft::Current => TaggedIntMode::Spec,
+            CorimDraft::Legacy03 => TaggedIntMode::Legacy,
+        };
+        let mut unconverted = vec![];
+        for env in converted.environments_mut() {
+            if let Some(ClassMapCbor { id: Some(id), .. }) = &mut env.class {
+                match id.with_tagged_int_mode(mode) {
+                    Ok(c) => *id = c,
+                    Err(_) => unconverted.push(id.clone()),
+                }
+            }
+        }
+        *self = converted;
+        Ok(unconverted)
+    }
+}
+
 /// The `corim-locator-map` type is defined in [CoRIM Section 2.1.3].
 ///
 /// ```text
@@ -135,6 +299,29 @@ pub struct CorimMap {
     //extensions
 }
 
+impl CorimMap {
+    /// Returns the CoMIDs from `tags`. CoSWIDs are skipped. Returns an error if a tag cannot be
+    /// parsed.
+    pub fn comids(&self) -> Result<Vec<ConciseMidTag>, String> {
+        let mut retval = vec![];
+        for t in &self.tags {
+            let BytesType::Bytes(b) = t;
+            let v: Value = match from_reader(b.as_slice()) {
+                Ok(v) => v,
+                Err(e) => return Err(format!("Failed to parse tag: {:?}", e)),
+            };
+            match v {
+                Value::Tag(505, _) => {}
+                Value::Tag(506, v) => {
+                    retval.push(ConciseMidTag::try_from(ConciseMidTagCbor::try_from(*v)?)?)
+                }
+                _ => return Err("Unrecognized concise-tag-type-choice".to_string()),
+            }
+        }
+        Ok(retval)
+    }
+}
+
 /// The `coswid-triple-record` type is defined in [CoRIM Section 2.2.2].
 ///
 /// ```text
@@ -176,6 +363,49 @@ pub struct CorimSignerMap {
     //extensions
 }
 
+/// The `cwt-claims` type is defined in the current [CoRIM spec], which permits CWT claims to be
+/// used in place of `corim-meta-map` to convey metadata about the signer of a CoRIM.
+///
+/// ```text
+/// cwt-claims = {
+///   &(iss: 1) => tstr
+///   ? &(sub: 2) => tstr
+///   ? &(exp: 4) => int
+///   ? &(nbf: 5) => int
+///   * int => any
+/// }
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct CwtClaimsMap {
+    #[cbor(tag = "1", value = "Text")]
+    pub iss: String,
+    #[cbor(tag = "2", value = "Text")]
+    pub sub: Option<String>,
+    #[cbor(tag = "4", value = "Integer")]
+    pub exp: Option<i64>,
+    #[cbor(tag = "5", value = "Integer")]
+    pub nbf: Option<i64>,
+    #[cbor(value = "Array", cbor = "true")]
+    pub other: Option<Vec<Tuple>>,
+}
+
+impl CwtClaimsMap {
+    /// Returns the validity period expressed by the `nbf` and `exp` claims or None if both are
+    /// absent. When only `nbf` is present, the period has no end and `not_after` is `Time::MAX`.
+    pub fn validity(&self) -> Option<ValidityMap> {
+        if self.nbf.is_none() && self.exp.is_none() {
+            return None;
+        }
+        Some(ValidityMap {
+            not_before: self.nbf,
+            not_after: self.exp.unwrap_or(Time::MAX),
+        })
+    }
+}
+
 /// The `entity-map` type is defined in [CoRIM Section 1.3.2].
 ///
 /// ```text
@@ -240,40 +470,161 @@ pub struct EnvironmentMap {
 /// ```
 ///
 /// [CoRIM Section 3.1.4.1.5.5]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.5.5
-// #[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
-// #[allow(missing_docs)]
-// pub struct FlagsMap {
-//     #[cbor(tag = "0", value = "Bool")]
-//     pub configured: Option<bool>,
-//     #[cbor(tag = "1", value = "Bool")]
-//     pub secure: Option<bool>,
-//     #[cbor(tag = "2", value = "Bool")]
-//     pub recovery: Option<bool>,
-//     #[cbor(tag = "3", value = "Bool")]
-//     pub debug: Option<bool>,
-//     #[cbor(tag = "4", value = "Bool")]
-//     pub replay_protected: Option<bool>,
-//     #[cbor(tag = "5", value = "Bool")]
-//     pub integrity_protected: Option<bool>,
-//     #[cbor(value = "Array", cbor = "true")]
-//     pub other: Option<Vec<Tuple>>,
-// }
-//todo using i8 to align with comid repo output (it is disaligned with corim spec)
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct FlagsMap(i8);
-impl TryFrom<&Value> for FlagsMap {
+#[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct FlagsMap {
+    #[cbor(tag = "0", value = "Bool")]
+    pub configured: Option<bool>,
+    #[cbor(tag = "1", value = "Bool")]
+    pub secure: Option<bool>,
+    #[cbor(tag = "2", value = "Bool")]
+    pub recovery: Option<bool>,
+    #[cbor(tag = "3", value = "Bool")]
+    pub debug: Option<bool>,
+    #[cbor(tag = "4", value = "Bool")]
+    pub replay_protected: Option<bool>,
+    #[cbor(tag = "5", value = "Bool")]
+    pub integrity_protected: Option<bool>,
+    #[cbor(value = "Array", cbor = "true")]
+    pub other: Option<Vec<Tuple>>,
+}
+
+impl FlagsMap {
+    /// Compares `evidence` to this map when used as a reference value. Absent flags and extensions
+    /// are treated as "don't care". Each flag or extension that is present must be present with
+    /// the same value in `evidence`. Returns an error describing the first difference encountered.
+    pub fn compare(&self, evidence: &FlagsMap) -> Result<(), String> {
+        let flags = [
+            ("configured", self.configured, evidence.configured),
+            ("secure", self.secure, evidence.secure),
+            ("recovery", self.recovery, evidence.recovery),
+            ("debug", self.debug, evidence.debug),
+            (
+                "replay-protected",
+                self.replay_protected,
+                evidence.replay_protected,
+            ),
+            (
+                "integrity-protected",
+                self.integrity_protected,
+                evidence.integrity_protected,
+            ),
+        ];
+        for (name, r, e) in flags {
+            if let Some(r) = r {
+                match e {
+                    Some(e) if e == r => {}
+                    Some(e) => {
+                        return Err(format!(
+                            "{} flag mismatch: expected {}, found {}",
+                            name, r, e
+                        ))
+                    }
+                    None => return Err(format!("{} flag absent from evidence", name)),
+                }
+            }
+        }
+        if let Some(ro) = &self.other {
+            for r in ro {
+                let found = match &evidence.other {
+                    Some(eo) => eo.iter().any(|e| e == r),
+                    None => false,
+                };
+                if !found {
+                    return Err(format!("flags extension {:?} mismatch", r.key));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `integrity-registers` type is defined in the current [CoRIM spec].
+///
+/// ```text
+/// integrity-registers = {
+///   + integrity-register-id-type-choice => digests-type
+/// }
+/// ```
+///
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityRegisters(pub BTreeMap<IntegrityRegisterIdTypeChoice, Vec<HashEntry>>);
+
+/// CBOR encoding of [IntegrityRegisters]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntegrityRegistersCbor(pub BTreeMap<IntegrityRegisterIdTypeChoice, Vec<HashEntryCbor>>);
+
+impl TryFrom<IntegrityRegistersCbor> for IntegrityRegisters {
+    type Error = String;
+    fn try_from(value: IntegrityRegistersCbor) -> Result<Self, Self::Error> {
+        IntegrityRegisters::try_from(&value)
+    }
+}
+impl TryFrom<&IntegrityRegistersCbor> for IntegrityRegisters {
+    type Error = String;
+    fn try_from(value: &IntegrityRegistersCbor) -> Result<Self, Self::Error> {
+        let mut retval = BTreeMap::new();
+        for (k, v) in &value.0 {
+            let mut digests = vec![];
+            for d in v {
+                digests.push(HashEntry::try_from(d)?);
+            }
+            retval.insert(k.clone(), digests);
+        }
+        Ok(IntegrityRegisters(retval))
+    }
+}
+impl TryFrom<IntegrityRegisters> for IntegrityRegistersCbor {
+    type Error = String;
+    fn try_from(value: IntegrityRegisters) -> Result<Self, Self::Error> {
+        IntegrityRegistersCbor::try_from(&value)
+    }
+}
+impl TryFrom<&IntegrityRegisters> for IntegrityRegistersCbor {
+    type Error = String;
+    fn try_from(value: &IntegrityRegisters) -> Result<Self, Self::Error> {
+        let mut retval = BTreeMap::new();
+        for (k, v) in &value.0 {
+            let mut digests = vec![];
+            for d in v {
+                digests.push(HashEntryCbor::try_from(d)?);
+            }
+            retval.insert(k.clone(), digests);
+        }
+        Ok(IntegrityRegistersCbor(retval))
+    }
+}
+impl TryFrom<Value> for IntegrityRegistersCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        IntegrityRegistersCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for IntegrityRegistersCbor {
     type Error = String;
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
-        match value {
-            Value::Integer(i) => {
-                let v: i8 = match Integer::try_into(*i) {
-                    Ok(i) => i,
-                    Err(e) => return Err(e.to_string()),
-                };
-                Ok(FlagsMap(v))
+        let entries = match value.as_map() {
+            Some(m) => m,
+            None => return Err("Failed to parse IntegrityRegistersCbor as a map".to_string()),
+        };
+        let mut retval = BTreeMap::new();
+        for (k, v) in entries {
+            let digests = match v.as_array() {
+                Some(a) => a,
+                None => {
+                    return Err(
+                        "Failed to parse IntegrityRegistersCbor digests as an array".to_string()
+                    )
+                }
+            };
+            let mut hes = vec![];
+            for d in digests {
+                hes.push(HashEntryCbor::try_from(d)?);
             }
-            _ => Err("Failed to parse value as a FlagsMap".to_string()),
+            retval.insert(IntegrityRegisterIdTypeChoice::try_from(k)?, hes);
         }
+        Ok(IntegrityRegistersCbor(retval))
     }
 }
 
@@ -333,10 +684,14 @@ pub struct MeasurementMap {
 ///   ? &(ueid: 9) => ueid-type
 ///   ? &(uuid: 10) => uuid-type
 ///   ? &(name: 11) => text
+///   ? &(cryptokeys: 13) => [ + $crypto-key-type-choice ]
+///   ? &(integrity-registers: 14) => integrity-registers
 ///   * $$measurement-values-map-extension
 /// }>
 /// ```
 ///
+/// The `cryptokeys` and `integrity-registers` fields were added in later revisions of the spec.
+///
 /// [CoRIM Section 3.1.4.1.5.2]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4.1.5.2
 #[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
 #[allow(missing_docs)]
@@ -347,14 +702,13 @@ pub struct MeasurementValuesMap {
     pub svn: Option<SvnTypeChoice>,
     #[cbor(tag = "2", value = "Array", cbor = "true")]
     pub digests: Option<Vec<HashEntry>>,
-    #[cbor(tag = "3")]
+    #[cbor(tag = "3", cbor = "true")]
     #[serde(rename = "op-flags")]
-    pub flags: Option<FlagsMap>,
-    //todo raw field support
-    // #[cbor(tag = "4")]
-    // pub raw_value: Option<RawValueTypeChoice>,
-    // #[cbor(tag = "5")]
-    // pub raw_value_mask: Option<RawValueMaskType>,
+    pub flags: Option<FlagsTypeChoice>,
+    #[cbor(tag = "4")]
+    pub raw_value: Option<RawValueTypeChoice>,
+    #[cbor(tag = "5", value = "Bytes")]
+    pub raw_value_mask: Option<RawValueMaskType>,
     #[cbor(tag = "6", value = "Bytes")]
     pub mac_addr: Option<Vec<u8>>,
     #[cbor(tag = "7", value = "Bytes")]
@@ -367,38 +721,75 @@ pub struct MeasurementValuesMap {
     pub uuid: Option<UuidType>,
     #[cbor(tag = "11", value = "Text")]
     pub name: Option<String>,
+    #[cbor(tag = "13", value = "Array")]
+    pub cryptokeys: Option<Vec<CryptoKeyTypeChoice>>,
+    #[cbor(tag = "14", cbor = "true")]
+    #[serde(rename = "integrity-registers")]
+    pub integrity_registers: Option<IntegrityRegisters>,
     #[cbor(value = "Array", cbor = "true")]
     pub other: Option<Vec<Tuple>>,
 }
 
-/// The `protected-corim-header-map` type is defined in [CoRIM Section 2.2.1].
+impl MeasurementValuesMap {
+    /// Compares `evidence` to `raw_value` under the applicable mask, i.e., the mask from a masked
+    /// raw value or else `raw_value_mask`. Returns an error if `raw_value` is absent.
+    pub fn raw_value_matches(&self, evidence: &[u8]) -> Result<bool, String> {
+        match &self.raw_value {
+            Some(rv) => Ok(rv.matches(self.raw_value_mask.as_deref(), evidence)),
+            None => Err("raw-value is absent".to_string()),
+        }
+    }
+}
+
+/// The `protected-corim-header-map` type is defined in [CoRIM Section 2.2.1]. The current [CoRIM spec]
+/// uses the "application/rim+cbor" content type (see [CORIM_CONTENT_TYPE](crate::CORIM_CONTENT_TYPE))
+/// and permits CWT claims to be used in place of `corim-meta-map`.
 ///
 /// ```text
 /// protected-corim-header-map = {
 ///   &(alg-id: 1) => int
-///   &(content-type: 3) => "application/corim-unsigned+cbor"
+///   &(content-type: 3) => "application/rim+cbor"
 ///   &(issuer-key-id: 4) => bstr
-///   &(corim-meta: 8) => bstr .cbor corim-meta-map
+///   meta-group
 ///   * cose-label => cose-value
 /// }
+///
+/// meta-group = (
+///   &(corim-meta: 8) => bstr .cbor corim-meta-map //
+///   &(CWT-Claims: 15) => cwt-claims
+/// )
 /// ```
 ///
 /// [CoRIM Section 2.2.1]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-2.2.1
+/// [CoRIM spec]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-corim
 #[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct ProtectedCorimHeaderMap {
-    #[cbor(tag = "0", value = "Integer")]
-    pub alg_id: u64,
+    #[cbor(tag = "1", value = "Integer")]
+    pub alg_id: i64,
     #[cbor(tag = "3", value = "Text")]
-    content_type: String,
+    pub content_type: String,
     #[cbor(tag = "4", value = "Bytes")]
     pub issuer_key_id: Vec<u8>,
     #[cbor(tag = "8", value = "Map", cbor = "true")]
-    pub meta: CorimMetaMap,
+    pub meta: Option<CorimMetaMap>,
+    #[cbor(tag = "15", value = "Map", cbor = "true")]
+    pub cwt_claims: Option<CwtClaimsMap>,
     // todo CoseValues
     //pub cose_label: CoseValues
 }
 
+impl ProtectedCorimHeaderMap {
+    /// Returns the validity period of the signature, i.e., `signature-validity` from the
+    /// `corim-meta-map` or, failing that, the validity period expressed by the CWT claims.
+    pub fn signer_validity(&self) -> Option<ValidityMap> {
+        if let Some(v) = self.meta.as_ref().and_then(|m| m.validity.clone()) {
+            return Some(v);
+        }
+        self.cwt_claims.as_ref().and_then(|c| c.validity())
+    }
+}
+
 /// The `coswid-triple-record` type is defined in [CoRIM Section 3.1.1].
 ///
 /// ```text
@@ -433,10 +824,15 @@ pub struct TagIdentityMap {
 ///   ? &(dependency-triples: 4) => [ + domain-dependency-triple-record ]
 ///   ? &(membership-triples: 5) => [ + domain-membership-triple-record ]
 ///   ? &(coswid-triples: 6) => [ + coswid-triple-record ]
+///   ? &(conditional-endorsement-series-triples: 8) =>
+///       [ + conditional-endorsement-series-triple-record ]
+///   ? &(conditional-endorsement-triples: 10) => [ + conditional-endorsement-triple-record ]
 ///   * $$triples-map-extension
 /// }>
 /// ```
 ///
+/// The conditional endorsement triples were added in later revisions of the spec.
+///
 /// [CoRIM Section 3.1.4]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.4
 #[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
 #[allow(missing_docs)]
@@ -456,6 +852,11 @@ pub struct TriplesMap {
     pub membership_triples: Option<Vec<DomainDependencyTripleRecord>>,
     #[cbor(tag = "6", value = "Array", cbor = "true")]
     pub coswid_triples: Option<Vec<CoswidTripleRecord>>,
+    #[cbor(tag = "8", value = "Array", cbor = "true")]
+    pub conditional_endorsement_series_triples:
+        Option<Vec<ConditionalEndorsementSeriesTripleRecord>>,
+    #[cbor(tag = "10", value = "Array", cbor = "true")]
+    pub conditional_endorsement_triples: Option<Vec<ConditionalEndorsementTripleRecord>>,
     #[cbor(value = "Array", cbor = "true")]
     pub other: Option<Vec<Tuple>>,
 }
@@ -479,9 +880,37 @@ pub struct ValidityMap {
     pub not_after: Time,
 }
 
-// todo - the corim sample uses this struct still
+impl ValidityMap {
+    /// Returns the status of the validity period at `now`. Both bounds are inclusive.
+    pub fn status(&self, now: Time) -> ValidityStatus {
+        match self.not_before {
+            Some(nb) if now < nb => ValidityStatus::NotYetValid(nb),
+            _ if now > self.not_after => ValidityStatus::Expired(self.not_after),
+            _ => ValidityStatus::Valid,
+        }
+    }
+
+    /// Returns the period during which both `self` and `other` are valid or None if the periods do
+    /// not overlap.
+    pub fn intersect(&self, other: &ValidityMap) -> Option<ValidityMap> {
+        let not_before = match (self.not_before, other.not_before) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let not_after = self.not_after.min(other.not_after);
+        match not_before {
+            Some(nb) if nb > not_after => None,
+            _ => Some(ValidityMap {
+                not_before,
+                not_after,
+            }),
+        }
+    }
+}
+
 /// The `verification-key-map` type is not defined in the current CoRIM but is used
-/// in samples generated by the reference implementation (it had been in -02).
+/// in samples generated by the reference implementation (it had been in -02). See
+/// [VerificationKeyTypeChoice].
 ///
 /// ```text
 ///    verification-key-map = {
@@ -517,3 +946,77 @@ pub struct VersionMap {
     #[cbor(tag = "1")]
     pub version_scheme: Option<VersionScheme>,
 }
+
+impl VersionMap {
+    /// Parses `version` according to `version_scheme`, which is treated as multipartnumeric when
+    /// absent.
+    pub fn parse_version(&self) -> Result<Version, String> {
+        let scheme = effective_version_scheme(self.version_scheme.as_ref())?;
+        Version::parse(&scheme, &self.version)
+    }
+
+    /// Compares this version to `other`. An error is returned if either version cannot be parsed
+    /// or if the two versions use different version schemes.
+    pub fn compare(&self, other: &VersionMap) -> Result<Ordering, String> {
+        compare(
+            &self.version,
+            self.version_scheme.as_ref(),
+            &other.version,
+            other.version_scheme.as_ref(),
+        )
+    }
+
+    /// Returns true if this version is greater than or equal to `min_version`, which is parsed
+    /// using the same version scheme as this version.
+    pub fn is_at_least(&self, min_version: &str) -> Result<bool, String> {
+        is_at_least(&self.version, self.version_scheme.as_ref(), min_version)
+    }
+}
+
+impl VersionMapCbor {
+    /// Parses `version` according to `version_scheme`, which is treated as multipartnumeric when
+    /// absent.
+    pub fn parse_version(&self) -> Result<Version, String> {
+        let scheme = effective_version_scheme(self.version_scheme.as_ref())?;
+        Version::parse(&scheme, &self.version)
+    }
+
+    /// Compares this version to `other`. An error is returned if either version cannot be parsed
+    /// or if the two versions use different version schemes.
+    pub fn compare(&self, other: &VersionMapCbor) -> Result<Ordering, String> {
+        compare(
+            &self.version,
+            self.version_scheme.as_ref(),
+            &other.version,
+            other.version_scheme.as_ref(),
+        )
+    }
+
+    /// Returns true if this version is greater than or equal to `min_version`, which is parsed
+    /// using the same version scheme as this version.
+    pub fn is_at_least(&self, min_version: &str) -> Result<bool, String> {
+        is_at_least(&self.version, self.version_scheme.as_ref(), min_version)
+    }
+}
+
+fn compare(
+    version: &str,
+    scheme: Option<&VersionScheme>,
+    other_version: &str,
+    other_scheme: Option<&VersionScheme>,
+) -> Result<Ordering, String> {
+    let scheme = effective_version_scheme(scheme)?;
+    if scheme != effective_version_scheme(other_scheme)? {
+        return Err("Versions use different version schemes".to_string());
+    }
+    compare_versions(&scheme, version, other_version)
+}
+
+fn is_at_least(
+    version: &str,
+    scheme: Option<&VersionScheme>,
+    min_version: &str,
+) -> Result<bool, String> {
+    let scheme = effective_version_scheme(scheme)?;
+    Ok(compare_versions(&scheme, version, min_version)? != Ordering::Less)
+}
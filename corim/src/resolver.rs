@@ -0,0 +1,407 @@
+//! Resolution of the dependent RIMs named by the `dependent-rims` field of a CoRIM
+//!
+//! Each `corim-locator-map` names the location of a CoRIM upon which a CoRIM depends and,
+//! optionally, the thumbprint of the encoded CoRIM (see [CoRIM Section 2.1.3]). A [RimResolver]
+//! retrieves each dependent CoRIM using the [RimFetcher] registered for the scheme of its `href`,
+//! verifies the thumbprint, if present, then recursively resolves the dependencies of the retrieved
+//! CoRIM. A CoRIM that is named by more than one CoRIM is retrieved once.
+//!
+//! A relative `href` is resolved against the `href` of the CoRIM that names it, e.g., `b.cbor` named
+//! by `https://rims.example/rims/a.cbor` is retrieved from `https://rims.example/rims/b.cbor`.
+//! Relative `href` values in a CoRIM passed to [RimResolver::resolve] are passed to the fetcher
+//! as-is. `.` and `..` segments are removed from each `href` before it is compared to the `href`
+//! values of the CoRIMs already retrieved.
+//!
+//! A CoRIM retrieved using a scheme other than `file` may not name a CoRIM in the local file system,
+//! i.e., a `file` URI, unless [RimResolver::with_local_from_remote] is used to allow it.
+//!
+//! A [FileFetcher] is registered for the `file` scheme (and for `href` values without a scheme)
+//! when the `std` feature is enabled. Fetchers for other schemes, i.e., `https`, must be supplied by
+//! the caller.
+//!
+//! [CoRIM Section 2.1.3]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-2.1.3
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use ciborium::de::from_reader;
+use ciborium::value::Value;
+
+use crate::maps::*;
+
+/// Default limit on the length of a chain of dependent RIMs
+pub const DEFAULT_MAX_DEPTH: usize = 16;
+
+/// Retrieves the encoded CoRIM located by an `href` from a `corim-locator-map`.
+pub trait RimFetcher {
+    /// Returns the encoded CoRIM located by `href`.
+    fn fetch(&self, href: &str) -> Result<Vec<u8>, String>;
+}
+
+/// [RimFetcher] that reads CoRIMs from the local file system. `file://` URIs and paths without a
+/// scheme are supported. Relative paths are interpreted relative to the base directory, if any.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+pub struct FileFetcher {
+    base_dir: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "std")]
+impl FileFetcher {
+    /// Creates a fetcher that interprets relative paths relative to the current directory.
+    pub fn new() -> Self {
+        FileFetcher::default()
+    }
+
+    /// Creates a fetcher that interprets relative paths relative to `base_dir`.
+    pub fn with_base_dir<P: Into<std::path::PathBuf>>(base_dir: P) -> Self {
+        FileFetcher {
+            base_dir: Some(base_dir.into()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl RimFetcher for FileFetcher {
+    fn fetch(&self, href: &str) -> Result<Vec<u8>, String> {
+        let path = match href.strip_prefix("file://") {
+            // file://localhost/path and file:///path are equivalent
+            Some(p) => p.strip_prefix("localhost").unwrap_or(p),
+            None => href.strip_prefix("file:").unwrap_or(href),
+        };
+        let path = std::path::Path::new(path);
+        let path = match &self.base_dir {
+            Some(base) if path.is_relative() => base.join(path),
+            _ => path.to_path_buf(),
+        };
+        std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+    }
+}
+
+/// A dependent CoRIM retrieved by a [RimResolver].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResolvedRim {
+    /// `href` from the `corim-locator-map` that named the CoRIM, resolved against the `href` of the
+    /// CoRIM that contained the locator if relative and with `.` and `..` segments removed
+    pub href: String,
+    /// Encoded CoRIM as retrieved
+    pub encoded: Vec<u8>,
+    /// Decoded CoRIM
+    pub corim: CorimMapCbor,
+    /// True if the CoRIM was verified against a thumbprint
+    pub thumbprint_verified: bool,
+}
+
+/// A CoRIM and each CoRIM upon which it directly or indirectly depends.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorimBundle {
+    /// CoRIM whose dependencies were resolved
+    pub root: CorimMapCbor,
+    /// Dependent CoRIMs in the order in which they were retrieved, i.e., depth first
+    pub dependencies: Vec<ResolvedRim>,
+}
+
+impl CorimBundle {
+    /// Returns the root CoRIM followed by each dependent CoRIM.
+    pub fn corims(&self) -> Vec<&CorimMapCbor> {
+        let mut retval = Vec::with_capacity(self.dependencies.len() + 1);
+        retval.push(&self.root);
+        retval.extend(self.dependencies.iter().map(|d| &d.corim));
+        retval
+    }
+
+    /// Returns the dependent CoRIM retrieved from `href`, if any.
+    pub fn get(&self, href: &str) -> Option<&ResolvedRim> {
+        self.dependencies.iter().find(|d| d.href == href)
+    }
+}
+
+/// Recursively retrieves and verifies the dependent RIMs of a CoRIM.
+pub struct RimResolver {
+    fetchers: BTreeMap<String, Box<dyn RimFetcher>>,
+    max_depth: usize,
+    local_from_remote: bool,
+}
+
+impl Default for RimResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RimResolver {
+    /// Creates a resolver with a [FileFetcher] registered for the `file` scheme when the `std`
+    /// feature is enabled and with no fetchers otherwise.
+    pub fn new() -> Self {
+        #[allow(unused_mut)]
+        let mut fetchers: BTreeMap<String, Box<dyn RimFetcher>> = BTreeMap::new();
+        #[cfg(feature = "std")]
+        fetchers.insert("file".to_string(), Box::new(FileFetcher::new()));
+        RimResolver {
+            fetchers,
+            max_depth: DEFAULT_MAX_DEPTH,
+            local_from_remote: false,
+        }
+    }
+
+    /// Registers `fetcher` for `scheme`, replacing any fetcher previously registered for the
+    /// scheme. Use the `file` scheme to replace the fetcher used for `href` values without a scheme.
+    pub fn with_fetcher<F: RimFetcher + 'static>(mut self, scheme: &str, fetcher: F) -> Self {
+        self.fetchers
+            .insert(scheme.to_ascii_lowercase(), Box::new(fetcher));
+        self
+    }
+
+    /// Sets the maximum length of a chain of dependent RIMs, not counting the CoRIM whose
+    /// dependencies are resolved.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets whether a CoRIM retrieved using a scheme other than `file` may name a CoRIM in the local
+    /// file system. This is not allowed by default so that a remote CoRIM cannot cause local files
+    /// to be read.
+    pub fn with_local_from_remote(mut self, allow: bool) -> Self {
+        self.local_from_remote = allow;
+        self
+    }
+
+    /// Retrieves the CoRIM located by `href` then resolves its dependencies.
+    pub fn resolve_href(&self, href: &str) -> Result<CorimBundle, String> {
+        let root = decode_corim(&self.fetch(href)?)?;
+        self.resolve_from(root, Some(href))
+    }
+
+    /// Resolves the dependencies of `root`.
+    pub fn resolve(&self, root: &CorimMapCbor) -> Result<CorimBundle, String> {
+        self.resolve_from(root.clone(), None)
+    }
+
+    fn resolve_from(&self, root: CorimMapCbor, href: Option<&str>) -> Result<CorimBundle, String> {
+        let href = href.map(remove_dot_segments);
+        let mut path = Vec::new();
+        if let Some(href) = &href {
+            path.push(href.clone());
+        }
+        let mut visited = BTreeSet::new();
+        let mut dependencies = Vec::new();
+        self.resolve_dependencies(
+            &root,
+            href.as_deref(),
+            0,
+            &mut path,
+            &mut visited,
+            &mut dependencies,
+        )?;
+        Ok(CorimBundle { root, dependencies })
+    }
+
+    /// Resolves the dependencies of `corim`, which was retrieved from `base`, if known, and is
+    /// `depth` links below the root. `path` holds the `href` of each CoRIM from the root to `corim`.
+    fn resolve_dependencies(
+        &self,
+        corim: &CorimMapCbor,
+        base: Option<&str>,
+        depth: usize,
+        path: &mut Vec<String>,
+        visited: &mut BTreeSet<String>,
+        dependencies: &mut Vec<ResolvedRim>,
+    ) -> Result<(), String> {
+        let locators = match &corim.dependent_rims {
+            Some(locators) => locators,
+            None => return Ok(()),
+        };
+        for locator in locators {
+            let href =
+                remove_dot_segments(&resolve_relative(base, &String::try_from(&locator.href)?));
+            if let Some(base) = base {
+                if !self.local_from_remote && !is_local(base) && is_local(&href) {
+                    return Err(format!(
+                        "Dependent RIM {} is a local file named by remote CoRIM {}",
+                        href, base
+                    ));
+                }
+            }
+            if path.contains(&href) {
+                return Err(format!("Dependent RIMs form a cycle at {}", href));
+            }
+            if visited.contains(&href) {
+                let prior = dependencies.iter().find(|d| d.href == href);
+                if let (Some(prior), Some(thumbprint)) = (prior, &locator.thumbprint) {
+                    if !thumbprint.verify(&prior.encoded)? {
+                        return Err(format!("Thumbprint mismatch for {}", href));
+                    }
+                }
+                continue;
+            }
+            if depth >= self.max_depth {
+                return Err(format!(
+                    "Dependent RIM {} exceeds maximum depth of {}",
+                    href, self.max_depth
+                ));
+            }
+
+            let encoded = self.fetch(&href)?;
+            let thumbprint_verified = match &locator.thumbprint {
+                Some(thumbprint) => {
+                    if !thumbprint.verify(&encoded)? {
+                        return Err(format!("Thumbprint mismatch for {}", href));
+                    }
+                    true
+                }
+                None => false,
+            };
+            let dependency = decode_corim(&encoded)?;
+
+            visited.insert(href.clone());
+            path.push(href.clone());
+            dependencies.push(ResolvedRim {
+                href: href.clone(),
+                encoded,
+                corim: dependency.clone(),
+                thumbprint_verified,
+            });
+            self.resolve_dependencies(
+                &dependency,
+                Some(&href),
+                depth + 1,
+                path,
+                visited,
+                dependencies,
+            )?;
+            path.pop();
+        }
+        Ok(())
+    }
+
+    fn fetch(&self, href: &str) -> Result<Vec<u8>, String> {
+        let scheme = effective_scheme(href);
+        match self.fetchers.get(&scheme) {
+            Some(fetcher) => fetcher.fetch(href),
+            None => Err(format!("No fetcher registered for {} scheme", scheme)),
+        }
+    }
+}
+
+/// Returns the scheme of `href` in lower case. Single character schemes are not recognized so that
+/// Windows drive letters are not mistaken for schemes.
+fn scheme(href: &str) -> Option<String> {
+    let (scheme, _) = href.split_once(':')?;
+    let mut chars = scheme.chars();
+    let first = chars.next()?;
+    if scheme.len() > 1
+        && first.is_ascii_alphabetic()
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    {
+        Some(scheme.to_ascii_lowercase())
+    } else {
+        None
+    }
+}
+
+/// Returns the scheme of `href` in lower case, or `file` if `href` has no scheme.
+fn effective_scheme(href: &str) -> String {
+    scheme(href).unwrap_or_else(|| "file".to_string())
+}
+
+/// Returns true if `href` names a file in the local file system.
+fn is_local(href: &str) -> bool {
+    effective_scheme(href) == "file"
+}
+
+/// Removes `.` and `..` segments from the path of `href`, as described in [RFC 3986 Section 5.2.4].
+/// Leading `..` segments of a relative path are retained.
+///
+/// [RFC 3986 Section 5.2.4]: https://datatracker.ietf.org/doc/html/rfc3986#section-5.2.4
+fn remove_dot_segments(href: &str) -> String {
+    // split off the scheme and authority, if any, and the query and fragment, if any
+    let (prefix, rest) = match scheme(href) {
+        Some(_) => {
+            let (scheme, rest) = href.split_once(':').unwrap_or(("", href));
+            match rest.strip_prefix("//") {
+                Some(rest) => {
+                    let end = rest.find('/').unwrap_or(rest.len());
+                    (format!("{}://{}", scheme, &rest[..end]), &rest[end..])
+                }
+                None => (format!("{}:", scheme), rest),
+            }
+        }
+        None => (String::new(), href),
+    };
+    let (path, suffix) = rest.split_at(rest.find(['?', '#']).unwrap_or(rest.len()));
+
+    let absolute = path.starts_with('/');
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.strip_prefix('/').unwrap_or(path).split('/') {
+        match segment {
+            "." => {}
+            ".." => match segments.last() {
+                Some(last) if *last != ".." => {
+                    segments.pop();
+                }
+                _ if absolute => {}
+                _ => segments.push(segment),
+            },
+            _ => segments.push(segment),
+        }
+    }
+    format!(
+        "{}{}{}{}",
+        prefix,
+        if absolute { "/" } else { "" },
+        segments.join("/"),
+        suffix
+    )
+}
+
+/// Resolves `href` against `base` if `href` is relative, i.e., has no scheme. A path that starts with
+/// `/` replaces the path of `base`. Other relative paths replace the last segment of the path of
+/// `base`.
+fn resolve_relative(base: Option<&str>, href: &str) -> String {
+    let base = match base {
+        Some(base) if scheme(href).is_none() => base,
+        _ => return href.to_string(),
+    };
+    if href.starts_with('/') {
+        return match scheme(base) {
+            Some(_) => {
+                // keep the scheme and authority, if any, of the base
+                let (scheme, rest) = base.split_once(':').unwrap_or(("", base));
+                match rest.strip_prefix("//") {
+                    Some(rest) => {
+                        let authority = rest.split('/').next().unwrap_or("");
+                        format!("{}://{}{}", scheme, authority, href)
+                    }
+                    None => format!("{}:{}", scheme, href),
+                }
+            }
+            None => href.to_string(),
+        };
+    }
+    match base.rfind('/') {
+        Some(i) => format!("{}{}", &base[..=i], href),
+        None => href.to_string(),
+    }
+}
+
+/// Decodes an unsigned CoRIM, with or without the `#6.500` and `#6.501` tags. Signed CoRIMs are not
+/// accepted because the signature must be verified by the caller.
+pub fn decode_corim(encoded: &[u8]) -> Result<CorimMapCbor, String> {
+    let mut v: Value = match from_reader(encoded) {
+        Ok(v) => v,
+        Err(e) => return Err(format!("Failed to parse CoRIM: {:?}", e)),
+    };
+    if let Value::Tag(500, inner) = v {
+        v = *inner;
+    }
+    match v {
+        Value::Tag(501, inner) => CorimMapCbor::try_from(*inner),
+        Value::Tag(502, _) => Err("Signed CoRIMs are not supported".to_string()),
+        Value::Map(_) => CorimMapCbor::try_from(v),
+        _ => Err("Failed to parse value as a CorimMapCbor".to_string()),
+    }
+}
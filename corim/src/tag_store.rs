@@ -0,0 +1,424 @@
+//! Indexing of CoMIDs and CoSWIDs by tag ID and version with resolution of tag relationships
+//!
+//! A [TagStore] retains each version of each tag it is given. Only the highest version of a tag is
+//! considered when resolving relationships (see [CoRIM Section 3.1.1.2]). Relationships are taken
+//! from the `linked-tags` field of a CoMID (see [CoRIM Section 3.1.3]) and from `supersedes`,
+//! `supplemental` and `patches` links of a CoSWID whose `href` uses the `swid:` scheme.
+//!
+//! A tag is in force unless it is replaced by another tag in the store, it is a supplement to a tag
+//! that is not in force or it is part of a cycle of relationships. Replacement is transitive, i.e.,
+//! when C replaces B and B replaces A, only C is in force.
+//!
+//! [CoRIM Section 3.1.1.2]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.1.2
+//! [CoRIM Section 3.1.3]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-3.1.3
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use ciborium::de::from_reader;
+use ciborium::value::Value;
+
+use crate::arrays::*;
+use crate::choices::*;
+use crate::maps::*;
+use common::{BytesType, TextOrBinary, UuidType};
+use coswid::choices::{Rel, RelKnown};
+use coswid::maps::*;
+
+/// Tag ID used to index a [TagStore]. Text tag IDs and binary tag IDs (i.e., UUIDs) are distinct.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum TagKey {
+    /// Text tag ID
+    Text(String),
+    /// Binary tag ID, i.e., the 16 bytes of a UUID
+    Bytes(Vec<u8>),
+}
+
+impl From<&TagIdTypeChoice> for TagKey {
+    fn from(id: &TagIdTypeChoice) -> Self {
+        match id {
+            TagIdTypeChoice::Str(s) => TagKey::Text(s.clone()),
+            TagIdTypeChoice::Uuid(UuidType::Uuid(u)) => TagKey::Bytes(u.clone()),
+        }
+    }
+}
+
+impl From<&TextOrBinary> for TagKey {
+    fn from(id: &TextOrBinary) -> Self {
+        match id {
+            TextOrBinary::Text(s) => TagKey::Text(s.clone()),
+            TextOrBinary::Binary(b) => TagKey::Bytes(b.clone()),
+        }
+    }
+}
+
+/// A tag retained by a [TagStore].
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+#[allow(clippy::large_enum_variant)]
+pub enum StoredTag {
+    Comid(ConciseMidTag),
+    Coswid(ConciseSwidTag),
+}
+
+/// Relationship from one tag to another.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TagRelation {
+    /// The source tag supplements the target tag and is in force only while the target is
+    Supplements,
+    /// The source tag replaces the target tag
+    Replaces,
+}
+
+/// Problem encountered while resolving tag relationships.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LinkIssue {
+    /// A tag refers to a tag that is not present in the store
+    Dangling {
+        /// Tag containing the link
+        from: TagKey,
+        /// Tag that was not found
+        to: TagKey,
+        /// Relationship expressed by the link
+        relation: TagRelation,
+    },
+    /// The listed tags form a cycle of relationships. None of these tags are in force.
+    Cycle(Vec<TagKey>),
+}
+
+/// Result of resolving the relationships between the tags in a [TagStore].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Resolution {
+    /// Tags that are in force, with the version of each
+    pub in_force: BTreeMap<TagKey, u64>,
+    /// Tags that are not in force, paired with the tag that replaced each (or, for supplements
+    /// whose target is not in force, the target)
+    pub superseded: BTreeMap<TagKey, TagKey>,
+    /// Dangling links and cycles
+    pub issues: Vec<LinkIssue>,
+}
+
+impl Resolution {
+    /// Returns true if `key` is in force.
+    pub fn is_in_force(&self, key: &TagKey) -> bool {
+        self.in_force.contains_key(key)
+    }
+}
+
+/// Collection of CoMIDs and CoSWIDs indexed by tag ID and version.
+#[derive(Clone, Debug, Default)]
+pub struct TagStore {
+    tags: BTreeMap<TagKey, BTreeMap<u64, StoredTag>>,
+}
+
+impl TagStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a CoMID. An error is returned if the CoMID has no tag identity. A tag with the same
+    /// tag ID and version as a tag already in the store replaces it.
+    pub fn insert_comid(&mut self, comid: ConciseMidTag) -> Result<(), String> {
+        let (key, version) = match &comid.tag_identity {
+            Some(ti) => (
+                TagKey::from(&ti.tag_id),
+                match ti.tag_version {
+                    Some(TagVersionType::U64(v)) => v,
+                    None => 0,
+                },
+            ),
+            None => return Err("CoMID has no tag identity".to_string()),
+        };
+        self.tags
+            .entry(key)
+            .or_default()
+            .insert(version, StoredTag::Comid(comid));
+        Ok(())
+    }
+
+    /// Adds a CoSWID. An error is returned if the tag version is negative.
+    pub fn insert_coswid(&mut self, coswid: ConciseSwidTag) -> Result<(), String> {
+        let version = match u64::try_from(coswid.tag_version) {
+            Ok(v) => v,
+            Err(_) => {
+                return Err(format!(
+                    "Invalid CoSWID tag version: {}",
+                    coswid.tag_version
+                ))
+            }
+        };
+        self.tags
+            .entry(TagKey::from(&coswid.tag_id))
+            .or_default()
+            .insert(version, StoredTag::Coswid(coswid));
+        Ok(())
+    }
+
+    /// Adds each CoMID and CoSWID in the `tags` field of `corim`.
+    pub fn insert_corim(&mut self, corim: &CorimMapCbor) -> Result<(), String> {
+        for t in &corim.tags {
+            let BytesType::Bytes(b) = t;
+            let v: Value = match from_reader(b.as_slice()) {
+                Ok(v) => v,
+                Err(e) => return Err(format!("Failed to parse tag: {:?}", e)),
+            };
+            match v {
+                Value::Tag(505, v) => {
+                    let c = ConciseSwidTagCbor::try_from(*v)?;
+                    self.insert_coswid(ConciseSwidTag::try_from(c)?)?;
+                }
+                Value::Tag(506, v) => {
+                    let c = ConciseMidTagCbor::try_from(*v)?;
+                    self.insert_comid(ConciseMidTag::try_from(c)?)?;
+                }
+                _ => return Err("Unrecognized concise-tag-type-choice".to_string()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the tag with the given ID and version or, if `version` is None, the highest
+    /// version of the tag.
+    pub fn get(&self, key: &TagKey, version: Option<u64>) -> Option<&StoredTag> {
+        let versions = self.tags.get(key)?;
+        match version {
+            Some(v) => versions.get(&v),
+            None => versions.values().next_back(),
+        }
+    }
+
+    /// Returns the versions present for the given tag ID in ascending order.
+    pub fn versions(&self, key: &TagKey) -> Vec<u64> {
+        match self.tags.get(key) {
+            Some(versions) => versions.keys().copied().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Returns the number of distinct tag IDs in the store.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Returns true if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    fn latest(&self) -> BTreeMap<&TagKey, (u64, &StoredTag)> {
+        self.tags
+            .iter()
+            .filter_map(|(k, versions)| versions.iter().next_back().map(|(v, t)| (k, (*v, t))))
+            .collect()
+    }
+
+    // Finds the key for a link target, allowing swid: links to name UUID tag IDs in text form.
+    fn find_key(&self, key: TagKey) -> TagKey {
+        if self.tags.contains_key(&key) {
+            return key;
+        }
+        if let TagKey::Text(s) = &key {
+            if let Some(u) = parse_uuid(s) {
+                let alt = TagKey::Bytes(u);
+                if self.tags.contains_key(&alt) {
+                    return alt;
+                }
+            }
+        }
+        key
+    }
+
+    /// Resolves the relationships among the highest version of each tag in the store.
+    pub fn resolve(&self) -> Resolution {
+        let latest = self.latest();
+        let mut retval = Resolution::default();
+
+        let mut edges: Vec<(TagKey, TagKey, TagRelation)> = vec![];
+        for (k, (_, t)) in &latest {
+            for (to, relation) in relations(t) {
+                let to = self.find_key(to);
+                if latest.contains_key(&to) {
+                    edges.push(((*k).clone(), to, relation));
+                } else {
+                    retval.issues.push(LinkIssue::Dangling {
+                        from: (*k).clone(),
+                        to,
+                        relation,
+                    });
+                }
+            }
+        }
+
+        let cyclic = find_cycles(&edges, &mut retval.issues);
+
+        // a tag is replaced if any tag that is not part of a cycle replaces it
+        let mut replaced: BTreeMap<&TagKey, &TagKey> = BTreeMap::new();
+        for (from, to, relation) in &edges {
+            if *relation == TagRelation::Replaces && !cyclic.contains(from) {
+                replaced.entry(to).or_insert(from);
+            }
+        }
+
+        let mut memo: BTreeMap<TagKey, bool> = BTreeMap::new();
+        for k in latest.keys() {
+            in_force(k, &edges, &cyclic, &replaced, &mut memo);
+        }
+        for (k, (version, _)) in &latest {
+            if memo.get(*k) == Some(&true) {
+                retval.in_force.insert((*k).clone(), *version);
+            } else if let Some(by) = replaced.get(*k) {
+                retval.superseded.insert((*k).clone(), (*by).clone());
+            } else if let Some((_, to, _)) = edges
+                .iter()
+                .find(|(from, _, r)| from == *k && *r == TagRelation::Supplements)
+            {
+                retval.superseded.insert((*k).clone(), to.clone());
+            }
+        }
+        retval
+    }
+
+    /// Returns the CoMIDs that are in force.
+    pub fn effective_comids(&self) -> Vec<&ConciseMidTag> {
+        let resolution = self.resolve();
+        resolution
+            .in_force
+            .iter()
+            .filter_map(|(k, v)| match self.get(k, Some(*v)) {
+                Some(StoredTag::Comid(c)) => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the reference value triples from the CoMIDs that are in force.
+    pub fn effective_reference_values(&self) -> Vec<&ReferenceTripleRecord> {
+        self.effective_comids()
+            .into_iter()
+            .filter_map(|c| c.triples.reference_triples.as_ref())
+            .flatten()
+            .collect()
+    }
+}
+
+fn relations(tag: &StoredTag) -> Vec<(TagKey, TagRelation)> {
+    let mut retval = vec![];
+    match tag {
+        StoredTag::Comid(c) => {
+            for lt in c.linked_tags.iter().flatten() {
+                let relation = match &lt.tag_rel {
+                    TagRelTypeChoice::Known(TagRelTypeChoiceKnown::Supplements) => {
+                        TagRelation::Supplements
+                    }
+                    TagRelTypeChoice::Known(TagRelTypeChoiceKnown::Replaces) => {
+                        TagRelation::Replaces
+                    }
+                    TagRelTypeChoice::Extensions(_) => continue,
+                };
+                retval.push((TagKey::from(&lt.linked_tag_id), relation));
+            }
+        }
+        StoredTag::Coswid(c) => {
+            let links = match &c.link {
+                Some(OneOrMoreLinkEntry::One(l)) => vec![l],
+                Some(OneOrMoreLinkEntry::More(v)) => v.iter().collect(),
+                None => vec![],
+            };
+            for l in links {
+                let relation = match &l.rel {
+                    Rel::Known(RelKnown::Supersedes) => TagRelation::Replaces,
+                    Rel::Known(RelKnown::Supplemental) | Rel::Known(RelKnown::Patches) => {
+                        TagRelation::Supplements
+                    }
+                    _ => continue,
+                };
+                if let Some(id) = l.href.strip_prefix("swid:") {
+                    retval.push((TagKey::Text(id.to_string()), relation));
+                }
+            }
+        }
+    }
+    retval
+}
+
+fn in_force(
+    key: &TagKey,
+    edges: &[(TagKey, TagKey, TagRelation)],
+    cyclic: &BTreeSet<TagKey>,
+    replaced: &BTreeMap<&TagKey, &TagKey>,
+    memo: &mut BTreeMap<TagKey, bool>,
+) -> bool {
+    if let Some(r) = memo.get(key) {
+        return *r;
+    }
+    let mut r = !cyclic.contains(key) && !replaced.contains_key(key);
+    if r {
+        // cycles were excluded above so the recursion terminates
+        for (_, to, _) in edges
+            .iter()
+            .filter(|(from, _, rel)| from == key && *rel == TagRelation::Supplements)
+        {
+            if !in_force(to, edges, cyclic, replaced, memo) {
+                r = false;
+            }
+        }
+    }
+    memo.insert(key.clone(), r);
+    r
+}
+
+// Depth-first search for cycles. Each cycle found is reported once and its members returned.
+fn find_cycles(
+    edges: &[(TagKey, TagKey, TagRelation)],
+    issues: &mut Vec<LinkIssue>,
+) -> BTreeSet<TagKey> {
+    fn visit<'a>(
+        key: &'a TagKey,
+        edges: &'a [(TagKey, TagKey, TagRelation)],
+        stack: &mut Vec<&'a TagKey>,
+        done: &mut BTreeSet<&'a TagKey>,
+        cycles: &mut Vec<Vec<TagKey>>,
+    ) {
+        if done.contains(key) {
+            return;
+        }
+        if let Some(pos) = stack.iter().position(|k| *k == key) {
+            cycles.push(stack[pos..].iter().map(|k| (*k).clone()).collect());
+            return;
+        }
+        stack.push(key);
+        for (_, to, _) in edges.iter().filter(|(from, _, _)| from == key) {
+            visit(to, edges, stack, done, cycles);
+        }
+        stack.pop();
+        done.insert(key);
+    }
+
+    let mut cycles = vec![];
+    let mut done = BTreeSet::new();
+    for (from, _, _) in edges {
+        let mut stack = vec![];
+        visit(from, edges, &mut stack, &mut done, &mut cycles);
+    }
+    let mut retval = BTreeSet::new();
+    for c in cycles {
+        retval.extend(c.iter().cloned());
+        issues.push(LinkIssue::Cycle(c));
+    }
+    retval
+}
+
+fn parse_uuid(s: &str) -> Option<Vec<u8>> {
+    let hex: Vec<u8> = s.bytes().filter(|b| *b != b'-').collect();
+    if s.len() != 36 || hex.len() != 32 {
+        return None;
+    }
+    let mut retval = Vec::with_capacity(16);
+    for pair in hex.chunks(2) {
+        let digits = core::str::from_utf8(pair).ok()?;
+        retval.push(u8::from_str_radix(digits, 16).ok()?);
+    }
+    Some(retval)
+}
@@ -0,0 +1,99 @@
+//! Evaluation of CoRIM validity periods
+//!
+//! A CoRIM may carry a `rim-validity` period (see [CoRIM Section 2.1]) and the `corim-meta-map` of a
+//! signed CoRIM may carry a `signature-validity` period (see [CoRIM Section 2.2.2]). When both are
+//! present, the CoRIM is valid only during the intersection of the two periods. A CoRIM that carries
+//! neither period is always valid.
+//!
+//! [CoRIM Section 2.1]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-2.1
+//! [CoRIM Section 2.2.2]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-2.2.2
+
+use crate::choices::CorimIdTypeChoice;
+use crate::maps::*;
+use common::Time;
+
+/// Source of the current time, expressed as seconds since the epoch.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> Time;
+}
+
+/// A fixed time serves as a clock that always returns that time.
+impl Clock for Time {
+    fn now(&self) -> Time {
+        *self
+    }
+}
+
+/// [Clock] that reads the system time.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> Time {
+        match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as Time,
+            Err(e) => -(e.duration().as_secs() as Time),
+        }
+    }
+}
+
+/// Status of a validity period at a given time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidityStatus {
+    /// The time falls within the validity period
+    Valid,
+    /// The time precedes the `not-before` time, which is included
+    NotYetValid(Time),
+    /// The time follows the `not-after` time, which is included
+    Expired(Time),
+    /// The signer validity period and the RIM validity period do not overlap
+    Disjoint,
+}
+
+/// Result of evaluating the validity of a CoRIM.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RimValidity {
+    /// ID of the CoRIM
+    pub id: CorimIdTypeChoice,
+    /// Intersection of the signer and RIM validity periods, if either is present
+    pub period: Option<ValidityMap>,
+    /// Status of the period at the time of evaluation
+    pub status: ValidityStatus,
+}
+
+impl RimValidity {
+    /// Returns true if the CoRIM was valid at the time of evaluation.
+    pub fn is_valid(&self) -> bool {
+        self.status == ValidityStatus::Valid
+    }
+}
+
+/// Evaluates the validity of `corim` at the time returned by `clock`. `signer_validity` is the
+/// validity period from the signature that covered the CoRIM, if any (see
+/// [ProtectedCorimHeaderMap::signer_validity]).
+pub fn evaluate_rim<C: Clock + ?Sized>(
+    corim: &CorimMap,
+    signer_validity: Option<&ValidityMap>,
+    clock: &C,
+) -> RimValidity {
+    let (period, status) = match (signer_validity, &corim.rim_validity) {
+        (Some(s), Some(r)) => match s.intersect(r) {
+            Some(p) => {
+                let status = p.status(clock.now());
+                (Some(p), status)
+            }
+            None => (None, ValidityStatus::Disjoint),
+        },
+        (Some(p), None) | (None, Some(p)) => (Some(p.clone()), p.status(clock.now())),
+        (None, None) => (None, ValidityStatus::Valid),
+    };
+    RimValidity {
+        id: corim.id.clone(),
+        period,
+        status,
+    }
+}
@@ -0,0 +1,323 @@
+use ciborium::de::from_reader;
+use common::arrays::HashEntry;
+use common::choices::{VersionScheme, VersionSchemeKnown};
+use common::{BytesType, IntType, TaggedMinSvn, TaggedSvn};
+use corim::appraisal::*;
+use corim::arrays::*;
+use corim::choices::*;
+use corim::maps::*;
+use corim::{MaskedRawValue, TaggedBytes, TaggedMaskedRawValue};
+
+mod utils;
+use crate::utils::*;
+
+fn psa_refval() -> ConciseMidTag {
+    let comid_cbor_bytes = read_cbor(&Some("./tests/examples/comid-psa-refval.cbor".to_string()));
+    let comid: ConciseMidTagCbor = from_reader(comid_cbor_bytes.as_slice()).unwrap();
+    comid.try_into().unwrap()
+}
+
+fn evidence_from(comid: &ConciseMidTag) -> Vec<EvidenceRecord> {
+    comid
+        .triples
+        .reference_triples
+        .clone()
+        .unwrap()
+        .into_iter()
+        .map(EvidenceRecord::from)
+        .collect()
+}
+
+fn values() -> MeasurementValuesMap {
+    MeasurementValuesMap {
+        version: None,
+        svn: None,
+        digests: None,
+        flags: None,
+        raw_value: None,
+        raw_value_mask: None,
+        mac_addr: None,
+        ip_addr: None,
+        serial_number: None,
+        ueid: None,
+        uuid: None,
+        name: None,
+        cryptokeys: None,
+        integrity_registers: None,
+        other: None,
+    }
+}
+
+#[test]
+fn appraise_psa_refval_test() {
+    let comid = psa_refval();
+    let evidence = evidence_from(&comid);
+    let result = appraise(&[comid], &evidence);
+    assert!(!result.reference_results.is_empty());
+    assert!(result.is_success());
+    assert!(result
+        .reference_results
+        .iter()
+        .all(|r| r.status == ClaimStatus::Matched));
+}
+
+#[test]
+fn appraise_psa_refval_mismatch_test() {
+    let comid = psa_refval();
+    let mut evidence = evidence_from(&comid);
+    let digests = evidence[0].measurements[0].value.digests.as_mut().unwrap();
+    digests[0].hash_value[0] ^= 0xff;
+
+    let result = appraise(std::slice::from_ref(&comid), &evidence);
+    assert!(!result.is_success());
+    assert_eq!(result.mismatches().len(), 1);
+    assert_eq!(
+        result.mismatches()[0].mkey,
+        comid.triples.reference_triples.as_ref().unwrap()[0].measurement_map[0].mkey
+    );
+
+    // no evidence at all
+    let result = appraise(&[comid], &[]);
+    assert!(!result.is_success());
+    assert!(result
+        .reference_results
+        .iter()
+        .all(|r| r.status == ClaimStatus::NotFound));
+}
+
+#[test]
+fn environment_matches_test() {
+    let reference = EnvironmentMap {
+        class: Some(ClassMap {
+            id: Some(ClassIdTypeChoice::int(IntType::Int(1234))),
+            vendor: Some("ACME Ltd".to_string()),
+            model: None,
+            layer: None,
+            index: None,
+        }),
+        instance: None,
+        group: None,
+    };
+    let mut evidence = EnvironmentMap {
+        class: Some(ClassMap {
+            id: Some(ClassIdTypeChoice::legacy_int(IntType::Bytes(vec![
+                0x04, 0xd2,
+            ]))),
+            vendor: Some("ACME Ltd".to_string()),
+            model: Some("RoadRunner".to_string()),
+            layer: None,
+            index: None,
+        }),
+        instance: None,
+        group: None,
+    };
+    assert!(environment_matches(&reference, &evidence));
+    assert!(!environment_matches(&evidence, &reference));
+
+    evidence.class.as_mut().unwrap().vendor = Some("Other".to_string());
+    assert!(!environment_matches(&reference, &evidence));
+
+    // byte string class IDs are compared by value, regardless of leading zeros
+    let class_id = |id: ClassIdTypeChoice| EnvironmentMap {
+        class: Some(ClassMap {
+            id: Some(id),
+            vendor: None,
+            model: None,
+            layer: None,
+            index: None,
+        }),
+        instance: None,
+        group: None,
+    };
+    assert!(environment_matches(
+        &class_id(ClassIdTypeChoice::int(IntType::Int(5))),
+        &class_id(ClassIdTypeChoice::legacy_int(IntType::Bytes(vec![
+            0x00, 0x05
+        ])))
+    ));
+    assert!(environment_matches(
+        &class_id(ClassIdTypeChoice::legacy_int(IntType::Bytes(vec![
+            0x00, 0x05
+        ]))),
+        &class_id(ClassIdTypeChoice::int(IntType::Bytes(vec![0x05])))
+    ));
+    assert!(!environment_matches(
+        &class_id(ClassIdTypeChoice::int(IntType::Int(5))),
+        &class_id(ClassIdTypeChoice::int(IntType::Bytes(vec![0x05, 0x00])))
+    ));
+}
+
+#[test]
+fn compare_measurement_values_test() {
+    let mut reference = values();
+    reference.svn = Some(SvnTypeChoice::TaggedMinSvn(TaggedMinSvn { 0: 3 }));
+    reference.digests = Some(vec![HashEntry {
+        hash_alg_id: 1,
+        hash_value: vec![1, 2, 3],
+    }]);
+    reference.version = Some(VersionMap {
+        version: "1.2".to_string(),
+        version_scheme: None,
+    });
+
+    let mut evidence = values();
+    evidence.svn = Some(SvnTypeChoice::TaggedSvn(TaggedSvn { 0: 4 }));
+    evidence.digests = Some(vec![
+        HashEntry {
+            hash_alg_id: 7,
+            hash_value: vec![9, 9, 9],
+        },
+        HashEntry {
+            hash_alg_id: 1,
+            hash_value: vec![1, 2, 3],
+        },
+    ]);
+    evidence.version = Some(VersionMap {
+        version: "1.2.0".to_string(),
+        version_scheme: Some(VersionScheme::Known(VersionSchemeKnown::Multipartnumeric)),
+    });
+    evidence.serial_number = Some("12345".to_string());
+    assert!(compare_measurement_values(&reference, &evidence).is_ok());
+
+    evidence.svn = Some(SvnTypeChoice::TaggedSvn(TaggedSvn { 0: 2 }));
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+    reference.svn = Some(SvnTypeChoice::TaggedSvn(TaggedSvn { 0: 2 }));
+    assert!(compare_measurement_values(&reference, &evidence).is_ok());
+
+    // min-svn describes a reference, not an observed value
+    evidence.svn = Some(SvnTypeChoice::TaggedMinSvn(TaggedMinSvn { 0: 2 }));
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+    evidence.svn = Some(SvnTypeChoice::TaggedSvn(TaggedSvn { 0: 2 }));
+
+    evidence.digests.as_mut().unwrap()[0].hash_alg_id = 8;
+    evidence.digests.as_mut().unwrap()[1].hash_alg_id = 8;
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+
+    evidence.digests = None;
+    reference.digests = None;
+    reference.serial_number = Some("54321".to_string());
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+}
+
+#[test]
+fn appraise_endorsements_test() {
+    let mut comid = psa_refval();
+    let env = comid.triples.reference_triples.as_ref().unwrap()[0]
+        .environment_map
+        .clone();
+    let mut endorsed_values = values();
+    endorsed_values.name = Some("certified".to_string());
+    comid.triples.endorsed_triples = Some(vec![EndorsedTripleRecord {
+        environment_map: env,
+        measurement_map: vec![MeasurementMap {
+            mkey: None,
+            value: endorsed_values,
+        }],
+    }]);
+
+    let mut evidence = evidence_from(&comid);
+    let result = appraise(&[comid.clone()], &evidence);
+    assert!(result.is_success());
+    assert_eq!(result.endorsements.len(), 1);
+
+    for e in evidence.iter_mut() {
+        for m in e.measurements.iter_mut() {
+            m.value.digests.as_mut().unwrap()[0].hash_value[0] ^= 0xff;
+        }
+    }
+    let result = appraise(&[comid.clone()], &evidence);
+    assert!(!result.is_success());
+    assert!(result.endorsements.is_empty());
+
+    // a record that mismatches is not endorsed even if another record matched the claim
+    // and the appraisal fails
+    let mut evidence = evidence_from(&comid);
+    let mut faulty = evidence[0].clone();
+    faulty.environment.class.as_mut().unwrap().layer = Some(1);
+    faulty.measurements[0].value.digests.as_mut().unwrap()[0].hash_value[0] ^= 0xff;
+    evidence.push(faulty.clone());
+    comid.triples.endorsed_triples.as_mut().unwrap()[0].environment_map = faulty.environment;
+    let result = appraise(&[comid], &evidence);
+    assert!(!result.is_success());
+    assert_eq!(result.failed_evidence, vec![evidence.len() - 1]);
+    assert_eq!(result.mismatches().len(), 1);
+    assert!(result.endorsements.is_empty());
+}
+
+#[test]
+fn appraise_alternatives_test() {
+    let mut comid = psa_refval();
+    let evidence = evidence_from(&comid);
+    let expected = appraise(std::slice::from_ref(&comid), &evidence)
+        .reference_results
+        .len();
+
+    // measurement-maps sharing an mkey are alternatives, only one of which needs to match
+    let rv = &mut comid.triples.reference_triples.as_mut().unwrap()[0];
+    let mut alternative = rv.measurement_map[0].clone();
+    alternative.value.digests.as_mut().unwrap()[0].hash_value[0] ^= 0xff;
+    rv.measurement_map.insert(0, alternative);
+    let result = appraise(std::slice::from_ref(&comid), &evidence);
+    assert!(result.is_success());
+    assert_eq!(result.reference_results.len(), expected);
+    assert!(result.failed_evidence.is_empty());
+
+    // evidence that matches none of the alternatives fails
+    let mut evidence = evidence;
+    evidence[0].measurements[0].value.digests.as_mut().unwrap()[0].hash_value[0] ^= 0x0f;
+    let result = appraise(&[comid], &evidence);
+    assert!(!result.is_success());
+    assert_eq!(result.failed_evidence, vec![0]);
+}
+
+#[test]
+fn compare_raw_value_test() {
+    let mut reference = values();
+    reference.raw_value = Some(RawValueTypeChoice::Bytes(TaggedBytes {
+        0: BytesType::Bytes(vec![0x12, 0x34, 0x56, 0x78]),
+    }));
+    reference.raw_value_mask = Some(vec![0xff, 0xff, 0x00, 0x00]);
+
+    let mut evidence = values();
+    evidence.raw_value = Some(RawValueTypeChoice::Bytes(TaggedBytes {
+        0: BytesType::Bytes(vec![0x12, 0x34, 0xab, 0xcd]),
+    }));
+    assert!(compare_measurement_values(&reference, &evidence).is_ok());
+
+    reference.raw_value_mask = None;
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+
+    reference.raw_value = Some(RawValueTypeChoice::MaskedRawValue(TaggedMaskedRawValue {
+        0: MaskedRawValue(vec![0x12, 0x00, 0xab, 0x00], vec![0xff, 0x00, 0xff, 0x00]),
+    }));
+    assert!(compare_measurement_values(&reference, &evidence).is_ok());
+
+    evidence.raw_value = None;
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+}
+
+#[test]
+fn compare_flags_test() {
+    let mut reference = values();
+    reference.flags = Some(FlagsTypeChoice::Map(FlagsMap {
+        configured: None,
+        secure: Some(true),
+        recovery: None,
+        debug: Some(false),
+        replay_protected: None,
+        integrity_protected: None,
+        other: None,
+    }));
+
+    // legacy bit field with no flags set, i.e., configured and secure
+    let mut evidence = values();
+    evidence.flags = Some(FlagsTypeChoice::Legacy(0));
+    assert!(compare_measurement_values(&reference, &evidence).is_ok());
+
+    // debug
+    evidence.flags = Some(FlagsTypeChoice::Legacy(8));
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+
+    evidence.flags = None;
+    assert!(compare_measurement_values(&reference, &evidence).is_err());
+}
@@ -20,10 +20,12 @@ fn attest_key_triple_record_test() {
             )))),
             group: None,
         },
-        crypto_keys: vec![VerificationKeyMapCbor {
-            key: "Some Key".to_string(),
-            keychain: None,
-        }],
+        crypto_keys: vec![VerificationKeyTypeChoiceCbor::Legacy(
+            VerificationKeyMapCbor {
+                key: "Some Key".to_string(),
+                keychain: None,
+            },
+        )],
     };
 
     let _ = into_writer(&fab, &mut encoded_token);
@@ -41,7 +43,10 @@ fn attest_key_triple_record_test() {
             _ => panic!(),
         }
     );
-    assert_eq!("Some Key", fab.crypto_keys[0].key);
+    match &fab.crypto_keys[0] {
+        VerificationKeyTypeChoiceCbor::Legacy(k) => assert_eq!("Some Key", k.key),
+        _ => panic!(),
+    }
 
     let dec_j: AttestKeyTripleRecord = dec.try_into().unwrap();
     let _ = serde_json::to_string(&dec_j).unwrap();
@@ -114,11 +119,11 @@ fn coswid_triple_record_test() {
 fn domain_dependency_triple_record_test() {
     let mut encoded_token = vec![];
     let fab = DomainDependencyTripleRecordCbor {
-        domain_type_choice: DomainTypeChoice::Text("Some DomainTypeChoice".to_string()),
+        domain_type_choice: DomainTypeChoiceCbor::Text("Some DomainTypeChoice".to_string()),
         domain_type_choices: vec![
-            DomainTypeChoice::Text("Some other DomainTypeChoice".to_string()),
-            DomainTypeChoice::U64(666u64),
-            DomainTypeChoice::Uuid(Required(UuidType::Uuid(TEST_UUID.as_bytes().to_vec()))),
+            DomainTypeChoiceCbor::Text("Some other DomainTypeChoice".to_string()),
+            DomainTypeChoiceCbor::U64(666u64),
+            DomainTypeChoiceCbor::Uuid(Required(UuidType::Uuid(TEST_UUID.as_bytes().to_vec()))),
         ],
     };
 
@@ -132,28 +137,28 @@ fn domain_dependency_triple_record_test() {
     assert_eq!(
         "Some DomainTypeChoice",
         match &fab.domain_type_choice {
-            DomainTypeChoice::Text(t) => t.as_str(),
+            DomainTypeChoiceCbor::Text(t) => t.as_str(),
             _ => panic!(),
         }
     );
     assert_eq!(
         "Some other DomainTypeChoice",
         match &fab.domain_type_choices[0] {
-            DomainTypeChoice::Text(t) => t.as_str(),
+            DomainTypeChoiceCbor::Text(t) => t.as_str(),
             _ => panic!(),
         }
     );
     assert_eq!(
         666,
         match &fab.domain_type_choices[1] {
-            DomainTypeChoice::U64(t) => *t,
+            DomainTypeChoiceCbor::U64(t) => *t,
             _ => panic!(),
         }
     );
     assert_eq!(
         TEST_UUID.as_bytes().to_vec(),
         match &fab.domain_type_choices[2] {
-            DomainTypeChoice::Uuid(ciborium::tag::Required(UuidType::Uuid(v))) => v.clone(),
+            DomainTypeChoiceCbor::Uuid(ciborium::tag::Required(UuidType::Uuid(v))) => v.clone(),
             _ => panic!(),
         }
     );
@@ -187,12 +192,16 @@ fn endorsed_triple_record_test() {
                 svn: None,
                 digests: None,
                 flags: None,
+                raw_value: None,
+                raw_value_mask: None,
                 mac_addr: None,
                 ip_addr: None,
                 serial_number: None,
                 ueid: None,
                 uuid: None,
                 name: None,
+                cryptokeys: None,
+                integrity_registers: None,
                 other: None,
             },
         }],
@@ -242,10 +251,12 @@ fn identity_triple_record_test() {
             )))),
             group: None,
         },
-        crypto_keys: vec![VerificationKeyMapCbor {
-            key: "Some Key".to_string(),
-            keychain: None,
-        }],
+        crypto_keys: vec![VerificationKeyTypeChoiceCbor::Legacy(
+            VerificationKeyMapCbor {
+                key: "Some Key".to_string(),
+                keychain: None,
+            },
+        )],
     };
 
     let _ = into_writer(&fab, &mut encoded_token);
@@ -263,7 +274,10 @@ fn identity_triple_record_test() {
             _ => panic!(),
         }
     );
-    assert_eq!("Some Key", fab.crypto_keys[0].key);
+    match &fab.crypto_keys[0] {
+        VerificationKeyTypeChoiceCbor::Legacy(k) => assert_eq!("Some Key", k.key),
+        _ => panic!(),
+    }
 
     let dec_j: IdentityTripleRecord = dec.try_into().unwrap();
     let _ = serde_json::to_string(&dec_j).unwrap();
@@ -294,12 +308,16 @@ fn reference_triple_record_test() {
                 svn: None,
                 digests: None,
                 flags: None,
+                raw_value: None,
+                raw_value_mask: None,
                 mac_addr: None,
                 ip_addr: None,
                 serial_number: None,
                 ueid: None,
                 uuid: None,
                 name: None,
+                cryptokeys: None,
+                integrity_registers: None,
                 other: None,
             },
         }],
@@ -0,0 +1,182 @@
+use ciborium::de::from_reader;
+use ciborium::ser::into_writer;
+use ciborium::tag::Required;
+use ciborium::value::Value;
+use common::arrays::HashEntry;
+use common::digest::HashAlgorithm;
+use common::{BytesType, Tuple, UuidType};
+use corim::builder::*;
+use corim::choices::*;
+use corim::maps::*;
+
+mod utils;
+use crate::utils::*;
+
+fn example_comid() -> ConciseMidTag {
+    let env = EnvironmentMapBuilder::new()
+        .vendor("ACME")
+        .model("RoadRunner")
+        .instance_ueid(&TEST_UEID)
+        .build()
+        .unwrap();
+    let m = MeasurementMapBuilder::new()
+        .version("1.2.3", None)
+        .svn(2)
+        .digest(HashEntry::compute(HashAlgorithm::Sha256, b"firmware"))
+        .raw_value(&[0xde, 0xad, 0xbe, 0xef])
+        .raw_value_mask(&[0xff, 0xff, 0, 0])
+        .build()
+        .unwrap();
+    ConciseMidTagBuilder::new()
+        .language("en-GB")
+        .tag_id(TagIdTypeChoice::Str("example".to_string()))
+        .tag_version(1)
+        .triples(
+            TriplesMapBuilder::new()
+                .reference_value(env.clone(), vec![m])
+                .attest_keys(
+                    env,
+                    vec![CryptoKeyTypeChoice::Key(Required("key".to_string()))],
+                )
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn concise_mid_tag_builder_test() {
+    let comid = example_comid();
+    let identity = comid.tag_identity.as_ref().unwrap();
+    assert_eq!(identity.tag_id, TagIdTypeChoice::Str("example".to_string()));
+    assert_eq!(identity.tag_version, Some(TagVersionType::U64(1)));
+    assert_eq!(comid.triples.reference_triples.as_ref().unwrap().len(), 1);
+    assert!(comid.triples.endorsed_triples.is_none());
+
+    let comid_cbor: ConciseMidTagCbor = comid.clone().try_into().unwrap();
+    let mut encoded_token = vec![];
+    into_writer(&comid_cbor, &mut encoded_token).unwrap();
+    let comid_d: ConciseMidTagCbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(comid_cbor, comid_d);
+    let comid_j: ConciseMidTag = comid_d.try_into().unwrap();
+    assert_eq!(comid, comid_j);
+}
+
+#[test]
+fn concise_mid_tag_builder_error_test() {
+    let env = EnvironmentMapBuilder::new().vendor("ACME").build().unwrap();
+    let m = MeasurementMapBuilder::new().svn(1).build().unwrap();
+    let triples = TriplesMapBuilder::new()
+        .endorsed_value(env.clone(), vec![m])
+        .build()
+        .unwrap();
+
+    assert!(ConciseMidTagBuilder::new()
+        .triples(triples.clone())
+        .build()
+        .is_err());
+    assert!(ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str("example".to_string()))
+        .build()
+        .is_err());
+    assert!(ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str("example".to_string()))
+        .triples(triples)
+        .build_cbor()
+        .is_ok());
+
+    assert!(TriplesMapBuilder::new().build().is_err());
+    let extension = Tuple {
+        key: Value::Integer(100.into()),
+        value: Value::Bool(true),
+    };
+    assert!(TriplesMapBuilder::new()
+        .extension(extension.clone())
+        .build()
+        .is_err());
+    assert!(TriplesMapBuilder::new()
+        .extension(extension)
+        .build_cbor()
+        .is_err());
+    assert!(TriplesMapBuilder::new()
+        .reference_value(env.clone(), vec![])
+        .build()
+        .is_err());
+    assert!(TriplesMapBuilder::new()
+        .identity_keys(env, vec![])
+        .build()
+        .is_err());
+
+    assert!(EnvironmentMapBuilder::new().build().is_err());
+    assert!(EnvironmentMapBuilder::new()
+        .class(ClassMap {
+            id: None,
+            vendor: None,
+            model: None,
+            layer: None,
+            index: None,
+        })
+        .build()
+        .is_err());
+
+    assert!(MeasurementMapBuilder::new().build().is_err());
+    assert!(MeasurementMapBuilder::new()
+        .raw_value_mask(&[0xff])
+        .build()
+        .is_err());
+}
+
+#[test]
+fn generated_id_test() {
+    let comid = ConciseMidTagBuilder::new()
+        .generate_tag_id()
+        .triples(
+            TriplesMapBuilder::new()
+                .endorsed_value(
+                    EnvironmentMapBuilder::new().vendor("ACME").build().unwrap(),
+                    vec![MeasurementMapBuilder::new().name("fw").build().unwrap()],
+                )
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    let id = comid.tag_identity.unwrap().tag_id;
+    match &id {
+        TagIdTypeChoice::Uuid(UuidType::Uuid(u)) => assert_eq!(u.len(), 16),
+        _ => panic!(),
+    }
+    assert_ne!(generate_uuid(), generate_uuid());
+}
+
+#[test]
+fn corim_map_builder_test() {
+    let comid = example_comid();
+    let corim = CorimMapBuilder::new()
+        .id(CorimIdTypeChoice::Str("corim".to_string()))
+        .comid(&comid)
+        .build_cbor()
+        .unwrap();
+    let mut encoded_token = vec![];
+    into_writer(&corim, &mut encoded_token).unwrap();
+    let corim_d: CorimMapCbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(corim, corim_d);
+
+    match &corim_d.tags[0] {
+        BytesType::Bytes(b) => {
+            let v: Value = from_reader(b.as_slice()).unwrap();
+            match v {
+                Value::Tag(506, v) => {
+                    let comid_d: ConciseMidTagCbor = (*v).try_into().unwrap();
+                    let comid_j: ConciseMidTag = comid_d.try_into().unwrap();
+                    assert_eq!(comid, comid_j);
+                }
+                _ => panic!(),
+            }
+        }
+    }
+
+    assert!(CorimMapBuilder::new().comid(&comid).build().is_err());
+    assert!(CorimMapBuilder::new().generate_id().build().is_err());
+}
@@ -4,8 +4,10 @@ use ciborium::ser::into_writer;
 use ciborium::tag::Required;
 use ciborium::value::{Integer, Value};
 use common::choices::*;
-use common::{IntType, OidType, UeidType, UuidType};
+use common::{IntType, OidType, TaggedIntMode, UeidType, UuidType};
 use corim::choices::*;
+use corim::CorimDraft;
+use hex_literal::hex;
 
 mod utils;
 
@@ -32,7 +34,7 @@ fn class_id_type_choice_test() {
     let fab2_c: ClassIdTypeChoiceCbor = fab2_j.try_into().unwrap();
     assert_eq!(fab2, fab2_c);
 
-    let fab3 = ClassIdTypeChoiceCbor::Int(Required(IntType::Int([0x01].to_vec())));
+    let fab3 = ClassIdTypeChoiceCbor::Int(Required(IntType::Bytes([0x01].to_vec())));
     let mut encoded_token3 = vec![];
     into_writer(&fab3, &mut encoded_token3).unwrap();
 
@@ -139,6 +141,62 @@ l4wOuDwKQa+upc8GftXE2C//4mKANBC6It01gUaTIpo=
     assert_eq!(fab3, fab_c3);
 }
 
+#[test]
+fn crypto_key_type_choice_current_draft_test() {
+    // 557([1, h'DEADBEEF']), 558({1: 2}), 559([1, h'DEADBEEF']), 560(h'01'), 561([1, h'DEADBEEF']),
+    // 562(h'3000')
+    let values = [
+        hex!("d9022d820144deadbeef").to_vec(),
+        hex!("d9022ea10102").to_vec(),
+        hex!("d9022f820144deadbeef").to_vec(),
+        hex!("d902304101").to_vec(),
+        hex!("d90231820144deadbeef").to_vec(),
+        hex!("d90232423000").to_vec(),
+    ];
+    for v in values {
+        let value: Value = from_reader(v.as_slice()).unwrap();
+        let k = CryptoKeyTypeChoice::try_from(&value).unwrap();
+        let mut encoded_token = vec![];
+        into_writer(&k, &mut encoded_token).unwrap();
+        assert_eq!(v, encoded_token);
+    }
+
+    let value: Value = from_reader(hex!("d9022e4101").as_slice()).unwrap();
+    assert!(CryptoKeyTypeChoice::try_from(&value).is_err());
+    let value: Value = from_reader(hex!("d9022d4101").as_slice()).unwrap();
+    assert!(CryptoKeyTypeChoice::try_from(&value).is_err());
+}
+
+#[test]
+fn verification_key_type_choice_test() {
+    // {0: "key"}
+    let legacy: Value = from_reader(hex!("a10063 6b6579").as_slice()).unwrap();
+    let k = VerificationKeyTypeChoiceCbor::try_from(&legacy).unwrap();
+    assert!(matches!(k, VerificationKeyTypeChoiceCbor::Legacy(_)));
+
+    // 554("key")
+    let current = k.to_draft(CorimDraft::Current).unwrap();
+    let mut encoded_token = vec![];
+    into_writer(&current, &mut encoded_token).unwrap();
+    assert_eq!(hex!("d9022a636b6579").to_vec(), encoded_token);
+    let value: Value = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(
+        current,
+        VerificationKeyTypeChoiceCbor::try_from(&value).unwrap()
+    );
+    assert_eq!(k, current.to_draft(CorimDraft::Legacy03).unwrap());
+
+    // {0: "key", 1: ["cert"]}
+    let chained: Value = from_reader(hex!("a200636b65790181 6463657274").as_slice()).unwrap();
+    let k2 = VerificationKeyTypeChoiceCbor::try_from(&chained).unwrap();
+    assert!(k2.to_draft(CorimDraft::Current).is_err());
+    assert_eq!(k2, k2.to_draft(CorimDraft::Legacy03).unwrap());
+
+    let k_j = VerificationKeyTypeChoice::try_from(&k2).unwrap();
+    let _ = serde_json::to_string(&k_j).unwrap();
+    assert_eq!(k2, VerificationKeyTypeChoiceCbor::try_from(&k_j).unwrap());
+}
+
 #[test]
 fn domain_type_choice_test() {
     let fab = DomainTypeChoice::U64(5);
@@ -385,8 +443,8 @@ fn version_scheme_test() {
     assert_eq!(vs3, vs_cbor3);
 
     let unknown = 99999;
-    let unknown_as_int: Integer = unknown.try_into().unwrap();
-    let unknown_as_value: Value = unknown_as_int.try_into().unwrap();
+    let unknown_as_int: Integer = unknown.into();
+    let unknown_as_value: Value = unknown_as_int.into();
     let vs4: VersionSchemeCbor = unknown_as_value.try_into().unwrap();
     assert_eq!(vs4, VersionSchemeCbor::IntExtensions(99999));
     let mut encoded_token4 = vec![];
@@ -399,3 +457,48 @@ fn version_scheme_test() {
     let vs_cbor4: VersionSchemeCbor = vs_j4.try_into().unwrap();
     assert_eq!(vs4, vs_cbor4);
 }
+
+#[test]
+fn class_id_type_choice_int_test() {
+    // 551(5)
+    let spec = hex_literal::hex!("d9022705");
+    let v: Value = from_reader(spec.as_slice()).unwrap();
+    let cid = ClassIdTypeChoiceCbor::try_from(&v).unwrap();
+    assert_eq!(cid, ClassIdTypeChoiceCbor::Int(Required(IntType::Int(5))));
+    let mut encoded_token = vec![];
+    into_writer(&cid, &mut encoded_token).unwrap();
+    assert_eq!(spec.to_vec(), encoded_token);
+
+    let cid_j: ClassIdTypeChoice = cid.clone().try_into().unwrap();
+    assert_eq!(cid_j, ClassIdTypeChoice::int(IntType::Int(5)));
+    let json = serde_json::to_string(&cid_j).unwrap();
+    let cid_j2: ClassIdTypeChoice = serde_json::from_str(&json).unwrap();
+    let cid_c: ClassIdTypeChoiceCbor = cid_j2.try_into().unwrap();
+    assert_eq!(cid, cid_c);
+
+    // 600(h'05')
+    let legacy = hex_literal::hex!("d902584105");
+    let cid_legacy = cid.with_tagged_int_mode(TaggedIntMode::Legacy).unwrap();
+    assert_eq!(
+        cid_legacy,
+        ClassIdTypeChoiceCbor::Int2(Required(IntType::Bytes(vec![5])))
+    );
+    let mut encoded_token2 = vec![];
+    into_writer(&cid_legacy, &mut encoded_token2).unwrap();
+    assert_eq!(legacy.to_vec(), encoded_token2);
+    let v2: Value = from_reader(legacy.as_slice()).unwrap();
+    assert_eq!(cid_legacy, ClassIdTypeChoiceCbor::try_from(&v2).unwrap());
+    assert_eq!(
+        cid,
+        cid_legacy
+            .with_tagged_int_mode(TaggedIntMode::Spec)
+            .unwrap()
+    );
+
+    // legacy values that do not fit in an int cannot be expressed per the spec
+    let impl_id = ClassIdTypeChoiceCbor::Int2(Required(IntType::Bytes(vec![0x61; 32])));
+    assert!(impl_id.with_tagged_int_mode(TaggedIntMode::Spec).is_err());
+    assert!(ClassIdTypeChoiceCbor::Int(Required(IntType::Int(-1)))
+        .with_tagged_int_mode(TaggedIntMode::Legacy)
+        .is_err());
+}
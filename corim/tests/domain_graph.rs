@@ -0,0 +1,103 @@
+use ciborium::de::from_reader;
+use ciborium::ser::into_writer;
+use corim::builder::*;
+use corim::choices::*;
+use corim::domain_graph::*;
+use corim::maps::*;
+
+fn env(vendor: &str, model: &str) -> DomainTypeChoice {
+    DomainTypeChoice::Environment(
+        EnvironmentMapBuilder::new()
+            .vendor(vendor)
+            .model(model)
+            .build()
+            .unwrap(),
+    )
+}
+
+fn comid(id: &str, triples: TriplesMapBuilder) -> ConciseMidTag {
+    ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str(id.to_string()))
+        .triples(triples.build().unwrap())
+        .build()
+        .unwrap()
+}
+
+fn platform() -> Vec<ConciseMidTag> {
+    let platform = DomainTypeChoice::Text("platform".to_string());
+    let rot = DomainTypeChoice::Text("root-of-trust".to_string());
+    vec![
+        comid(
+            "oem",
+            TriplesMapBuilder::new()
+                .membership(
+                    platform.clone(),
+                    vec![env("SoC Co", "soc"), rot.clone(), env("BMC Co", "bmc")],
+                )
+                .dependency(env("BMC Co", "bmc"), vec![rot.clone()]),
+        ),
+        comid(
+            "tpm-vendor",
+            TriplesMapBuilder::new()
+                .membership(rot.clone(), vec![env("TPM Co", "tpm")])
+                .dependency(rot, vec![DomainTypeChoice::U64(7)]),
+        ),
+        comid(
+            "soc-vendor",
+            TriplesMapBuilder::new().membership(platform, vec![env("SoC Co", "soc")]),
+        ),
+    ]
+}
+
+#[test]
+fn domain_graph_test() {
+    let graph = DomainGraph::from_comids(&platform());
+    let platform = DomainTypeChoice::Text("platform".to_string());
+    let rot = DomainTypeChoice::Text("root-of-trust".to_string());
+
+    assert_eq!(graph.nodes().len(), 6);
+    // the SoC membership asserted by two CoMIDs yields one edge
+    assert_eq!(graph.edges().len(), 6);
+    assert_eq!(graph.members(&platform).len(), 3);
+    assert_eq!(graph.member_of(&env("TPM Co", "tpm")), vec![&rot]);
+    assert_eq!(graph.trustees(&env("BMC Co", "bmc")), vec![&rot]);
+    assert_eq!(graph.dependents(&rot), vec![&env("BMC Co", "bmc")]);
+    assert_eq!(
+        graph.all_trustees(&env("BMC Co", "bmc")),
+        vec![&rot, &DomainTypeChoice::U64(7)]
+    );
+
+    let models: Vec<String> = graph
+        .environments(&platform)
+        .iter()
+        .map(|e| e.class.as_ref().unwrap().model.clone().unwrap())
+        .collect();
+    assert_eq!(models, vec!["soc", "bmc", "tpm"]);
+    assert!(graph.environments(&env("TPM Co", "tpm")).is_empty());
+    assert!(graph
+        .members(&DomainTypeChoice::Text("unknown".to_string()))
+        .is_empty());
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph domains {\n"));
+    assert!(dot.contains("  n0 [label=\"platform\", shape=ellipse];\n"));
+    assert!(dot.contains("  n1 [label=\"SoC Co soc\", shape=box];\n"));
+    assert!(dot.contains("  n0 -> n1 [label=\"member\"];\n"));
+    assert!(dot.contains("  n3 -> n2 [label=\"depends on\", style=dashed];\n"));
+}
+
+#[test]
+fn domain_type_choice_environment_test() {
+    let comid = &platform()[0];
+    let comid_cbor: ConciseMidTagCbor = comid.clone().try_into().unwrap();
+    let mut encoded_token = vec![];
+    into_writer(&comid_cbor, &mut encoded_token).unwrap();
+    let comid_d: ConciseMidTagCbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(comid_cbor, comid_d);
+    let comid_j: ConciseMidTag = comid_d.try_into().unwrap();
+    assert_eq!(comid, &comid_j);
+
+    let json = serde_json::to_string(&comid_j).unwrap();
+    let comid_j2: ConciseMidTag = serde_json::from_str(&json).unwrap();
+    assert_eq!(comid_j, comid_j2);
+}
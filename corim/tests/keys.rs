@@ -0,0 +1,176 @@
+use ciborium::de::from_reader;
+use ciborium::tag::Required;
+use common::arrays::HashEntryCbor;
+use common::{BytesType, PkixBase64Type};
+use corim::builder::*;
+use corim::choices::*;
+use corim::keys::*;
+use corim::maps::*;
+use spki::ObjectIdentifier;
+
+mod utils;
+use crate::utils::*;
+
+const ID_EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const PRIME256V1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+
+fn pem(name: &str) -> String {
+    String::from_utf8(read_cbor(&Some(format!("./tests/examples/{}", name)))).unwrap()
+}
+
+fn pem_body(pem: &str) -> String {
+    pem.lines().filter(|l| !l.starts_with("-----")).collect()
+}
+
+fn der(pem: &str) -> Vec<u8> {
+    base64::decode(pem_body(pem)).unwrap()
+}
+
+#[test]
+fn key_index_psa_iakpub_test() {
+    let bytes = read_cbor(&Some("./tests/examples/comid-psa-iakpub.cbor".to_string()));
+    let comid_cbor: ConciseMidTagCbor = from_reader(bytes.as_slice()).unwrap();
+    let comid: ConciseMidTag = comid_cbor.try_into().unwrap();
+    let triples = comid.triples.identity_triples.as_ref().unwrap();
+
+    let index = KeyIndex::from_comids(std::slice::from_ref(&comid));
+    assert!(index.issues.is_empty());
+    assert_eq!(index.entries.len(), triples.len());
+    assert!(index
+        .entries
+        .iter()
+        .all(|e| e.purpose == KeyPurpose::Identity));
+
+    let env = &triples[0].environment_map;
+    let keys = index.identity_keys_for(env);
+    assert_eq!(keys.len(), 1);
+    assert!(index.attest_keys_for(env).is_empty());
+    let key = keys[0];
+    assert_eq!(key.algorithm().unwrap(), ID_EC_PUBLIC_KEY);
+    assert_eq!(key.curve().unwrap(), Some(PRIME256V1));
+    let point = key.public_key_bytes().unwrap();
+    assert_eq!(point.len(), 65);
+    assert_eq!(point[0], 4);
+    assert!(key.certificates.is_empty());
+
+    let other = EnvironmentMapBuilder::new().vendor("EMCA").build().unwrap();
+    assert!(index.keys_for(&other).is_empty());
+}
+
+#[test]
+fn verification_key_cert_path_test() {
+    let ee = pem("attest-key-ee.pem");
+    let ca = pem("attest-key-ca.pem");
+
+    let from_cert =
+        VerificationKey::try_from(&CryptoKeyTypeChoice::Cert(Required(ee.clone()))).unwrap();
+    assert_eq!(from_cert.certificates, vec![der(&ee)]);
+    assert_eq!(from_cert.curve().unwrap(), Some(PRIME256V1));
+
+    // the path is reordered so the certificate containing the key comes first
+    let path = format!("{}{}", ca, ee);
+    let from_path = VerificationKey::try_from(&CryptoKeyTypeChoice::Path(Required(path))).unwrap();
+    assert_eq!(from_path.spki, from_cert.spki);
+    assert_eq!(from_path.certificates, vec![der(&ee), der(&ca)]);
+
+    // bare base64 of concatenated certificates
+    let bare = base64::encode([der(&ee), der(&ca)].concat());
+    assert_eq!(
+        VerificationKey::try_from(&CryptoKeyTypeChoice::Path(Required(bare))).unwrap(),
+        from_path
+    );
+
+    let from_der = VerificationKey::try_from(&CryptoKeyTypeChoice::Asn1DerCert(Required(
+        BytesType::Bytes(der(&ee)),
+    )))
+    .unwrap();
+    assert_eq!(from_der, from_cert);
+
+    let from_key = VerificationKey::try_from(&CryptoKeyTypeChoice::Key(Required(base64::encode(
+        &from_cert.spki,
+    ))))
+    .unwrap();
+    assert_eq!(from_key.spki, from_cert.spki);
+
+    // two end entity certificates do not form a path
+    let two = format!("{}{}", ee, ee);
+    assert!(VerificationKey::try_from(&CryptoKeyTypeChoice::Path(Required(two))).is_err());
+    assert!(
+        VerificationKey::try_from(&CryptoKeyTypeChoice::Cert(Required(
+            "not base64".to_string()
+        )))
+        .is_err()
+    );
+}
+
+#[test]
+fn verification_key_map_test() {
+    let ee = pem("attest-key-ee.pem");
+    let ca = pem("attest-key-ca.pem");
+    let ee_key =
+        VerificationKey::try_from(&CryptoKeyTypeChoice::Cert(Required(ee.clone()))).unwrap();
+
+    let vkm = VerificationKeyMap {
+        key: base64::encode(&ee_key.spki),
+        keychain: Some(vec![
+            PkixBase64Type::Base64(pem_body(&ee)),
+            PkixBase64Type::Base64(pem_body(&ca)),
+        ]),
+    };
+    let key = VerificationKey::try_from(&vkm).unwrap();
+    assert_eq!(key.spki, ee_key.spki);
+    assert_eq!(key.certificates.len(), 2);
+
+    let ca_key = VerificationKey::try_from(&CryptoKeyTypeChoice::Cert(Required(ca))).unwrap();
+    let mismatch = VerificationKeyMap {
+        key: base64::encode(&ca_key.spki),
+        ..vkm
+    };
+    assert!(VerificationKey::try_from(&mismatch).is_err());
+}
+
+#[test]
+fn key_index_attest_key_test() {
+    let ee = pem("attest-key-ee.pem");
+    let env = EnvironmentMapBuilder::new()
+        .vendor("ACME")
+        .model("RoadRunner")
+        .build()
+        .unwrap();
+    let comid = ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str("keys".to_string()))
+        .triples(
+            TriplesMapBuilder::new()
+                .attest_keys(
+                    env.clone(),
+                    vec![
+                        CryptoKeyTypeChoice::Cert(Required(ee)),
+                        CryptoKeyTypeChoice::KeyThumbprint(Required(HashEntryCbor {
+                            hash_alg_id: 1,
+                            hash_value: vec![0; 32],
+                        })),
+                    ],
+                )
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    let index = KeyIndex::from_comids(&[comid]);
+    assert_eq!(index.entries.len(), 1);
+    assert_eq!(index.issues.len(), 1);
+    assert_eq!(
+        index.entries[0].tag_id,
+        Some(TagIdTypeChoice::Str("keys".to_string()))
+    );
+
+    // evidence from a more specific environment matches the triple
+    let evidence_env = EnvironmentMapBuilder::new()
+        .vendor("ACME")
+        .model("RoadRunner")
+        .instance_ueid(&TEST_UEID)
+        .build()
+        .unwrap();
+    assert_eq!(index.attest_keys_for(&evidence_env).len(), 1);
+    assert!(index.identity_keys_for(&evidence_env).is_empty());
+}
@@ -2,11 +2,18 @@ use ciborium::de::from_reader;
 use ciborium::ser::into_writer;
 use ciborium::tag::Required;
 use ciborium::value::Value;
-use common::{BytesType, IntType, TaggedUriTypeCbor, TimeCbor, UeidType, UuidType};
+use common::choices::{VersionScheme, VersionSchemeKnown};
+use common::{BytesType, IntType, TaggedIntMode, TaggedUriTypeCbor, TimeCbor, UeidType, UuidType};
+use corim::arrays::{
+    ConditionalEndorsementTripleRecordCbor, DomainDependencyTripleRecordCbor,
+    EndorsedTripleRecordCbor, StatefulEnvironmentRecordCbor,
+};
 use corim::choices::*;
 use corim::maps::*;
+use corim::CorimDraft;
 use coswid::maps::*;
 use hex_literal::hex;
+use std::cmp::Ordering;
 
 mod utils;
 use crate::utils::*;
@@ -47,7 +54,7 @@ fn class_map_impl_full_test() {
     // {0: 600(h'61636D652D696D706C656D656E746174696F6E2D69642D303030303030303031'), 1: "EMCA Ltd", 2: "Rennurdaor", 3: 2, 4: 1}
     let expected = hex!("a500d90258582061636d652d696d706c656d656e746174696f6e2d69642d3030303030303030310168454d4341204c7464026a52656e6e757264616f7203020401");
     let e = ClassMapCbor {
-        id: Some(ClassIdTypeChoiceCbor::Int2(Required(IntType::Int(
+        id: Some(ClassIdTypeChoiceCbor::Int2(Required(IntType::Bytes(
             TEST_IMPL_ID.to_vec(),
         )))),
         vendor: Some("EMCA Ltd".to_string()),
@@ -60,6 +67,37 @@ fn class_map_impl_full_test() {
     assert_eq!(expected.to_vec(), actual);
 }
 
+#[test]
+fn class_map_int_class_id_test() {
+    // {0: 551(1234), 1: "ACME Ltd"}
+    let expected = hex!("a200d902271904d2016841434d45204c7464");
+    let decoded: ClassMapCbor = from_reader(expected.as_slice()).unwrap();
+    assert_eq!(
+        decoded.id,
+        Some(ClassIdTypeChoiceCbor::Int(Required(IntType::Int(1234))))
+    );
+    let mut actual = vec![];
+    let _ = into_writer(&decoded, &mut actual);
+    assert_eq!(expected.to_vec(), actual);
+}
+
+#[test]
+fn concise_mid_tag_tagged_int_mode_test() {
+    let comid_cbor_bytes = read_cbor(&Some("./tests/examples/comid-psa-refval.cbor".to_string()));
+    let mut comid: ConciseMidTagCbor = from_reader(comid_cbor_bytes.as_slice()).unwrap();
+
+    // the PSA implementation ID is 32 bytes and cannot be expressed as an int
+    assert!(comid
+        .clone()
+        .set_tagged_int_mode(TaggedIntMode::Spec)
+        .is_err());
+
+    comid.set_tagged_int_mode(TaggedIntMode::Legacy).unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&comid, &mut encoded_token);
+    assert_eq!(comid_cbor_bytes, encoded_token);
+}
+
 #[test]
 fn class_map_class_id_only_test() {
     // {0: 37(h'31FB5ABF023E4992AA4E95F9C1503BFA')}
@@ -112,19 +150,157 @@ fn concise_mid_tag_test() {
             buffer_to_hex(encoded_token2.as_slice())
         );
 
-        //todo the roundtrip here does not always yield expected result due to differences in tagged int encoding
-        // (tag 600 becomes tag 551 in the ->JSON-> transition)
-        // let comid_cbor: ConciseMidTagCbor = comid_json.try_into().unwrap();
-        // let mut encoded_token3 = vec![];
-        // let _ = into_writer(&comid_cbor, &mut encoded_token3);
-        // println!(
-        //     "Re-encoded ConciseMidTag with integer keys: {:?}",
-        //     buffer_to_hex(encoded_token3.as_slice())
-        // );
-        // assert_eq!(comid_cbor_bytes, encoded_token3);
+        let comid_cbor: ConciseMidTagCbor = comid_json.try_into().unwrap();
+        let mut encoded_token3 = vec![];
+        let _ = into_writer(&comid_cbor, &mut encoded_token3);
+        println!(
+            "Re-encoded ConciseMidTag with integer keys: {:?}",
+            buffer_to_hex(encoded_token3.as_slice())
+        );
+        assert_eq!(comid_cbor_bytes, encoded_token3);
     }
 }
 
+#[test]
+fn concise_mid_tag_convert_to_test() {
+    let comid_cbor_bytes = read_cbor(&Some("./tests/examples/comid-psa-iakpub.cbor".to_string()));
+    let mut comid: ConciseMidTagCbor = from_reader(comid_cbor_bytes.as_slice()).unwrap();
+    let keys = |c: &ConciseMidTagCbor| -> Vec<VerificationKeyTypeChoiceCbor> {
+        c.triples
+            .identity_triples
+            .as_ref()
+            .unwrap()
+            .iter()
+            .flat_map(|r| r.crypto_keys.clone())
+            .collect()
+    };
+    assert!(keys(&comid)
+        .iter()
+        .all(|k| matches!(k, VerificationKeyTypeChoiceCbor::Legacy(_))));
+
+    // the PSA implementation IDs are 32 bytes and retain the #6.600 form
+    let unconverted = comid.convert_to(CorimDraft::Current).unwrap();
+    assert!(!unconverted.is_empty());
+    assert!(unconverted.iter().all(|id| matches!(
+        id,
+        ClassIdTypeChoiceCbor::Int2(Required(IntType::Bytes(b))) if b.len() == 32
+    )));
+    assert!(keys(&comid).iter().all(|k| matches!(
+        k,
+        VerificationKeyTypeChoiceCbor::Key(CryptoKeyTypeChoice::Key(_))
+    )));
+    let mut encoded_token = vec![];
+    let _ = into_writer(&comid, &mut encoded_token);
+    assert_ne!(comid_cbor_bytes, encoded_token);
+
+    let mut comid2: ConciseMidTagCbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(comid, comid2);
+    assert!(comid2.convert_to(CorimDraft::Legacy03).unwrap().is_empty());
+    let mut encoded_token2 = vec![];
+    let _ = into_writer(&comid2, &mut encoded_token2);
+    assert_eq!(comid_cbor_bytes, encoded_token2);
+
+    // conditional endorsements have no -03 equivalent
+    let mut comid3: ConciseMidTagCbor = from_reader(comid_cbor_bytes.as_slice()).unwrap();
+    comid3.triples.conditional_endorsement_triples = Some(vec![]);
+    assert!(comid3.convert_to(CorimDraft::Legacy03).is_err());
+
+    // nothing is converted when a later check fails
+    let refval_cbor_bytes = read_cbor(&Some("./tests/examples/comid-psa-refval.cbor".to_string()));
+    let refval: ConciseMidTagCbor = from_reader(refval_cbor_bytes.as_slice()).unwrap();
+    let mut reference_triples = refval.triples.reference_triples.clone().unwrap();
+    reference_triples[0].measurement_map[0].value.cryptokeys = Some(vec![]);
+    comid.triples.reference_triples = Some(reference_triples);
+    let before = comid.clone();
+    assert!(comid.convert_to(CorimDraft::Legacy03).is_err());
+    assert_eq!(comid, before);
+
+    // flags are converted in conditional endorsement triples too
+    let mut comid4 = refval.clone();
+    let record = &comid4.triples.reference_triples.as_ref().unwrap()[0];
+    let mut measurement = record.measurement_map[0].clone();
+    measurement.value.flags = Some(FlagsTypeChoiceCbor::Legacy(10));
+    comid4.triples.conditional_endorsement_triples =
+        Some(vec![ConditionalEndorsementTripleRecordCbor {
+            conditions: vec![StatefulEnvironmentRecordCbor {
+                environment: record.environment_map.clone(),
+                claims_list: vec![measurement.clone()],
+            }],
+            endorsements: vec![EndorsedTripleRecordCbor {
+                environment_map: record.environment_map.clone(),
+                measurement_map: vec![measurement],
+            }],
+        }]);
+    comid4.convert_to(CorimDraft::Current).unwrap();
+    let cet = &comid4.triples.conditional_endorsement_triples.unwrap()[0];
+    assert!(cet.conditions[0]
+        .claims_list
+        .iter()
+        .chain(cet.endorsements[0].measurement_map.iter())
+        .all(|m| matches!(m.value.flags, Some(FlagsTypeChoiceCbor::Map(_)))));
+}
+
+#[test]
+fn concise_mid_tag_domain_environment_test() {
+    let comid_cbor_bytes = read_cbor(&Some("./tests/examples/comid-psa-iakpub.cbor".to_string()));
+    let mut comid: ConciseMidTagCbor = from_reader(comid_cbor_bytes.as_slice()).unwrap();
+    let env = |id: ClassIdTypeChoiceCbor| {
+        DomainTypeChoiceCbor::Environment(EnvironmentMapCbor {
+            class: Some(ClassMapCbor {
+                id: Some(id),
+                vendor: None,
+                model: None,
+                layer: None,
+                index: None,
+            }),
+            instance: None,
+            group: None,
+        })
+    };
+    let legacy = env(ClassIdTypeChoiceCbor::Int2(Required(IntType::Bytes(vec![
+        0x04, 0xd2,
+    ]))));
+    let spec = env(ClassIdTypeChoiceCbor::Int(Required(IntType::Int(1234))));
+    let record = |d: &DomainTypeChoiceCbor| DomainDependencyTripleRecordCbor {
+        domain_type_choice: d.clone(),
+        domain_type_choices: vec![DomainTypeChoiceCbor::U64(1), d.clone()],
+    };
+    comid.triples = TriplesMapCbor {
+        reference_triples: None,
+        endorsed_triples: None,
+        identity_triples: None,
+        attest_key_triples: None,
+        dependency_triples: Some(vec![record(&legacy)]),
+        membership_triples: Some(vec![record(&legacy)]),
+        coswid_triples: None,
+        conditional_endorsement_series_triples: None,
+        conditional_endorsement_triples: None,
+        other: None,
+    };
+
+    // environments in dependency and membership triples are converted
+    let mut converted = comid.clone();
+    converted.set_tagged_int_mode(TaggedIntMode::Spec).unwrap();
+    assert_eq!(
+        converted.triples.dependency_triples,
+        Some(vec![record(&spec)])
+    );
+    assert_eq!(
+        converted.triples.membership_triples,
+        Some(vec![record(&spec)])
+    );
+    converted.convert_to(CorimDraft::Legacy03).unwrap();
+    assert_eq!(converted, comid);
+    assert!(converted
+        .convert_to(CorimDraft::Current)
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        converted.triples.dependency_triples,
+        Some(vec![record(&spec)])
+    );
+}
+
 #[test]
 fn corim_locator_map_test() {
     //todo fix to feature hashentry
@@ -431,26 +607,93 @@ fn environment_map_to_cbor_group_only_test() {
 
 #[test]
 fn flags_map_test() {
-    // drop for the moment
-    // let mut encoded_token = vec![];
-    // let fm = FlagsMapCbor {
-    //     configured: Some(true),
-    //     secure: None,
-    //     recovery: Some(false),
-    //     debug: None,
-    //     replay_protected: Some(true),
-    //     integrity_protected: None,
-    //     other: None,
-    // };
-    // let _ = into_writer(&fm, &mut encoded_token);
-    // let fm_d: FlagsMapCbor = from_reader(encoded_token.clone().as_slice()).unwrap();
-    // assert_eq!(fm, fm_d);
-    // assert!(fm.configured.unwrap());
-    // assert!(!fm.recovery.unwrap());
-    // assert!(fm.replay_protected.unwrap());
-    // assert!(fm.secure.is_none());
-    // assert!(fm.debug.is_none());
-    // assert!(fm.integrity_protected.is_none());
+    let mut encoded_token = vec![];
+    let fm = FlagsMapCbor {
+        configured: Some(true),
+        secure: None,
+        recovery: Some(false),
+        debug: None,
+        replay_protected: Some(true),
+        integrity_protected: None,
+        other: None,
+    };
+    let _ = into_writer(&fm, &mut encoded_token);
+    // {0: true, 2: false, 4: true}
+    assert_eq!(hex!("a300f502f404f5").to_vec(), encoded_token);
+    let fm_d: FlagsMapCbor = from_reader(encoded_token.clone().as_slice()).unwrap();
+    assert_eq!(fm, fm_d);
+    assert!(fm.configured.unwrap());
+    assert!(!fm.recovery.unwrap());
+    assert!(fm.replay_protected.unwrap());
+    assert!(fm.secure.is_none());
+    assert!(fm.debug.is_none());
+    assert!(fm.integrity_protected.is_none());
+
+    // {0: true, 2: false, 4: true, 100: "ext"}
+    let expected = hex!("a400f502f404f5186463657874");
+    let fm_d: FlagsMapCbor = from_reader(expected.as_slice()).unwrap();
+    assert_eq!(fm_d.other.as_ref().unwrap().len(), 1);
+    let mut encoded_token = vec![];
+    let _ = into_writer(&fm_d, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    let fm: FlagsMap = fm_d.try_into().unwrap();
+    let json = serde_json::to_string(&fm).unwrap();
+    let fm2: FlagsMap = serde_json::from_str(&json).unwrap();
+    assert_eq!(fm, fm2);
+}
+
+#[test]
+fn flags_type_choice_test() {
+    // {3: 10} where 10 is the legacy bit field (not-secure and debug)
+    let expected = hex!("a1030a");
+    let mvm: MeasurementValuesMapCbor = from_reader(expected.as_slice()).unwrap();
+    assert_eq!(mvm.flags, Some(FlagsTypeChoiceCbor::Legacy(10)));
+    let mut encoded_token = vec![];
+    let _ = into_writer(&mvm, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    let mvm: MeasurementValuesMap = mvm.try_into().unwrap();
+    let fm = mvm.flags.unwrap().to_flags_map();
+    assert_eq!(fm.configured, Some(true));
+    assert_eq!(fm.secure, Some(false));
+    assert_eq!(fm.recovery, Some(false));
+    assert_eq!(fm.debug, Some(true));
+    assert_eq!(fm.replay_protected, None);
+
+    // {3: {1: true}}
+    let expected = hex!("a103a101f5");
+    let mvm: MeasurementValuesMapCbor = from_reader(expected.as_slice()).unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&mvm, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+    let mvm: MeasurementValuesMap = mvm.try_into().unwrap();
+    let reference = mvm.flags.unwrap().to_flags_map();
+
+    // absent flags in the reference are "don't care"
+    assert!(reference.compare(&fm).is_err());
+    let mut evidence = fm.clone();
+    evidence.secure = Some(true);
+    assert!(reference.compare(&evidence).is_ok());
+    evidence.secure = None;
+    assert!(reference.compare(&evidence).is_err());
+    // legacy bit fields are expressed as a flags-map per the current draft and back again
+    let legacy = FlagsTypeChoiceCbor::Legacy(10);
+    let current = legacy.to_draft(CorimDraft::Current).unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&current, &mut encoded_token);
+    // {0: true, 1: false, 2: false, 3: true}
+    assert_eq!(hex!("a400f501f402f403f5").to_vec(), encoded_token);
+    assert_eq!(legacy, current.to_draft(CorimDraft::Legacy03).unwrap());
+    assert!(FlagsTypeChoiceCbor::Legacy(0x10)
+        .to_draft(CorimDraft::Current)
+        .is_err());
+    let partial: MeasurementValuesMapCbor = from_reader(expected.as_slice()).unwrap();
+    assert!(partial
+        .flags
+        .unwrap()
+        .to_draft(CorimDraft::Legacy03)
+        .is_err());
 }
 
 #[test]
@@ -465,12 +708,79 @@ fn measurement_map_test() {
 
 #[test]
 fn measurement_values_map_test() {
-    //todo
+    // {4: 560(h'DEADBEEF'), 5: h'FFFF0000'}
+    let expected = hex!("a204d9023044deadbeef0544ffff0000");
+    let mvm_cbor: MeasurementValuesMapCbor = from_reader(expected.as_slice()).unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&mvm_cbor, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    let mvm: MeasurementValuesMap = mvm_cbor.try_into().unwrap();
+    assert_eq!(mvm.raw_value_mask, Some(vec![0xff, 0xff, 0, 0]));
+    assert!(mvm.raw_value_matches(&hex!("deadcafe")).unwrap());
+    assert!(!mvm.raw_value_matches(&hex!("beefbeef")).unwrap());
+
+    let mvm_cbor2: MeasurementValuesMapCbor = mvm.clone().try_into().unwrap();
+    let mut encoded_token2 = vec![];
+    let _ = into_writer(&mvm_cbor2, &mut encoded_token2);
+    assert_eq!(expected.to_vec(), encoded_token2);
+
+    let json = serde_json::to_string(&mvm).unwrap();
+    let mvm2: MeasurementValuesMap = serde_json::from_str(&json).unwrap();
+    assert_eq!(mvm, mvm2);
+}
+
+#[test]
+fn measurement_values_map_current_draft_test() {
+    // {13: [558({1: 2})], 14: {0: [[1, h'DEADBEEF']], "pcr": [[1, h'00']]}}
+    let expected = hex!("a20d81d9022ea101020ea20081820144deadbeef6370637281820141 00");
+    let mvm_cbor: MeasurementValuesMapCbor = from_reader(expected.as_slice()).unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&mvm_cbor, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    match &mvm_cbor.cryptokeys.as_ref().unwrap()[0] {
+        CryptoKeyTypeChoice::CoseKey(k) => assert!(k.0.is_map()),
+        _ => panic!(),
+    }
+    let regs = mvm_cbor.integrity_registers.as_ref().unwrap();
+    assert_eq!(
+        regs.0[&IntegrityRegisterIdTypeChoice::Uint(0)][0].hash_value,
+        hex!("deadbeef")
+    );
+    assert_eq!(
+        regs.0[&IntegrityRegisterIdTypeChoice::Text("pcr".to_string())][0].hash_alg_id,
+        1
+    );
+
+    let mvm: MeasurementValuesMap = mvm_cbor.try_into().unwrap();
+    let _ = serde_json::to_string(&mvm).unwrap();
+    let mvm_cbor2: MeasurementValuesMapCbor = mvm.try_into().unwrap();
+    let mut encoded_token2 = vec![];
+    let _ = into_writer(&mvm_cbor2, &mut encoded_token2);
+    assert_eq!(expected.to_vec(), encoded_token2);
 }
 
 #[test]
 fn protected_corim_header_map_test() {
-    //todo
+    // {1: -7, 3: "application/rim+cbor", 4: h'0102', 15: {1: "ACME", 4: 1669139948}}
+    let expected = hex!(
+        "a4012603746170706c69636174696f6e2f72696d2b63626f7204420102 0fa2016441434d45041a637d0dec"
+    );
+    let h: ProtectedCorimHeaderMapCbor = from_reader(expected.as_slice()).unwrap();
+    assert_eq!(h.alg_id, -7);
+    assert_eq!(h.content_type, corim::CORIM_CONTENT_TYPE);
+    assert!(h.meta.is_none());
+    let claims = h.cwt_claims.as_ref().unwrap();
+    assert_eq!(claims.iss, "ACME");
+    assert_eq!(claims.exp, Some(1669139948));
+
+    let mut encoded_token = vec![];
+    let _ = into_writer(&h, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    let h_j: ProtectedCorimHeaderMap = h.try_into().unwrap();
+    let _ = serde_json::to_string(&h_j).unwrap();
 }
 
 #[test]
@@ -496,7 +806,35 @@ fn tag_identity_map_test() {
 
 #[test]
 fn triples_map_test() {
-    //todo
+    // {10: [[[[{0: {1: "ACME"}}, [{1: {1: 552(5)}}]]], [[{0: {1: "ACME"}}, [{1: {1: 552(5)}}]]]]]}
+    let expected = hex!(
+        "a10a81828182a100a1016441434d4581a101a101d902280581 82a100a1016441434d4581a101a101d9022805"
+    );
+    let t: TriplesMapCbor = from_reader(expected.as_slice()).unwrap();
+    let cet = &t.conditional_endorsement_triples.as_ref().unwrap()[0];
+    assert_eq!(cet.conditions[0].claims_list.len(), 1);
+    assert_eq!(cet.endorsements[0].measurement_map.len(), 1);
+    let mut encoded_token = vec![];
+    let _ = into_writer(&t, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    // {8: [[[{0: {1: "ACME"}}, [{1: {1: 552(5)}}]], [[[{1: {1: 552(5)}}], [{1: {1: 552(5)}}]]]]]}
+    let expected2 = hex!(
+        "a1088182 82a100a1016441434d4581a101a101d9022805 818281a101a101d902280581a101a101d9022805"
+    );
+    let t2: TriplesMapCbor = from_reader(expected2.as_slice()).unwrap();
+    let cest = &t2.conditional_endorsement_series_triples.as_ref().unwrap()[0];
+    assert_eq!(cest.series[0].selection, cest.series[0].addition);
+    let mut encoded_token2 = vec![];
+    let _ = into_writer(&t2, &mut encoded_token2);
+    assert_eq!(expected2.to_vec(), encoded_token2);
+
+    let t2_j: TriplesMap = t2.try_into().unwrap();
+    let _ = serde_json::to_string(&t2_j).unwrap();
+    let t2_c: TriplesMapCbor = t2_j.try_into().unwrap();
+    let mut encoded_token3 = vec![];
+    let _ = into_writer(&t2_c, &mut encoded_token3);
+    assert_eq!(expected2.to_vec(), encoded_token3);
 }
 
 #[test]
@@ -522,5 +860,51 @@ fn verification_key_map_test() {
 
 #[test]
 fn version_map_test() {
-    //todo
+    // {0: "2.3.1", 1: 16384}
+    let expected = hex!("a20065322e332e3101194000");
+    let vm_cbor: VersionMapCbor = from_reader(expected.as_slice()).unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&vm_cbor, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    let vm: VersionMap = vm_cbor.clone().try_into().unwrap();
+    assert!(vm.is_at_least("2.3.1").unwrap());
+    assert!(vm.is_at_least("2.3.1-rc.1").unwrap());
+    assert!(!vm.is_at_least("2.10.0").unwrap());
+    assert!(vm.is_at_least("2.3").is_err());
+
+    let older = VersionMap {
+        version: "2.3.1-rc.1".to_string(),
+        version_scheme: Some(VersionScheme::Known(VersionSchemeKnown::Semver)),
+    };
+    assert_eq!(vm.compare(&older).unwrap(), Ordering::Greater);
+    assert_eq!(older.compare(&vm).unwrap(), Ordering::Less);
+
+    // absent version scheme is treated as multipartnumeric
+    let mp = VersionMap {
+        version: "2.3.1".to_string(),
+        version_scheme: None,
+    };
+    assert!(mp.is_at_least("2.3").unwrap());
+    assert!(!mp.is_at_least("2.3.1.1").unwrap());
+    assert!(mp.compare(&vm).is_err());
+
+    // invalid versions cannot be compared
+    let invalid = VersionMap {
+        version: "x".to_string(),
+        version_scheme: None,
+    };
+    assert!(invalid.compare(&mp).is_err());
+
+    // the same helpers are available on the CBOR form
+    let older_cbor: VersionMapCbor = older.try_into().unwrap();
+    assert!(vm_cbor.is_at_least("2.3.1").unwrap());
+    assert!(!vm_cbor.is_at_least("2.10.0").unwrap());
+    assert_eq!(vm_cbor.compare(&older_cbor).unwrap(), Ordering::Greater);
+    let mp_cbor: VersionMapCbor = mp.try_into().unwrap();
+    assert!(vm_cbor.compare(&mp_cbor).is_err());
+    assert_eq!(
+        mp_cbor.parse_version().unwrap(),
+        common::version::Version::parse(&VersionSchemeKnown::Multipartnumeric, "2.3.1").unwrap()
+    );
 }
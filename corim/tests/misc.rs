@@ -2,7 +2,9 @@
 use ciborium::de::from_reader;
 use ciborium::ser::into_writer;
 use ciborium::value::Value;
-use common::{OidType, TaggedOidTypeCbor};
+use common::{BytesType, OidType, TaggedOidTypeCbor};
+use corim::choices::{masked_equal, RawValueTypeChoice};
+use corim::{MaskedRawValue, TaggedBytes, TaggedMaskedRawValue};
 use hex_literal::hex;
 mod utils;
 use crate::utils::buffer_to_hex;
@@ -62,10 +64,59 @@ fn tagged_min_svn_test() {
 
 #[test]
 fn raw_value_type_choice_test() {
-    //todo
+    // 560(h'0102')
+    let expected = hex!("d90230420102");
+    let v: Value = from_reader(expected.as_slice()).unwrap();
+    let rv = RawValueTypeChoice::try_from(&v).unwrap();
+    assert_eq!(
+        rv,
+        RawValueTypeChoice::Bytes(TaggedBytes {
+            0: BytesType::Bytes(vec![1, 2]),
+        })
+    );
+    assert_eq!(rv.value(), &[1, 2]);
+    assert_eq!(rv.mask(), None);
+    let mut encoded_token = vec![];
+    let _ = into_writer(&rv, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    // 563([h'0102', h'FF00'])
+    let expected = hex!("d902338242010242ff00");
+    let v: Value = from_reader(expected.as_slice()).unwrap();
+    let rv = RawValueTypeChoice::try_from(&v).unwrap();
+    assert_eq!(
+        rv,
+        RawValueTypeChoice::MaskedRawValue(TaggedMaskedRawValue {
+            0: MaskedRawValue(vec![1, 2], vec![0xff, 0]),
+        })
+    );
+    assert_eq!(rv.mask(), Some([0xff, 0].as_slice()));
+    assert!(rv.matches(None, &[1, 0x7f]));
+    assert!(!rv.matches(None, &[2, 2]));
+    let mut encoded_token = vec![];
+    let _ = into_writer(&rv, &mut encoded_token);
+    assert_eq!(expected.to_vec(), encoded_token);
+
+    // 560("text") is not a raw value
+    let v: Value = from_reader(hex!("d9023064746578740a").as_slice()).unwrap();
+    assert!(RawValueTypeChoice::try_from(&v).is_err());
 }
 
 #[test]
 fn raw_value_mask_type_choice_test() {
-    //todo
+    assert!(masked_equal(&[0x12, 0x34], &[0x12, 0x34], None));
+    assert!(!masked_equal(&[0x12, 0x34], &[0x12, 0x35], None));
+    assert!(masked_equal(
+        &[0x12, 0x34],
+        &[0x12, 0x35],
+        Some(&[0xff, 0xf0])
+    ));
+    assert!(!masked_equal(
+        &[0x12, 0x34],
+        &[0x13, 0x34],
+        Some(&[0xff, 0xf0])
+    ));
+    // lengths must agree
+    assert!(!masked_equal(&[0x12, 0x34], &[0x12], None));
+    assert!(!masked_equal(&[0x12, 0x34], &[0x12, 0x34], Some(&[0xff])));
 }
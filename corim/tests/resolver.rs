@@ -0,0 +1,229 @@
+use ciborium::ser::into_writer;
+use common::arrays::HashEntry;
+use common::digest::HashAlgorithm;
+use corim::builder::*;
+use corim::choices::*;
+use corim::maps::*;
+use corim::resolver::*;
+use std::path::{Path, PathBuf};
+
+fn corim(id: &str, deps: &[(&str, Option<HashEntry>)]) -> Vec<u8> {
+    let comid = ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str(id.to_string()))
+        .triples(
+            TriplesMapBuilder::new()
+                .reference_value(
+                    EnvironmentMapBuilder::new().vendor("ACME").build().unwrap(),
+                    vec![MeasurementMapBuilder::new().svn(1).build().unwrap()],
+                )
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    let mut b = CorimMapBuilder::new()
+        .id(CorimIdTypeChoice::Str(id.to_string()))
+        .comid(&comid);
+    for (href, thumbprint) in deps {
+        b = b.dependent_rim(CorimLocatorMap {
+            href: href.to_string(),
+            thumbprint: thumbprint.clone(),
+        });
+    }
+    let mut encoded = vec![];
+    into_writer(&b.build_cbor().unwrap(), &mut encoded).unwrap();
+    encoded
+}
+
+fn thumbprint(encoded: &[u8]) -> Option<HashEntry> {
+    Some(HashEntry::compute(HashAlgorithm::Sha256, encoded))
+}
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("corim_resolver_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &Path, name: &str, encoded: &[u8]) {
+    std::fs::write(dir.join(name), encoded).unwrap();
+}
+
+/// Stands in for a remote repository by mapping https URIs to files in a local directory
+struct LocalRepository(PathBuf);
+
+impl RimFetcher for LocalRepository {
+    fn fetch(&self, href: &str) -> Result<Vec<u8>, String> {
+        let name = href
+            .strip_prefix("https://rims.example/")
+            .ok_or_else(|| format!("Unknown host in {}", href))?;
+        std::fs::read(self.0.join(name)).map_err(|e| e.to_string())
+    }
+}
+
+#[test]
+fn resolver_file_test() {
+    let dir = test_dir("file");
+    let c = corim("c", &[]);
+    write(&dir, "c.cbor", &c);
+    let b = corim("b", &[("c.cbor", thumbprint(&c))]);
+    write(&dir, "b.cbor", &b);
+    let a = corim(
+        "a",
+        &[
+            ("b.cbor", thumbprint(&b)),
+            ("c.cbor", None),
+            (&format!("file://{}", dir.join("c.cbor").display()), None),
+        ],
+    );
+    write(&dir, "a.cbor", &a);
+
+    let resolver = RimResolver::new().with_fetcher("file", FileFetcher::with_base_dir(&dir));
+    let bundle = resolver.resolve_href("a.cbor").unwrap();
+    assert_eq!(bundle.root.id, CorimIdTypeChoice::Str("a".to_string()));
+    assert_eq!(bundle.dependencies.len(), 3);
+    assert_eq!(bundle.corims().len(), 4);
+    assert!(bundle.get("b.cbor").unwrap().thumbprint_verified);
+    assert!(bundle.get("c.cbor").unwrap().thumbprint_verified);
+    assert_eq!(bundle.get("c.cbor").unwrap().encoded, c);
+    assert_eq!(
+        bundle.dependencies[2].corim.id,
+        CorimIdTypeChoice::Str("c".to_string())
+    );
+
+    // the root can also be supplied directly
+    let root = decode_corim(&a).unwrap();
+    assert_eq!(resolver.resolve(&root).unwrap(), bundle);
+}
+
+#[test]
+fn resolver_https_test() {
+    let dir = test_dir("https");
+    let b = corim("b", &[]);
+    write(&dir, "b.cbor", &b);
+    let a = corim("a", &[("https://rims.example/b.cbor", thumbprint(&b))]);
+    let root = decode_corim(&a).unwrap();
+
+    assert!(RimResolver::new().resolve(&root).is_err());
+    let resolver = RimResolver::new().with_fetcher("https", LocalRepository(dir.clone()));
+    let bundle = resolver.resolve(&root).unwrap();
+    assert_eq!(bundle.dependencies.len(), 1);
+    assert_eq!(
+        bundle.dependencies[0].corim.id,
+        CorimIdTypeChoice::Str("b".to_string())
+    );
+
+    // a modified dependency fails thumbprint verification
+    write(&dir, "b.cbor", &corim("b2", &[]));
+    let err = resolver.resolve(&root).unwrap_err();
+    assert!(err.contains("Thumbprint mismatch"));
+}
+
+#[test]
+fn resolver_error_test() {
+    let dir = test_dir("error");
+    write(&dir, "x.cbor", &corim("x", &[("y.cbor", None)]));
+    write(&dir, "y.cbor", &corim("y", &[("x.cbor", None)]));
+    write(&dir, "z.cbor", &corim("z", &[("missing.cbor", None)]));
+    let resolver = RimResolver::new().with_fetcher("file", FileFetcher::with_base_dir(&dir));
+
+    let err = resolver.resolve_href("x.cbor").unwrap_err();
+    assert!(err.contains("cycle"));
+    assert!(resolver.resolve_href("z.cbor").is_err());
+    assert!(RimResolver::new()
+        .with_fetcher("file", FileFetcher::with_base_dir(&dir))
+        .with_max_depth(1)
+        .resolve(&decode_corim(&corim("w", &[("x.cbor", None)])).unwrap())
+        .is_err());
+    assert!(decode_corim(&[0xd9, 0x01, 0xf6, 0x80]).is_err());
+}
+
+#[test]
+fn resolver_depth_test() {
+    let dir = test_dir("depth");
+    write(&dir, "d2.cbor", &corim("d2", &[]));
+    let d1 = corim("d1", &[("d2.cbor", None)]);
+    write(&dir, "d1.cbor", &d1);
+    let root = decode_corim(&d1).unwrap();
+
+    // the root is not counted whether it is retrieved or supplied directly
+    for max_depth in [0, 1] {
+        let resolver = RimResolver::new()
+            .with_fetcher("file", FileFetcher::with_base_dir(&dir))
+            .with_max_depth(max_depth);
+        assert_eq!(resolver.resolve_href("d1.cbor").is_ok(), max_depth == 1);
+        assert_eq!(resolver.resolve(&root).is_ok(), max_depth == 1);
+    }
+}
+
+#[test]
+fn resolver_relative_test() {
+    let dir = test_dir("relative");
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    let q = corim("q", &[]);
+    write(&dir.join("sub"), "q.cbor", &q);
+    write(&dir, "q.cbor", &corim("other", &[]));
+    write(
+        &dir.join("sub"),
+        "p.cbor",
+        &corim("p", &[("q.cbor", thumbprint(&q))]),
+    );
+
+    // relative hrefs are resolved against the CoRIM that names them
+    let resolver = RimResolver::new().with_fetcher("file", FileFetcher::with_base_dir(&dir));
+    let bundle = resolver.resolve_href("sub/p.cbor").unwrap();
+    assert_eq!(bundle.get("sub/q.cbor").unwrap().encoded, q);
+    assert!(bundle.get("q.cbor").is_none());
+
+    let a = corim("a", &[("b.cbor", None), ("/c.cbor", None)]);
+    write(&dir, "a.cbor", &a);
+    write(&dir, "b.cbor", &corim("b", &[]));
+    write(&dir, "c.cbor", &corim("c", &[]));
+    let resolver = RimResolver::new().with_fetcher("https", LocalRepository(dir.clone()));
+    let bundle = resolver
+        .resolve_href("https://rims.example/sub/../a.cbor")
+        .unwrap();
+    let hrefs: Vec<&str> = bundle
+        .dependencies
+        .iter()
+        .map(|d| d.href.as_str())
+        .collect();
+    assert_eq!(
+        hrefs,
+        vec!["https://rims.example/b.cbor", "https://rims.example/c.cbor"]
+    );
+
+    // dot segments are removed before checking for cycles
+    write(&dir, "x.cbor", &corim("x", &[("sub/../x.cbor", None)]));
+    let resolver = RimResolver::new().with_fetcher("file", FileFetcher::with_base_dir(&dir));
+    let err = resolver.resolve_href("./x.cbor").unwrap_err();
+    assert!(err.contains("cycle"));
+}
+
+#[test]
+fn resolver_local_from_remote_test() {
+    let dir = test_dir("local");
+    let b = corim("b", &[]);
+    write(&dir, "b.cbor", &b);
+    let local = format!("file://{}", dir.join("b.cbor").display());
+    write(&dir, "a.cbor", &corim("a", &[(&local, None)]));
+
+    // a remote CoRIM may not name a local file unless allowed
+    let resolver = RimResolver::new().with_fetcher("https", LocalRepository(dir.clone()));
+    let err = resolver
+        .resolve_href("https://rims.example/a.cbor")
+        .unwrap_err();
+    assert!(err.contains("local file"));
+    let bundle = RimResolver::new()
+        .with_fetcher("https", LocalRepository(dir.clone()))
+        .with_local_from_remote(true)
+        .resolve_href("https://rims.example/a.cbor")
+        .unwrap();
+    assert_eq!(bundle.get(&local).unwrap().encoded, b);
+
+    // a local CoRIM may name a local file
+    let bundle = RimResolver::new()
+        .resolve_href(dir.join("a.cbor").to_str().unwrap())
+        .unwrap();
+    assert_eq!(bundle.dependencies.len(), 1);
+}
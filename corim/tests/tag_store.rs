@@ -0,0 +1,172 @@
+use ciborium::de::from_reader;
+use common::UuidType;
+use corim::builder::*;
+use corim::choices::*;
+use corim::maps::*;
+use corim::tag_store::*;
+
+mod utils;
+use crate::utils::*;
+
+fn comid(
+    id: &str,
+    version: u64,
+    links: &[(&str, TagRelTypeChoiceKnown)],
+    svn: u64,
+) -> ConciseMidTag {
+    let mut b = ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str(id.to_string()))
+        .tag_version(version)
+        .triples(
+            TriplesMapBuilder::new()
+                .reference_value(
+                    EnvironmentMapBuilder::new().vendor("ACME").build().unwrap(),
+                    vec![MeasurementMapBuilder::new().svn(svn).build().unwrap()],
+                )
+                .build()
+                .unwrap(),
+        );
+    for (to, rel) in links {
+        b = b.linked_tag(LinkedTagMap {
+            linked_tag_id: TagIdTypeChoice::Str(to.to_string()),
+            tag_rel: TagRelTypeChoice::Known(rel.clone()),
+        });
+    }
+    b.build().unwrap()
+}
+
+fn key(id: &str) -> TagKey {
+    TagKey::Text(id.to_string())
+}
+
+#[test]
+fn tag_store_version_test() {
+    let mut store = TagStore::new();
+    store.insert_comid(comid("a", 0, &[], 1)).unwrap();
+    store.insert_comid(comid("a", 2, &[], 3)).unwrap();
+    store.insert_comid(comid("a", 1, &[], 2)).unwrap();
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.versions(&key("a")), vec![0, 1, 2]);
+    assert_eq!(
+        store.get(&key("a"), None),
+        Some(&StoredTag::Comid(comid("a", 2, &[], 3)))
+    );
+    assert!(store.get(&key("a"), Some(5)).is_none());
+
+    let r = store.resolve();
+    assert_eq!(r.in_force.get(&key("a")), Some(&2));
+    assert!(r.issues.is_empty());
+    let rvs = store.effective_reference_values();
+    assert_eq!(rvs.len(), 1);
+    assert_eq!(
+        rvs[0].measurement_map[0].value.svn,
+        Some(SvnTypeChoice::TaggedSvn(ciborium::tag::Required(3)))
+    );
+}
+
+#[test]
+fn tag_store_replaces_test() {
+    use TagRelTypeChoiceKnown::*;
+    let mut store = TagStore::new();
+    store.insert_comid(comid("a", 0, &[], 1)).unwrap();
+    store
+        .insert_comid(comid("b", 0, &[("a", Replaces)], 2))
+        .unwrap();
+    store
+        .insert_comid(comid("c", 0, &[("b", Replaces)], 3))
+        .unwrap();
+    store
+        .insert_comid(comid("s", 0, &[("c", Supplements)], 4))
+        .unwrap();
+    store
+        .insert_comid(comid("t", 0, &[("a", Supplements)], 5))
+        .unwrap();
+
+    let r = store.resolve();
+    assert!(r.issues.is_empty());
+    assert_eq!(
+        r.in_force.keys().cloned().collect::<Vec<TagKey>>(),
+        vec![key("c"), key("s")]
+    );
+    assert_eq!(r.superseded.get(&key("a")), Some(&key("b")));
+    assert_eq!(r.superseded.get(&key("b")), Some(&key("c")));
+    assert_eq!(r.superseded.get(&key("t")), Some(&key("a")));
+    assert_eq!(store.effective_comids().len(), 2);
+    assert_eq!(store.effective_reference_values().len(), 2);
+}
+
+#[test]
+fn tag_store_link_issues_test() {
+    use TagRelTypeChoiceKnown::*;
+    let mut store = TagStore::new();
+    store
+        .insert_comid(comid("x", 0, &[("y", Replaces)], 1))
+        .unwrap();
+    store
+        .insert_comid(comid("y", 0, &[("x", Replaces)], 2))
+        .unwrap();
+    store
+        .insert_comid(comid("s", 0, &[("x", Supplements)], 3))
+        .unwrap();
+    store
+        .insert_comid(comid("d", 0, &[("missing", Supplements)], 4))
+        .unwrap();
+    store
+        .insert_comid(comid("e", 0, &[("missing", Replaces)], 5))
+        .unwrap();
+
+    let r = store.resolve();
+    assert_eq!(
+        r.in_force.keys().cloned().collect::<Vec<TagKey>>(),
+        vec![key("d"), key("e")]
+    );
+    assert!(r.issues.contains(&LinkIssue::Dangling {
+        from: key("d"),
+        to: key("missing"),
+        relation: TagRelation::Supplements,
+    }));
+    assert!(r.issues.contains(&LinkIssue::Dangling {
+        from: key("e"),
+        to: key("missing"),
+        relation: TagRelation::Replaces,
+    }));
+    let cycle = r
+        .issues
+        .iter()
+        .find_map(|i| match i {
+            LinkIssue::Cycle(c) => Some(c.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(cycle.len(), 2);
+    assert!(cycle.contains(&key("x")) && cycle.contains(&key("y")));
+    assert!(!r.is_in_force(&key("s")));
+}
+
+#[test]
+fn tag_store_corim_test() {
+    let mut store = TagStore::new();
+    let no_identity = ConciseMidTag {
+        tag_identity: None,
+        ..comid("a", 0, &[], 1)
+    };
+    assert!(store.insert_comid(no_identity).is_err());
+
+    let corim_bytes = read_cbor(&Some("./tests/examples/corim_1.cbor".to_string()));
+    let corim: CorimMapCbor = from_reader(corim_bytes.as_slice()).unwrap();
+    store.insert_corim(&corim).unwrap();
+    assert_eq!(store.len(), corim.tags.len());
+
+    let r = store.resolve();
+    assert_eq!(r.in_force.len(), store.len());
+    for k in r.in_force.keys() {
+        if let TagKey::Bytes(b) = k {
+            assert!(store
+                .get(
+                    &TagKey::from(&TagIdTypeChoice::Uuid(UuidType::Uuid(b.clone()))),
+                    None
+                )
+                .is_some());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use corim::appraisal::*;
+use corim::builder::*;
+use corim::choices::*;
+use corim::maps::*;
+use corim::validity::*;
+
+fn validity(not_before: Option<i64>, not_after: i64) -> ValidityMap {
+    ValidityMap {
+        not_before,
+        not_after,
+    }
+}
+
+fn comid() -> ConciseMidTag {
+    ConciseMidTagBuilder::new()
+        .tag_id(TagIdTypeChoice::Str("comid".to_string()))
+        .triples(
+            TriplesMapBuilder::new()
+                .reference_value(
+                    EnvironmentMapBuilder::new().vendor("ACME").build().unwrap(),
+                    vec![MeasurementMapBuilder::new().svn(1).build().unwrap()],
+                )
+                .build()
+                .unwrap(),
+        )
+        .build()
+        .unwrap()
+}
+
+fn corim(id: &str, rim_validity: Option<ValidityMap>) -> CorimMap {
+    let mut b = CorimMapBuilder::new()
+        .id(CorimIdTypeChoice::Str(id.to_string()))
+        .comid(&comid());
+    if let Some(v) = rim_validity {
+        b = b.validity(v);
+    }
+    b.build().unwrap()
+}
+
+#[test]
+fn validity_map_status_test() {
+    let v = validity(Some(100), 200);
+    assert_eq!(v.status(99), ValidityStatus::NotYetValid(100));
+    assert_eq!(v.status(100), ValidityStatus::Valid);
+    assert_eq!(v.status(200), ValidityStatus::Valid);
+    assert_eq!(v.status(201), ValidityStatus::Expired(200));
+    assert_eq!(validity(None, 200).status(i64::MIN), ValidityStatus::Valid);
+
+    assert_eq!(
+        v.intersect(&validity(Some(150), 300)),
+        Some(validity(Some(150), 200))
+    );
+    assert_eq!(
+        v.intersect(&validity(None, 150)),
+        Some(validity(Some(100), 150))
+    );
+    assert_eq!(
+        validity(None, 300).intersect(&validity(None, 150)),
+        Some(validity(None, 150))
+    );
+    assert_eq!(v.intersect(&validity(Some(201), 300)), None);
+}
+
+#[test]
+fn signer_validity_test() {
+    let cwt = CwtClaimsMap {
+        iss: "ACME".to_string(),
+        sub: None,
+        exp: Some(200),
+        nbf: None,
+        other: None,
+    };
+    assert_eq!(cwt.validity(), Some(validity(None, 200)));
+    let mut header = ProtectedCorimHeaderMap {
+        alg_id: -7,
+        content_type: corim::CORIM_CONTENT_TYPE.to_string(),
+        issuer_key_id: vec![1, 2, 3],
+        meta: None,
+        cwt_claims: Some(cwt),
+    };
+    assert_eq!(header.signer_validity(), Some(validity(None, 200)));
+    header.meta = Some(CorimMetaMap {
+        signer: CorimSignerMap {
+            entity_name: EntityNameTypeChoice::Text("ACME".to_string()),
+            reg_id: None,
+        },
+        validity: Some(validity(Some(100), 150)),
+    });
+    assert_eq!(header.signer_validity(), Some(validity(Some(100), 150)));
+
+    // nbf alone bounds the start of the period
+    let nbf_only = CwtClaimsMap {
+        iss: "ACME".to_string(),
+        sub: None,
+        exp: None,
+        nbf: Some(300),
+        other: None,
+    };
+    assert_eq!(nbf_only.validity(), Some(validity(Some(300), i64::MAX)));
+    let c = corim("rim", None);
+    assert_eq!(
+        evaluate_rim(&c, nbf_only.validity().as_ref(), &150).status,
+        ValidityStatus::NotYetValid(300)
+    );
+    assert!(evaluate_rim(&c, nbf_only.validity().as_ref(), &350).is_valid());
+    let neither = CwtClaimsMap {
+        nbf: None,
+        ..nbf_only
+    };
+    assert_eq!(neither.validity(), None);
+}
+
+#[test]
+fn evaluate_rim_test() {
+    let c = corim("rim", Some(validity(Some(100), 200)));
+    let r = evaluate_rim(&c, None, &150);
+    assert!(r.is_valid());
+    assert_eq!(r.period, Some(validity(Some(100), 200)));
+    assert_eq!(
+        evaluate_rim(&c, None, &250).status,
+        ValidityStatus::Expired(200)
+    );
+
+    // the signer validity narrows the RIM validity
+    let signer = validity(Some(120), 180);
+    assert_eq!(
+        evaluate_rim(&c, Some(&signer), &190).status,
+        ValidityStatus::Expired(180)
+    );
+    assert_eq!(
+        evaluate_rim(&c, Some(&signer), &110).status,
+        ValidityStatus::NotYetValid(120)
+    );
+    assert_eq!(
+        evaluate_rim(&c, Some(&validity(Some(300), 400)), &150).status,
+        ValidityStatus::Disjoint
+    );
+
+    let unbounded = corim("unbounded", None);
+    assert!(evaluate_rim(&unbounded, None, &SystemClock).is_valid());
+    assert!(!evaluate_rim(&unbounded, Some(&validity(None, 0)), &SystemClock).is_valid());
+}
+
+#[test]
+fn appraise_corims_test() {
+    let evidence = vec![EvidenceRecord::from(
+        comid().triples.reference_triples.unwrap()[0].clone(),
+    )];
+    let corims = vec![
+        VerifiedCorim::from(corim("current", Some(validity(None, 200)))),
+        VerifiedCorim {
+            corim: corim("signer-expired", Some(validity(None, 200))),
+            signer_validity: Some(validity(None, 100)),
+        },
+    ];
+
+    let r = appraise_corims(&corims, &evidence, &150).unwrap();
+    assert!(r.is_success());
+    assert_eq!(r.reference_results.len(), 1);
+    assert_eq!(r.stale_rims.len(), 1);
+    assert_eq!(
+        r.stale_rims[0].id,
+        CorimIdTypeChoice::Str("signer-expired".to_string())
+    );
+
+    let r = appraise_corims(&corims, &evidence, &250).unwrap();
+    assert!(!r.is_success());
+    assert!(r.reference_results.is_empty());
+    assert_eq!(r.stale_rims.len(), 2);
+}
@@ -0,0 +1,402 @@
+//! Creation and verification of `COSE_Sign1` and `COSE_Mac0` structures
+//!
+//! The [CoseSigner], [CoseVerifier] and [CoseMacKey] traits abstract the cryptographic operations
+//! so that keys held in hardware or by other libraries can be used. Implementations are provided
+//! for ES256 (using the `p256` crate), EdDSA (using the `ed25519-dalek` crate) and HMAC with SHA-2
+//! (see [HmacKey]).
+
+use alloc::string::{String, ToString};
+use alloc::{format, vec, vec::Vec};
+
+use ciborium::{de::from_reader, ser::into_writer, value::Value};
+use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha384, Sha512};
+use subtle::ConstantTimeEq;
+
+use crate::arrays::{CoseMac0Cbor, CoseSign1Cbor};
+use crate::choices::EmptyOrSerializedMap;
+use crate::maps::HeaderMapCbor;
+use common::{BinaryOrNil, BytesType, TextOrInt};
+
+/// CBOR tag for `COSE_Sign1_Tagged`
+pub const COSE_SIGN1_TAG: u64 = 18;
+/// CBOR tag for `COSE_Mac0_Tagged`
+pub const COSE_MAC0_TAG: u64 = 17;
+
+/// ECDSA with SHA-256, see [COSE Algorithms Section 2.1].
+///
+/// [COSE Algorithms Section 2.1]: https://datatracker.ietf.org/doc/html/rfc9053#section-2.1
+pub const ALG_ES256: i64 = -7;
+/// EdDSA, see [COSE Algorithms Section 2.2].
+///
+/// [COSE Algorithms Section 2.2]: https://datatracker.ietf.org/doc/html/rfc9053#section-2.2
+pub const ALG_EDDSA: i64 = -8;
+/// HMAC with SHA-256 truncated to 64 bits, see [COSE Algorithms Section 3.1].
+///
+/// [COSE Algorithms Section 3.1]: https://datatracker.ietf.org/doc/html/rfc9053#section-3.1
+pub const ALG_HMAC_256_64: i64 = 4;
+/// HMAC with SHA-256, see [COSE Algorithms Section 3.1].
+///
+/// [COSE Algorithms Section 3.1]: https://datatracker.ietf.org/doc/html/rfc9053#section-3.1
+pub const ALG_HMAC_256_256: i64 = 5;
+/// HMAC with SHA-384, see [COSE Algorithms Section 3.1].
+///
+/// [COSE Algorithms Section 3.1]: https://datatracker.ietf.org/doc/html/rfc9053#section-3.1
+pub const ALG_HMAC_384_384: i64 = 6;
+/// HMAC with SHA-512, see [COSE Algorithms Section 3.1].
+///
+/// [COSE Algorithms Section 3.1]: https://datatracker.ietf.org/doc/html/rfc9053#section-3.1
+pub const ALG_HMAC_512_512: i64 = 7;
+
+/// Produces signatures for `COSE_Sign1` structures.
+pub trait CoseSigner {
+    /// COSE algorithm identifier placed in the protected header
+    fn algorithm(&self) -> i64;
+    /// Returns the signature over the serialized `Sig_structure` in `tbs`.
+    fn sign(&self, tbs: &[u8]) -> Result<Vec<u8>, String>;
+}
+
+/// Verifies signatures on `COSE_Sign1` structures.
+pub trait CoseVerifier {
+    /// COSE algorithm identifier the verifier supports
+    fn algorithm(&self) -> i64;
+    /// Verifies `signature` over the serialized `Sig_structure` in `tbs`.
+    fn verify(&self, tbs: &[u8], signature: &[u8]) -> Result<(), String>;
+}
+
+/// Produces and verifies tags for `COSE_Mac0` structures.
+pub trait CoseMacKey {
+    /// COSE algorithm identifier placed in the protected header
+    fn algorithm(&self) -> i64;
+    /// Returns the tag over the serialized `MAC_structure` in `tbm`.
+    fn tag(&self, tbm: &[u8]) -> Result<Vec<u8>, String>;
+    /// Verifies `tag` over the serialized `MAC_structure` in `tbm`.
+    fn verify(&self, tbm: &[u8], tag: &[u8]) -> Result<(), String>;
+}
+
+impl CoseSigner for p256::ecdsa::SigningKey {
+    fn algorithm(&self) -> i64 {
+        ALG_ES256
+    }
+    fn sign(&self, tbs: &[u8]) -> Result<Vec<u8>, String> {
+        let sig: p256::ecdsa::Signature = p256::ecdsa::signature::Signer::try_sign(self, tbs)
+            .map_err(|e| format!("Failed to generate ES256 signature: {:?}", e))?;
+        Ok(sig.to_bytes().to_vec())
+    }
+}
+
+impl CoseVerifier for p256::ecdsa::VerifyingKey {
+    fn algorithm(&self) -> i64 {
+        ALG_ES256
+    }
+    fn verify(&self, tbs: &[u8], signature: &[u8]) -> Result<(), String> {
+        let sig = p256::ecdsa::Signature::from_slice(signature)
+            .map_err(|e| format!("Failed to parse ES256 signature: {:?}", e))?;
+        p256::ecdsa::signature::Verifier::verify(self, tbs, &sig)
+            .map_err(|e| format!("ES256 signature verification failed: {:?}", e))
+    }
+}
+
+impl CoseSigner for ed25519_dalek::SigningKey {
+    fn algorithm(&self) -> i64 {
+        ALG_EDDSA
+    }
+    fn sign(&self, tbs: &[u8]) -> Result<Vec<u8>, String> {
+        let sig = ed25519_dalek::Signer::try_sign(self, tbs)
+            .map_err(|e| format!("Failed to generate EdDSA signature: {:?}", e))?;
+        Ok(sig.to_bytes().to_vec())
+    }
+}
+
+impl CoseVerifier for ed25519_dalek::VerifyingKey {
+    fn algorithm(&self) -> i64 {
+        ALG_EDDSA
+    }
+    fn verify(&self, tbs: &[u8], signature: &[u8]) -> Result<(), String> {
+        let sig = ed25519_dalek::Signature::from_slice(signature)
+            .map_err(|e| format!("Failed to parse EdDSA signature: {:?}", e))?;
+        self.verify_strict(tbs, &sig)
+            .map_err(|e| format!("EdDSA signature verification failed: {:?}", e))
+    }
+}
+
+/// Symmetric key for use with one of the HMAC algorithms defined in [COSE Algorithms Section 3.1].
+///
+/// [COSE Algorithms Section 3.1]: https://datatracker.ietf.org/doc/html/rfc9053#section-3.1
+#[derive(Clone)]
+pub struct HmacKey {
+    alg: i64,
+    key: Vec<u8>,
+}
+
+impl HmacKey {
+    /// Creates a key for use with `alg`, which must be one of [ALG_HMAC_256_64],
+    /// [ALG_HMAC_256_256], [ALG_HMAC_384_384] or [ALG_HMAC_512_512].
+    pub fn new(alg: i64, key: &[u8]) -> Result<Self, String> {
+        match alg {
+            ALG_HMAC_256_64 | ALG_HMAC_256_256 | ALG_HMAC_384_384 | ALG_HMAC_512_512 => Ok(Self {
+                alg,
+                key: key.to_vec(),
+            }),
+            _ => Err(format!("Unsupported HMAC algorithm: {}", alg)),
+        }
+    }
+
+    fn full_tag(&self, tbm: &[u8]) -> Result<Vec<u8>, String> {
+        let err = |e| format!("Failed to prepare HMAC key: {:?}", e);
+        Ok(match self.alg {
+            ALG_HMAC_384_384 => {
+                let mut mac = Hmac::<Sha384>::new_from_slice(&self.key).map_err(err)?;
+                mac.update(tbm);
+                mac.finalize().into_bytes().to_vec()
+            }
+            ALG_HMAC_512_512 => {
+                let mut mac = Hmac::<Sha512>::new_from_slice(&self.key).map_err(err)?;
+                mac.update(tbm);
+                mac.finalize().into_bytes().to_vec()
+            }
+            _ => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).map_err(err)?;
+                mac.update(tbm);
+                mac.finalize().into_bytes().to_vec()
+            }
+        })
+    }
+}
+
+impl CoseMacKey for HmacKey {
+    fn algorithm(&self) -> i64 {
+        self.alg
+    }
+    fn tag(&self, tbm: &[u8]) -> Result<Vec<u8>, String> {
+        let mut tag = self.full_tag(tbm)?;
+        if self.alg == ALG_HMAC_256_64 {
+            tag.truncate(8);
+        }
+        Ok(tag)
+    }
+    fn verify(&self, tbm: &[u8], tag: &[u8]) -> Result<(), String> {
+        let expected = self.tag(tbm)?;
+        if expected.len() != tag.len() || !bool::from(expected.ct_eq(tag)) {
+            return Err("HMAC verification failed".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Returns a header map with no fields present.
+pub fn empty_header_map() -> HeaderMapCbor {
+    HeaderMapCbor {
+        alg_id: None,
+        criticality: None,
+        content_type: None,
+        key_id: None,
+        iv: None,
+        partial_iv: None,
+        other: None,
+    }
+}
+
+fn protected_alg_header(alg: i64) -> Result<EmptyOrSerializedMap, String> {
+    let header = HeaderMapCbor {
+        alg_id: Some(TextOrInt::Int(alg)),
+        ..empty_header_map()
+    };
+    let mut encoded = vec![];
+    into_writer(&header, &mut encoded)
+        .map_err(|e| format!("Failed to encode protected header: {:?}", e))?;
+    Ok(EmptyOrSerializedMap::SerializedMap(encoded))
+}
+
+fn protected_bytes(protected: &EmptyOrSerializedMap) -> &[u8] {
+    match protected {
+        EmptyOrSerializedMap::SerializedMap(b) | EmptyOrSerializedMap::Empty(b) => b,
+    }
+}
+
+fn decode_protected(protected: &EmptyOrSerializedMap) -> Result<HeaderMapCbor, String> {
+    let b = protected_bytes(protected);
+    if b.is_empty() {
+        return Ok(empty_header_map());
+    }
+    from_reader(b).map_err(|e| format!("Failed to parse protected header: {:?}", e))
+}
+
+/// Returns the algorithm identifier from the protected header or, failing that, the unprotected
+/// header.
+fn algorithm(protected: &EmptyOrSerializedMap, unprotected: &HeaderMapCbor) -> Result<i64, String> {
+    match decode_protected(protected)?
+        .alg_id
+        .or_else(|| unprotected.alg_id.clone())
+    {
+        Some(TextOrInt::Int(alg)) => Ok(alg),
+        Some(TextOrInt::Text(alg)) => Err(format!("Unsupported algorithm: {}", alg)),
+        None => Err("No algorithm identifier present in COSE headers".to_string()),
+    }
+}
+
+fn payload_bytes<'a>(
+    attached: &'a BinaryOrNil,
+    detached: Option<&'a [u8]>,
+) -> Result<&'a [u8], String> {
+    match (attached, detached) {
+        (BinaryOrNil::Binary(b), None) => Ok(b),
+        (BinaryOrNil::Nil, Some(b)) => Ok(b),
+        (BinaryOrNil::Nil, None) => Err("No payload available for detached content".to_string()),
+        (BinaryOrNil::Binary(_), Some(_)) => {
+            Err("Detached payload supplied for structure with attached payload".to_string())
+        }
+    }
+}
+
+fn encode_structure(context: &str, protected: &[u8], aad: &[u8], payload: &[u8]) -> Vec<u8> {
+    let structure = Value::Array(vec![
+        Value::Text(context.to_string()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(aad.to_vec()),
+        Value::Bytes(payload.to_vec()),
+    ]);
+    let mut encoded = vec![];
+    // serialization of a Value to a Vec does not fail
+    let _ = into_writer(&structure, &mut encoded);
+    encoded
+}
+
+impl CoseSign1Cbor {
+    /// Creates a `COSE_Sign1` over `payload`. The algorithm of `signer` is placed in the protected
+    /// header. `unprotected` is used as-is and `external_aad` is included in the signature but not
+    /// in the structure. When `detached` is true, the payload field is set to nil.
+    pub fn sign<S: CoseSigner + ?Sized>(
+        payload: &[u8],
+        unprotected: HeaderMapCbor,
+        external_aad: &[u8],
+        detached: bool,
+        signer: &S,
+    ) -> Result<Self, String> {
+        let protected = protected_alg_header(signer.algorithm())?;
+        let tbs = encode_structure(
+            "Signature1",
+            protected_bytes(&protected),
+            external_aad,
+            payload,
+        );
+        let signature = signer.sign(&tbs)?;
+        Ok(CoseSign1Cbor {
+            protected,
+            unprotected,
+            payload: match detached {
+                true => BinaryOrNil::Nil,
+                false => BinaryOrNil::Binary(payload.to_vec()),
+            },
+            signature: BytesType::Bytes(signature),
+        })
+    }
+
+    /// Returns the protected header parameters.
+    pub fn protected_header(&self) -> Result<HeaderMapCbor, String> {
+        decode_protected(&self.protected)
+    }
+
+    /// Returns the serialized `Sig_structure`. `payload` must be provided if and only if the
+    /// payload is detached.
+    pub fn to_be_signed(
+        &self,
+        external_aad: &[u8],
+        payload: Option<&[u8]>,
+    ) -> Result<Vec<u8>, String> {
+        Ok(encode_structure(
+            "Signature1",
+            protected_bytes(&self.protected),
+            external_aad,
+            payload_bytes(&self.payload, payload)?,
+        ))
+    }
+
+    /// Verifies the signature using `verifier`, which must support the algorithm indicated in the
+    /// headers. `payload` must be provided if and only if the payload is detached.
+    pub fn verify<V: CoseVerifier + ?Sized>(
+        &self,
+        external_aad: &[u8],
+        payload: Option<&[u8]>,
+        verifier: &V,
+    ) -> Result<(), String> {
+        let alg = algorithm(&self.protected, &self.unprotected)?;
+        if alg != verifier.algorithm() {
+            return Err(format!(
+                "Algorithm mismatch: structure uses {} but verifier supports {}",
+                alg,
+                verifier.algorithm()
+            ));
+        }
+        let tbs = self.to_be_signed(external_aad, payload)?;
+        let BytesType::Bytes(signature) = &self.signature;
+        verifier.verify(&tbs, signature)
+    }
+}
+
+impl CoseMac0Cbor {
+    /// Creates a `COSE_Mac0` over `payload`. The algorithm of `key` is placed in the protected
+    /// header. `unprotected` is used as-is and `external_aad` is included in the tag but not in the
+    /// structure. When `detached` is true, the payload field is set to nil.
+    pub fn mac<K: CoseMacKey + ?Sized>(
+        payload: &[u8],
+        unprotected: HeaderMapCbor,
+        external_aad: &[u8],
+        detached: bool,
+        key: &K,
+    ) -> Result<Self, String> {
+        let protected = protected_alg_header(key.algorithm())?;
+        let tbm = encode_structure("MAC0", protected_bytes(&protected), external_aad, payload);
+        let tag = key.tag(&tbm)?;
+        Ok(CoseMac0Cbor {
+            protected,
+            unprotected,
+            payload: match detached {
+                true => BinaryOrNil::Nil,
+                false => BinaryOrNil::Binary(payload.to_vec()),
+            },
+            tag: BytesType::Bytes(tag),
+        })
+    }
+
+    /// Returns the protected header parameters.
+    pub fn protected_header(&self) -> Result<HeaderMapCbor, String> {
+        decode_protected(&self.protected)
+    }
+
+    /// Returns the serialized `MAC_structure`. `payload` must be provided if and only if the
+    /// payload is detached.
+    pub fn to_be_maced(
+        &self,
+        external_aad: &[u8],
+        payload: Option<&[u8]>,
+    ) -> Result<Vec<u8>, String> {
+        Ok(encode_structure(
+            "MAC0",
+            protected_bytes(&self.protected),
+            external_aad,
+            payload_bytes(&self.payload, payload)?,
+        ))
+    }
+
+    /// Verifies the tag using `key`, which must support the algorithm indicated in the headers.
+    /// `payload` must be provided if and only if the payload is detached.
+    pub fn verify<K: CoseMacKey + ?Sized>(
+        &self,
+        external_aad: &[u8],
+        payload: Option<&[u8]>,
+        key: &K,
+    ) -> Result<(), String> {
+        let alg = algorithm(&self.protected, &self.unprotected)?;
+        if alg != key.algorithm() {
+            return Err(format!(
+                "Algorithm mismatch: structure uses {} but key supports {}",
+                alg,
+                key.algorithm()
+            ));
+        }
+        let tbm = self.to_be_maced(external_aad, payload)?;
+        let BytesType::Bytes(tag) = &self.tag;
+        key.verify(&tbm, tag)
+    }
+}
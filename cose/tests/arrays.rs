@@ -2,8 +2,10 @@ use std::path::Path;
 
 use ciborium::de::from_reader;
 use ciborium::ser::into_writer;
+use ciborium::value::Value;
 use hex_literal::hex;
 
+use common::BinaryOrNil;
 use cose::arrays::*;
 use cose::maps::*;
 
@@ -87,6 +89,50 @@ fn cosesign1_test() {
     assert_eq!(expected.to_vec(), encoded_token);
 }
 
+#[test]
+fn nil_fields_test() {
+    let expected =
+        get_file_as_byte_vec(Path::new(&"tests/examples/untagged_sign1.cbor".to_string()));
+    let mut sign1: CoseSign1Cbor = from_reader(expected.as_slice()).unwrap();
+
+    // a detached payload is encoded as nil and keeps its position in the array
+    sign1.payload = BinaryOrNil::Nil;
+    let mut encoded_token = vec![];
+    into_writer(&sign1, &mut encoded_token).unwrap();
+    let v: Value = from_reader(encoded_token.as_slice()).unwrap();
+    let a = v.as_array().unwrap();
+    assert_eq!(a.len(), 4);
+    assert_eq!(a[2], Value::Null);
+    let sign1_d: CoseSign1Cbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(sign1_d, sign1);
+
+    // absent optional fields are omitted while nil fields are retained
+    let recipient = CoseRecipientCbor {
+        protected: sign1.protected.clone(),
+        unprotected: sign1.unprotected.clone(),
+        ciphertext: BinaryOrNil::Nil,
+        recipients: None,
+    };
+    let mut encoded_token = vec![];
+    into_writer(&recipient, &mut encoded_token).unwrap();
+    let v: Value = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(v.as_array().unwrap().len(), 3);
+    let recipient_d: CoseRecipientCbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(recipient_d, recipient);
+
+    // encoding of arrays without nil fields is unchanged
+    let recipient = CoseRecipientCbor {
+        ciphertext: BinaryOrNil::Binary(vec![1, 2, 3]),
+        ..recipient
+    };
+    let mut encoded_token = vec![];
+    into_writer(&recipient, &mut encoded_token).unwrap();
+    let v: Value = from_reader(encoded_token.as_slice()).unwrap();
+    let a = v.as_array().unwrap();
+    assert_eq!(a.len(), 3);
+    assert_eq!(a[2], Value::Bytes(vec![1, 2, 3]));
+}
+
 #[test]
 fn header_map_test() {
     let expected = hex!("A104423131");
@@ -0,0 +1,105 @@
+use ciborium::de::from_reader;
+use ciborium::ser::into_writer;
+use ciborium::tag::Required;
+use common::{BinaryOrNil, TextOrBinary};
+use hex_literal::hex;
+
+use cose::arrays::*;
+use cose::maps::HeaderMapCbor;
+use cose::sign::*;
+
+// signed CWT from RFC 8392 Appendix A.3 with the key from Appendix A.2.3
+const RFC8392_SIGNED: [u8; 175] = hex!("d28443a10126a104524173796d6d657472696345434453413235365850a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b7158405427c1ff28d23fbad1f29c4c7c6a555e601d6fa29f9179bc3d7438bacaca5acd08c8d4d4f96131680c429a01f85951ecee743a52b9b63632c57209120e1c9e30");
+const RFC8392_EC_D: [u8; 32] =
+    hex!("6c1382765aec5358f117733d281c1c7bdc39884d04a45a1e6c67c858bc206c19");
+
+// MACed CWT from RFC 8392 Appendix A.4 with the key from Appendix A.2.2
+const RFC8392_MACED: [u8; 114] = hex!("d83dd18443a10104a1044c53796d6d65747269633235365850a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b7148093101ef6d789200");
+const RFC8392_MAC_KEY: [u8; 32] =
+    hex!("403697de87af64611c1d32a05dab0fe1fcb715a86ab435f1ec99192d79569388");
+
+#[test]
+fn rfc8392_sign1_test() {
+    let tagged: TaggedCoseSign1 = from_reader(RFC8392_SIGNED.as_slice()).unwrap();
+    let sign1 = tagged.0;
+    let sk = p256::ecdsa::SigningKey::from_slice(&RFC8392_EC_D).unwrap();
+    let vk = *sk.verifying_key();
+    sign1.verify(&[], None, &vk).unwrap();
+    assert_eq!(
+        sign1.protected_header().unwrap().alg_id,
+        Some(common::TextOrInt::Int(ALG_ES256))
+    );
+
+    // external AAD, a detached payload and an EdDSA key all cause verification to fail
+    assert!(sign1.verify(b"aad", None, &vk).is_err());
+    assert!(sign1.verify(&[], Some(b"payload"), &vk).is_err());
+    let ed = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+    assert!(sign1.verify(&[], None, &ed.verifying_key()).is_err());
+
+    // ECDSA signatures are deterministic, so re-signing reproduces the example
+    let BinaryOrNil::Binary(payload) = &sign1.payload else {
+        panic!()
+    };
+    let resigned =
+        CoseSign1Cbor::sign(payload, sign1.unprotected.clone(), &[], false, &sk).unwrap();
+    assert_eq!(resigned, sign1);
+}
+
+#[test]
+fn rfc8392_mac0_test() {
+    let cwt: Required<TaggedCoseMac0, 61> = from_reader(RFC8392_MACED.as_slice()).unwrap();
+    let mac0 = cwt.0 .0;
+    let key = HmacKey::new(ALG_HMAC_256_64, &RFC8392_MAC_KEY).unwrap();
+    mac0.verify(&[], None, &key).unwrap();
+
+    let wrong = HmacKey::new(ALG_HMAC_256_256, &RFC8392_MAC_KEY).unwrap();
+    assert!(mac0.verify(&[], None, &wrong).is_err());
+    let wrong = HmacKey::new(ALG_HMAC_256_64, &[0; 32]).unwrap();
+    assert!(mac0.verify(&[], None, &wrong).is_err());
+    assert!(HmacKey::new(ALG_ES256, &RFC8392_MAC_KEY).is_err());
+
+    // tags of the wrong length are rejected
+    let tag = key.tag(b"tbm").unwrap();
+    assert_eq!(tag.len(), 8);
+    key.verify(b"tbm", &tag).unwrap();
+    assert!(key.verify(b"tbm", &tag[..7]).is_err());
+    assert!(key
+        .verify(b"tbm", &[tag.clone(), vec![0]].concat())
+        .is_err());
+    assert!(key.verify(b"tbm", &[]).is_err());
+
+    let BinaryOrNil::Binary(payload) = &mac0.payload else {
+        panic!()
+    };
+    let remaced = CoseMac0Cbor::mac(payload, mac0.unprotected.clone(), &[], false, &key).unwrap();
+    assert_eq!(remaced, mac0);
+    let mut encoded = vec![];
+    into_writer(
+        &Required::<TaggedCoseMac0, 61>(Required(remaced)),
+        &mut encoded,
+    )
+    .unwrap();
+    assert_eq!(encoded, RFC8392_MACED.to_vec());
+}
+
+#[test]
+fn sign1_eddsa_detached_test() {
+    let sk = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+    let unprotected = HeaderMapCbor {
+        key_id: Some(TextOrBinary::Binary(b"ed".to_vec())),
+        ..empty_header_map()
+    };
+    let sign1 = CoseSign1Cbor::sign(b"payload", unprotected, b"aad", true, &sk).unwrap();
+    assert_eq!(sign1.payload, BinaryOrNil::Nil);
+
+    let mut encoded = vec![];
+    into_writer(&sign1, &mut encoded).unwrap();
+    let decoded: CoseSign1Cbor = from_reader(encoded.as_slice()).unwrap();
+    assert_eq!(decoded, sign1);
+
+    let vk = sk.verifying_key();
+    decoded.verify(b"aad", Some(b"payload"), &vk).unwrap();
+    assert!(decoded.verify(b"aad", Some(b"other"), &vk).is_err());
+    assert!(decoded.verify(b"aad", None, &vk).is_err());
+    assert!(decoded.verify(&[], Some(b"payload"), &vk).is_err());
+}
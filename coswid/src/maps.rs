@@ -1,6 +1,7 @@
 //! Map-based structs from the Concise Software Identification Tags (CoSWID) spec
 
 use ciborium::{cbor, value::Value};
+use core::cmp::Ordering;
 use core::{fmt, marker::PhantomData};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde::{
@@ -19,6 +20,7 @@ use cbor_derive::StructToMap;
 use cbor_derive::StructToOneOrMore;
 use common::arrays::*;
 use common::choices::*;
+use common::version::*;
 use common::*;
 use serde::ser::Error as OtherError;
 
@@ -100,6 +102,42 @@ pub struct ConciseSwidTag {
     pub other: Option<Vec<Tuple>>,
 }
 
+impl ConciseSwidTag {
+    /// Parses `software_version` according to `version_scheme`, which is treated as
+    /// multipartnumeric when absent.
+    pub fn parse_software_version(&self) -> Result<Version, String> {
+        let scheme = effective_version_scheme(self.version_scheme.as_ref())?;
+        match &self.software_version {
+            Some(v) => Version::parse(&scheme, v),
+            None => Err("software_version is absent".to_string()),
+        }
+    }
+
+    /// Compares `software_version` to that of `other`. An error is returned if either version is
+    /// absent or cannot be parsed or if the two tags use different version schemes.
+    pub fn compare_software_version(&self, other: &ConciseSwidTag) -> Result<Ordering, String> {
+        let scheme = effective_version_scheme(self.version_scheme.as_ref())?;
+        let other_scheme = effective_version_scheme(other.version_scheme.as_ref())?;
+        if scheme != other_scheme {
+            return Err("Software versions use different version schemes".to_string());
+        }
+        match (&self.software_version, &other.software_version) {
+            (Some(a), Some(b)) => compare_versions(&scheme, a, b),
+            _ => Err("software_version is absent".to_string()),
+        }
+    }
+
+    /// Returns true if `software_version` is greater than or equal to `min_version`, which is
+    /// parsed using the same version scheme as this tag.
+    pub fn software_version_is_at_least(&self, min_version: &str) -> Result<bool, String> {
+        let scheme = effective_version_scheme(self.version_scheme.as_ref())?;
+        match &self.software_version {
+            Some(v) => Ok(compare_versions(&scheme, v, min_version)? != Ordering::Less),
+            None => Err("software_version is absent".to_string()),
+        }
+    }
+}
+
 // directory-entry = {
 //    ? key => bool,
 //    ? location => text,
@@ -1,7 +1,9 @@
 use ciborium::de::from_reader;
 use ciborium::ser::into_writer;
+use common::choices::{VersionScheme, VersionSchemeKnown};
 use coswid::maps::*;
 use hex_literal::hex;
+use std::cmp::Ordering;
 
 #[allow(dead_code)]
 pub fn buffer_to_hex(buffer: &[u8]) -> String {
@@ -51,6 +53,29 @@ fn coswid_test() {
     assert_eq!(expected.to_vec(), actual);
 }
 
+#[test]
+fn coswid_software_version_test() {
+    let expected = hex!("a60050f432dc992e06434db9ad2b22e35b6fa40c0001781a526f616472756e6e657220736f6674776172652062756e646c650d65312e302e3002a3181f6841434d45204c746418206c61636d652e6578616d706c65182182010204a21826782464383466623565322d643139382d343962342d396436352d336138323432316266313830182806");
+    let coswid_cbor: ConciseSwidTagCbor = from_reader(expected.as_slice()).unwrap();
+    let coswid: ConciseSwidTag = coswid_cbor.try_into().unwrap();
+    assert!(coswid.software_version_is_at_least("1").unwrap());
+    assert!(coswid.software_version_is_at_least("0.9.12").unwrap());
+    assert!(!coswid.software_version_is_at_least("1.0.1").unwrap());
+
+    let mut newer = coswid.clone();
+    newer.software_version = Some("1.0.10".to_string());
+    assert_eq!(
+        newer.compare_software_version(&coswid).unwrap(),
+        Ordering::Greater
+    );
+
+    newer.version_scheme = Some(VersionScheme::Known(VersionSchemeKnown::Semver));
+    assert!(newer.compare_software_version(&coswid).is_err());
+
+    newer.software_version = None;
+    assert!(newer.software_version_is_at_least("1.0.0").is_err());
+}
+
 #[test]
 fn directory_entry_test() {
     //todo
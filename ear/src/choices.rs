@@ -0,0 +1,115 @@
+//! Choice-based structs
+
+use alloc::string::{String, ToString};
+
+use ciborium::value::Value;
+use num_enum::TryFromPrimitive;
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// JSON encoding/decoding of `trust-tier`, see [AR4SI Section 3.2].
+///
+/// Use [TrustTierCbor](TrustTierCbor) for CBOR-encoded EARs. Tiers are ordered from least to most
+/// severe, so the worse of two tiers is the greater.
+///
+/// ```text
+/// trust-tier = ar4si.none /
+///              ar4si.affirming /
+///              ar4si.warning /
+///              ar4si.contraindicated
+///
+/// none            = 0   ; "none" in JSON
+/// affirming       = 2   ; "affirming" in JSON
+/// warning         = 32  ; "warning" in JSON
+/// contraindicated = 96  ; "contraindicated" in JSON
+/// ```
+///
+/// [AR4SI Section 3.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-ar4si#section-3.2
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    serde_enum_str::Deserialize_enum_str,
+    serde_enum_str::Serialize_enum_str,
+)]
+#[allow(missing_docs)]
+pub enum TrustTier {
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "affirming")]
+    Affirming,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "contraindicated")]
+    Contraindicated,
+}
+
+/// CBOR encoding/decoding of `trust-tier`, see [TrustTier].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize_repr, Deserialize_repr, TryFromPrimitive)]
+#[allow(missing_docs)]
+#[repr(i8)]
+pub enum TrustTierCbor {
+    None = 0,
+    Affirming = 2,
+    Warning = 32,
+    Contraindicated = 96,
+}
+
+impl TryFrom<&TrustTier> for TrustTierCbor {
+    type Error = String;
+    fn try_from(value: &TrustTier) -> Result<Self, Self::Error> {
+        Ok(match value {
+            TrustTier::None => Self::None,
+            TrustTier::Affirming => Self::Affirming,
+            TrustTier::Warning => Self::Warning,
+            TrustTier::Contraindicated => Self::Contraindicated,
+        })
+    }
+}
+impl TryFrom<TrustTier> for TrustTierCbor {
+    type Error = String;
+    fn try_from(value: TrustTier) -> Result<Self, Self::Error> {
+        TrustTierCbor::try_from(&value)
+    }
+}
+impl TryFrom<&TrustTierCbor> for TrustTier {
+    type Error = String;
+    fn try_from(value: &TrustTierCbor) -> Result<Self, Self::Error> {
+        Ok(match value {
+            TrustTierCbor::None => Self::None,
+            TrustTierCbor::Affirming => Self::Affirming,
+            TrustTierCbor::Warning => Self::Warning,
+            TrustTierCbor::Contraindicated => Self::Contraindicated,
+        })
+    }
+}
+impl TryFrom<TrustTierCbor> for TrustTier {
+    type Error = String;
+    fn try_from(value: TrustTierCbor) -> Result<Self, Self::Error> {
+        TrustTier::try_from(&value)
+    }
+}
+impl TryFrom<Value> for TrustTierCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        TrustTierCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for TrustTierCbor {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(i) => match <ciborium::value::Integer as TryInto<i8>>::try_into(*i) {
+                Ok(vs) => match TrustTierCbor::try_from(vs) {
+                    Ok(val) => Ok(val),
+                    Err(_) => Err("Failed to parse value as a TrustTierCbor".to_string()),
+                },
+                Err(_) => Err("Failed to parse value as a TrustTierCbor".to_string()),
+            },
+            _ => Err("Failed to parse value as a TrustTierCbor".to_string()),
+        }
+    }
+}
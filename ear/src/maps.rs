@@ -0,0 +1,352 @@
+//! Map-based structs from the EAT Attestation Results (EAR) spec
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use ciborium::{cbor, value::Value};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::{
+    __private::size_hint,
+    de::{Error, MapAccess, Visitor},
+};
+
+use crate::choices::*;
+use crate::trust::{tier, TrustCategory};
+use cbor_derive::StructToMap;
+use common::tuple::TupleCbor;
+use common::*;
+use eat::maps::base64url;
+use eat::submods::NamedMap;
+use serde::ser::Error as OtherError;
+
+/// Value of the `profile` claim identifying an EAR
+pub const EAR_PROFILE: &str = "tag:github.com,2023:veraison/ear";
+
+/// CBOR and JSON encoding/decoding of `ar4si.trustworthiness-vector`, see [AR4SI Section 2.3].
+///
+/// The values and tiers of the claims are described in the [trust](crate::trust) module.
+///
+/// ```text
+/// ar4si.trustworthiness-vector = non-empty<{
+///   ? &(instance-identity: 0) => ar4si.trustworthiness-claim
+///   ? &(configuration: 1) => ar4si.trustworthiness-claim
+///   ? &(executables: 2) => ar4si.trustworthiness-claim
+///   ? &(file-system: 3) => ar4si.trustworthiness-claim
+///   ? &(hardware: 4) => ar4si.trustworthiness-claim
+///   ? &(runtime-opaque: 5) => ar4si.trustworthiness-claim
+///   ? &(storage-opaque: 6) => ar4si.trustworthiness-claim
+///   ? &(sourced-data: 7) => ar4si.trustworthiness-claim
+/// }>
+///
+/// ar4si.trustworthiness-claim = -128..127
+/// ```
+/// [AR4SI Section 2.3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-ar4si#section-2.3
+#[derive(Clone, Debug, Default, PartialEq, StructToMap, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct TrustVector {
+    #[cbor(tag = "0", value = "Integer")]
+    #[serde(
+        rename = "instance-identity",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub instance_identity: Option<i8>,
+    #[cbor(tag = "1", value = "Integer")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub configuration: Option<i8>,
+    #[cbor(tag = "2", value = "Integer")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub executables: Option<i8>,
+    #[cbor(tag = "3", value = "Integer")]
+    #[serde(
+        rename = "file-system",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub file_system: Option<i8>,
+    #[cbor(tag = "4", value = "Integer")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub hardware: Option<i8>,
+    #[cbor(tag = "5", value = "Integer")]
+    #[serde(
+        rename = "runtime-opaque",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub runtime_opaque: Option<i8>,
+    #[cbor(tag = "6", value = "Integer")]
+    #[serde(
+        rename = "storage-opaque",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub storage_opaque: Option<i8>,
+    #[cbor(tag = "7", value = "Integer")]
+    #[serde(
+        rename = "sourced-data",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub sourced_data: Option<i8>,
+}
+
+impl TrustVector {
+    /// Returns the claim for `category`, if present.
+    pub fn get(&self, category: TrustCategory) -> Option<i8> {
+        match category {
+            TrustCategory::InstanceIdentity => self.instance_identity,
+            TrustCategory::Configuration => self.configuration,
+            TrustCategory::Executables => self.executables,
+            TrustCategory::FileSystem => self.file_system,
+            TrustCategory::Hardware => self.hardware,
+            TrustCategory::RuntimeOpaque => self.runtime_opaque,
+            TrustCategory::StorageOpaque => self.storage_opaque,
+            TrustCategory::SourcedData => self.sourced_data,
+        }
+    }
+
+    /// Sets or clears the claim for `category`.
+    pub fn set(&mut self, category: TrustCategory, value: Option<i8>) {
+        let claim = match category {
+            TrustCategory::InstanceIdentity => &mut self.instance_identity,
+            TrustCategory::Configuration => &mut self.configuration,
+            TrustCategory::Executables => &mut self.executables,
+            TrustCategory::FileSystem => &mut self.file_system,
+            TrustCategory::Hardware => &mut self.hardware,
+            TrustCategory::RuntimeOpaque => &mut self.runtime_opaque,
+            TrustCategory::StorageOpaque => &mut self.storage_opaque,
+            TrustCategory::SourcedData => &mut self.sourced_data,
+        };
+        *claim = value;
+    }
+
+    /// Returns the category and value of each claim that is present.
+    pub fn claims(&self) -> Vec<(TrustCategory, i8)> {
+        TrustCategory::ALL
+            .iter()
+            .filter_map(|c| self.get(*c).map(|v| (*c, v)))
+            .collect()
+    }
+
+    /// Returns the most severe tier of the claims that are present, or [TrustTier::None] if there
+    /// are none.
+    pub fn worst_tier(&self) -> TrustTier {
+        self.claims()
+            .into_iter()
+            .map(|(_, v)| tier(v))
+            .max()
+            .unwrap_or(TrustTier::None)
+    }
+}
+
+/// CBOR and JSON encoding/decoding of `ar4si.verifier-id`, see [EAR Section 3].
+///
+/// ```text
+/// ar4si.verifier-id = {
+///   &(developer: 0) => text
+///   &(build: 1) => text
+/// }
+/// ```
+/// [EAR Section 3]: https://datatracker.ietf.org/doc/html/draft-fv-rats-ear#section-3
+#[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct VerifierId {
+    #[cbor(tag = "0", value = "Text")]
+    pub developer: String,
+    #[cbor(tag = "1", value = "Text")]
+    pub build: String,
+}
+
+/// JSON encoding/decoding of `EAR-appraisal`, see [EAR Section 3.1].
+///
+/// Use [AppraisalCbor](AppraisalCbor) for CBOR-encoded EARs.
+///
+/// ```text
+/// EAR-appraisal = {
+///   ear.status-label => ar4si.trust-tier
+///   ? ear.trustworthiness-vector-label => ar4si.trustworthiness-vector
+///   ? ear.appraisal-policy-id-label => text
+///   * $$ear-appraisal-extension
+/// }
+///
+/// ear.status-label = 1000
+/// ear.trustworthiness-vector-label = 1001
+/// ear.appraisal-policy-id-label = 1003
+/// ```
+/// [EAR Section 3.1]: https://datatracker.ietf.org/doc/html/draft-fv-rats-ear#section-3.1
+#[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct Appraisal {
+    #[cbor(tag = "1000", cbor = "true")]
+    #[serde(rename = "ear.status")]
+    pub status: TrustTier,
+    #[cbor(tag = "1001", cbor = "true")]
+    #[serde(
+        rename = "ear.trustworthiness-vector",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub trust_vector: Option<TrustVector>,
+    #[cbor(tag = "1003", value = "Text")]
+    #[serde(
+        rename = "ear.appraisal-policy-id",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub policy_id: Option<String>,
+}
+
+impl Appraisal {
+    /// Creates an appraisal with the given status and no trustworthiness vector.
+    pub fn new(status: TrustTier) -> Self {
+        Appraisal {
+            status,
+            trust_vector: None,
+            policy_id: None,
+        }
+    }
+
+    /// Raises the status to the most severe tier of the trustworthiness vector, if that is worse
+    /// than the current status.
+    pub fn update_status_from_trust_vector(&mut self) {
+        if let Some(tv) = &self.trust_vector {
+            self.status = self.status.max(tv.worst_tier());
+        }
+    }
+}
+
+/// JSON encoding/decoding of the `submods` claim of an EAR, i.e., a map from submodule name to
+/// [Appraisal], see [EAR Section 3]. Entries are kept in the order in which they were added or
+/// decoded.
+///
+/// Use [AppraisalsCbor](AppraisalsCbor) for CBOR-encoded EARs.
+///
+/// [EAR Section 3]: https://datatracker.ietf.org/doc/html/draft-fv-rats-ear#section-3
+pub type Appraisals = NamedMap<Appraisal>;
+
+/// CBOR encoding/decoding of the `submods` claim of an EAR, see [Appraisals].
+pub type AppraisalsCbor = NamedMap<AppraisalCbor>;
+
+/// JSON encoding/decoding of an EAR claims set, see [EAR Section 3].
+///
+/// Use [EarCbor](EarCbor) for CBOR-encoded EARs. The `profile`, `iat`, `submods` and `nonce` claims
+/// are those defined by EAT and use the types and JSON encodings of the [eat] crate, except that
+/// `profile` is text because EAR fixes its value to [EAR_PROFILE].
+///
+/// ```text
+/// EAR = {
+///   eat.profile-label => "tag:github.com,2023:veraison/ear"
+///   iat-label => int
+///   ear.verifier-id-label => ar4si.verifier-id
+///   ? ear.raw-evidence-label => eat.binary-data
+///   eat.submods-label => { + text => EAR-appraisal }
+///   ? eat.nonce-label => eat.nonce-type
+///   * $$ear-extension
+/// }
+///
+/// ear.verifier-id-label = 1004
+/// ear.raw-evidence-label = 1002
+/// ```
+/// [EAR Section 3]: https://datatracker.ietf.org/doc/html/draft-fv-rats-ear#section-3
+#[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct Ear {
+    #[cbor(tag = "265", value = "Text")]
+    #[serde(rename = "eat_profile")]
+    pub profile: String,
+    #[cbor(tag = "6", value = "Integer")]
+    pub iat: NumericDate,
+    #[cbor(tag = "1004", cbor = "true")]
+    #[serde(rename = "ear.verifier-id")]
+    pub verifier_id: VerifierId,
+    #[cbor(tag = "1002", value = "Bytes")]
+    #[serde(
+        rename = "ear.raw-evidence",
+        with = "base64url::bytes",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub raw_evidence: Option<Vec<u8>>,
+    #[cbor(tag = "266", cbor = "true")]
+    pub submods: Appraisals,
+    #[cbor(tag = "10")]
+    #[serde(
+        rename = "eat_nonce",
+        with = "base64url::nonce",
+        skip_serializing_if = "Option::is_none",
+        default
+    )]
+    pub nonce: Option<NonceType>,
+}
+
+impl Ear {
+    /// Creates an EAR issued at `iat` by `verifier_id` with no appraisals.
+    pub fn new(iat: NumericDate, verifier_id: VerifierId) -> Self {
+        Ear {
+            profile: EAR_PROFILE.to_string(),
+            iat,
+            verifier_id,
+            raw_evidence: None,
+            submods: Appraisals::default(),
+            nonce: None,
+        }
+    }
+
+    /// Returns the most severe status of the appraisals, or [TrustTier::None] if there are none.
+    pub fn status(&self) -> TrustTier {
+        self.submods
+            .iter()
+            .map(|(_, a)| a.status)
+            .max()
+            .unwrap_or(TrustTier::None)
+    }
+
+    /// Encodes the EAR as JSON.
+    pub fn to_json(&self) -> Result<Vec<u8>, String> {
+        match serde_json::to_vec(self) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to encode EAR: {:?}", e)),
+        }
+    }
+
+    /// Decodes a JSON-encoded EAR. An error is returned if the profile is not [EAR_PROFILE].
+    pub fn from_json(encoded: &[u8]) -> Result<Self, String> {
+        let ear: Ear = match serde_json::from_slice(encoded) {
+            Ok(e) => e,
+            Err(e) => return Err(format!("Failed to parse EAR: {:?}", e)),
+        };
+        ear.check_profile()?;
+        Ok(ear)
+    }
+
+    /// Encodes the EAR as CBOR.
+    pub fn to_cbor(&self) -> Result<Vec<u8>, String> {
+        let ear = EarCbor::try_from(self)?;
+        let mut encoded = vec![];
+        match ciborium::ser::into_writer(&ear, &mut encoded) {
+            Ok(_) => Ok(encoded),
+            Err(e) => Err(format!("Failed to encode EAR: {:?}", e)),
+        }
+    }
+
+    /// Decodes a CBOR-encoded EAR. An error is returned if the profile is not [EAR_PROFILE].
+    pub fn from_cbor(encoded: &[u8]) -> Result<Self, String> {
+        let value: Value = match ciborium::de::from_reader(encoded) {
+            Ok(v) => v,
+            Err(e) => return Err(format!("Failed to parse EAR: {:?}", e)),
+        };
+        let ear = Ear::try_from(EarCbor::try_from(value)?)?;
+        ear.check_profile()?;
+        Ok(ear)
+    }
+
+    fn check_profile(&self) -> Result<(), String> {
+        match self.profile == EAR_PROFILE {
+            true => Ok(()),
+            false => Err(format!("Unexpected EAR profile: {}", self.profile)),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Trustworthiness claims defined by AR4SI
+//!
+//! Each entry in a trustworthiness vector is an integer in the range -128 to 127 that describes the
+//! appraisal of one aspect of an attester, see [AR4SI Section 2.3]. The value determines the
+//! [TrustTier] of the claim:
+//!
+//! | Values                      | Tier            |
+//! |-----------------------------|-----------------|
+//! | -1 to 1                     | none            |
+//! | 2 to 31 and -32 to -2       | affirming       |
+//! | 32 to 95 and -96 to -33     | warning         |
+//! | 96 to 127 and -128 to -97   | contraindicated |
+//!
+//! Non-negative values are defined by AR4SI, negative values other than -1 are implementation
+//! specific. The constants below give the values defined for each category, and [describe] returns
+//! their meaning.
+//!
+//! [AR4SI Section 2.3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-ar4si#section-2.3
+
+use crate::choices::TrustTier;
+
+/// The verifier makes no claim about the category
+pub const NO_CLAIM: i8 = 0;
+/// The verifier encountered an error it did not expect while appraising the category
+pub const UNEXPECTED_EVAL_ERROR: i8 = 1;
+/// The verifier is not functioning correctly
+pub const VERIFIER_MALFUNCTION: i8 = -1;
+/// Cryptographic validation of the evidence failed
+pub const CRYPTO_VALIDATION_FAILED: i8 = 99;
+
+/// The attesting environment is recognized and its identity is trustworthy
+pub const TRUSTWORTHY_INSTANCE: i8 = 2;
+/// The attesting environment is recognized but its identity is not trustworthy
+pub const UNTRUSTWORTHY_INSTANCE: i8 = 96;
+/// The attesting environment is not recognized
+pub const UNRECOGNIZED_INSTANCE: i8 = 97;
+
+/// The configuration is approved by the owner
+pub const APPROVED_CONFIG: i8 = 2;
+/// The configuration has no known vulnerabilities
+pub const NO_CONFIG_VULNS: i8 = 3;
+/// The configuration has known vulnerabilities
+pub const UNSAFE_CONFIG: i8 = 32;
+/// The configuration is unsupportable, e.g., it is known to be compromised
+pub const UNSUPPORTABLE_CONFIG: i8 = 96;
+
+/// Only approved runtime executables are loaded
+pub const APPROVED_RUNTIME: i8 = 2;
+/// Only approved boot-time executables are loaded
+pub const APPROVED_BOOT: i8 = 3;
+/// Executables with known vulnerabilities are loaded
+pub const UNSAFE_RUNTIME: i8 = 32;
+/// Executables that are not recognized are loaded
+pub const UNRECOGNIZED_RUNTIME: i8 = 33;
+/// Contraindicated executables, e.g., known malware, are loaded
+pub const CONTRAINDICATED_RUNTIME: i8 = 96;
+
+/// Only approved files are present
+pub const APPROVED_FILES: i8 = 2;
+/// Files that are not recognized are present
+pub const UNRECOGNIZED_FILES: i8 = 32;
+/// Contraindicated files are present
+pub const CONTRAINDICATED_FILES: i8 = 96;
+
+/// The hardware is genuine
+pub const GENUINE_HARDWARE: i8 = 2;
+/// The hardware has known vulnerabilities
+pub const UNSAFE_HARDWARE: i8 = 32;
+/// The hardware is contraindicated, e.g., it is known to be compromised
+pub const CONTRAINDICATED_HARDWARE: i8 = 96;
+/// The hardware is not recognized
+pub const UNRECOGNIZED_HARDWARE: i8 = 97;
+
+/// Memory is encrypted and isolated from other environments
+pub const ENCRYPTED_MEMORY: i8 = 2;
+/// Memory is isolated from other environments but not encrypted
+pub const ISOLATED_MEMORY: i8 = 32;
+/// Memory is visible to other environments
+pub const VISIBLE_MEMORY: i8 = 96;
+
+/// Secrets are encrypted using keys protected by hardware
+pub const HW_KEYS_ENCRYPTED_SECRETS: i8 = 2;
+/// Secrets are encrypted using keys protected by software
+pub const SW_KEYS_ENCRYPTED_SECRETS: i8 = 32;
+/// Secrets are not encrypted
+pub const UNENCRYPTED_SECRETS: i8 = 96;
+
+/// Data is obtained only from trusted sources
+pub const TRUSTED_SOURCES: i8 = 2;
+/// Data is obtained from sources that are not trusted
+pub const UNTRUSTED_SOURCES: i8 = 32;
+/// Data is obtained from contraindicated sources
+pub const CONTRAINDICATED_SOURCES: i8 = 96;
+
+/// Categories of the claims in a trustworthiness vector, see [AR4SI Section 2.3].
+///
+/// [AR4SI Section 2.3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-ar4si#section-2.3
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum TrustCategory {
+    InstanceIdentity,
+    Configuration,
+    Executables,
+    FileSystem,
+    Hardware,
+    RuntimeOpaque,
+    StorageOpaque,
+    SourcedData,
+}
+
+impl TrustCategory {
+    /// All categories, in the order of their keys
+    pub const ALL: [TrustCategory; 8] = [
+        TrustCategory::InstanceIdentity,
+        TrustCategory::Configuration,
+        TrustCategory::Executables,
+        TrustCategory::FileSystem,
+        TrustCategory::Hardware,
+        TrustCategory::RuntimeOpaque,
+        TrustCategory::StorageOpaque,
+        TrustCategory::SourcedData,
+    ];
+
+    /// Returns the key used for the category in a CBOR-encoded trustworthiness vector.
+    pub fn key(&self) -> i32 {
+        match self {
+            TrustCategory::InstanceIdentity => 0,
+            TrustCategory::Configuration => 1,
+            TrustCategory::Executables => 2,
+            TrustCategory::FileSystem => 3,
+            TrustCategory::Hardware => 4,
+            TrustCategory::RuntimeOpaque => 5,
+            TrustCategory::StorageOpaque => 6,
+            TrustCategory::SourcedData => 7,
+        }
+    }
+
+    /// Returns the name used for the category in a JSON-encoded trustworthiness vector.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrustCategory::InstanceIdentity => "instance-identity",
+            TrustCategory::Configuration => "configuration",
+            TrustCategory::Executables => "executables",
+            TrustCategory::FileSystem => "file-system",
+            TrustCategory::Hardware => "hardware",
+            TrustCategory::RuntimeOpaque => "runtime-opaque",
+            TrustCategory::StorageOpaque => "storage-opaque",
+            TrustCategory::SourcedData => "sourced-data",
+        }
+    }
+}
+
+/// Returns the tier of a trustworthiness claim value.
+pub fn tier(value: i8) -> TrustTier {
+    match value {
+        -1..=1 => TrustTier::None,
+        -32..=-2 | 2..=31 => TrustTier::Affirming,
+        -96..=-33 | 32..=95 => TrustTier::Warning,
+        _ => TrustTier::Contraindicated,
+    }
+}
+
+/// Returns the meaning of `value` for claims in `category`, if it is defined by AR4SI.
+pub fn describe(category: TrustCategory, value: i8) -> Option<&'static str> {
+    let general = match value {
+        NO_CLAIM => Some("no claim is made"),
+        UNEXPECTED_EVAL_ERROR => Some("unexpected error during evaluation"),
+        VERIFIER_MALFUNCTION => Some("verifier malfunction"),
+        CRYPTO_VALIDATION_FAILED => Some("cryptographic validation failed"),
+        _ => None,
+    };
+    if general.is_some() {
+        return general;
+    }
+    match (category, value) {
+        (TrustCategory::InstanceIdentity, TRUSTWORTHY_INSTANCE) => {
+            Some("recognized and trustworthy instance")
+        }
+        (TrustCategory::InstanceIdentity, UNTRUSTWORTHY_INSTANCE) => {
+            Some("recognized but untrustworthy instance")
+        }
+        (TrustCategory::InstanceIdentity, UNRECOGNIZED_INSTANCE) => Some("unrecognized instance"),
+        (TrustCategory::Configuration, APPROVED_CONFIG) => Some("approved configuration"),
+        (TrustCategory::Configuration, NO_CONFIG_VULNS) => {
+            Some("no known configuration vulnerabilities")
+        }
+        (TrustCategory::Configuration, UNSAFE_CONFIG) => {
+            Some("configuration with known vulnerabilities")
+        }
+        (TrustCategory::Configuration, UNSUPPORTABLE_CONFIG) => Some("unsupportable configuration"),
+        (TrustCategory::Executables, APPROVED_RUNTIME) => Some("approved runtime executables"),
+        (TrustCategory::Executables, APPROVED_BOOT) => Some("approved boot executables"),
+        (TrustCategory::Executables, UNSAFE_RUNTIME) => {
+            Some("executables with known vulnerabilities")
+        }
+        (TrustCategory::Executables, UNRECOGNIZED_RUNTIME) => Some("unrecognized executables"),
+        (TrustCategory::Executables, CONTRAINDICATED_RUNTIME) => {
+            Some("contraindicated executables")
+        }
+        (TrustCategory::FileSystem, APPROVED_FILES) => Some("approved files"),
+        (TrustCategory::FileSystem, UNRECOGNIZED_FILES) => Some("unrecognized files"),
+        (TrustCategory::FileSystem, CONTRAINDICATED_FILES) => Some("contraindicated files"),
+        (TrustCategory::Hardware, GENUINE_HARDWARE) => Some("genuine hardware"),
+        (TrustCategory::Hardware, UNSAFE_HARDWARE) => Some("hardware with known vulnerabilities"),
+        (TrustCategory::Hardware, CONTRAINDICATED_HARDWARE) => Some("contraindicated hardware"),
+        (TrustCategory::Hardware, UNRECOGNIZED_HARDWARE) => Some("unrecognized hardware"),
+        (TrustCategory::RuntimeOpaque, ENCRYPTED_MEMORY) => Some("encrypted and isolated memory"),
+        (TrustCategory::RuntimeOpaque, ISOLATED_MEMORY) => Some("isolated memory"),
+        (TrustCategory::RuntimeOpaque, VISIBLE_MEMORY) => Some("visible memory"),
+        (TrustCategory::StorageOpaque, HW_KEYS_ENCRYPTED_SECRETS) => {
+            Some("secrets encrypted with hardware-protected keys")
+        }
+        (TrustCategory::StorageOpaque, SW_KEYS_ENCRYPTED_SECRETS) => {
+            Some("secrets encrypted with software-protected keys")
+        }
+        (TrustCategory::StorageOpaque, UNENCRYPTED_SECRETS) => Some("unencrypted secrets"),
+        (TrustCategory::SourcedData, TRUSTED_SOURCES) => Some("trusted sources"),
+        (TrustCategory::SourcedData, UNTRUSTED_SOURCES) => Some("untrusted sources"),
+        (TrustCategory::SourcedData, CONTRAINDICATED_SOURCES) => Some("contraindicated sources"),
+        _ => None,
+    }
+}
@@ -0,0 +1,110 @@
+use ciborium::value::Value;
+use common::{BytesType, NonceType};
+use ear::choices::*;
+use ear::maps::*;
+use ear::trust::*;
+
+const EAR_JSON: &str = r#"{
+    "eat_profile": "tag:github.com,2023:veraison/ear",
+    "iat": 1666529184,
+    "ear.verifier-id": {
+        "developer": "https://veraison-project.org",
+        "build": "vts 0.0.1"
+    },
+    "ear.raw-evidence": "3q2-7w",
+    "submods": {
+        "PARSEC_TPM": {
+            "ear.status": "affirming",
+            "ear.trustworthiness-vector": {
+                "instance-identity": 2,
+                "configuration": 2,
+                "executables": 3,
+                "file-system": 2,
+                "hardware": 2,
+                "runtime-opaque": 2,
+                "storage-opaque": 2,
+                "sourced-data": 2
+            },
+            "ear.appraisal-policy-id": "policy:TPM_ENACTTRUST"
+        }
+    }
+}"#;
+
+fn verifier_id() -> VerifierId {
+    VerifierId {
+        developer: "https://veraison-project.org".to_string(),
+        build: "vts 0.0.1".to_string(),
+    }
+}
+
+#[test]
+fn ear_json_test() {
+    let ear = Ear::from_json(EAR_JSON.as_bytes()).unwrap();
+    assert_eq!(ear.iat, 1666529184);
+    assert_eq!(ear.verifier_id, verifier_id());
+    assert_eq!(ear.raw_evidence, Some(vec![0xde, 0xad, 0xbe, 0xef]));
+    let tpm = ear.submods.get("PARSEC_TPM").unwrap();
+    assert_eq!(tpm.status, TrustTier::Affirming);
+    assert_eq!(tpm.policy_id, Some("policy:TPM_ENACTTRUST".to_string()));
+    let tv = tpm.trust_vector.as_ref().unwrap();
+    assert_eq!(tv.get(TrustCategory::Executables), Some(APPROVED_BOOT));
+    assert_eq!(tv.claims().len(), 8);
+    assert_eq!(ear.status(), TrustTier::Affirming);
+
+    let encoded = ear.to_json().unwrap();
+    let expected: serde_json::Value = serde_json::from_str(EAR_JSON).unwrap();
+    let actual: serde_json::Value = serde_json::from_slice(&encoded).unwrap();
+    assert_eq!(expected, actual);
+
+    let other = EAR_JSON.replace("veraison/ear", "veraison/other");
+    assert!(Ear::from_json(other.as_bytes()).is_err());
+}
+
+#[test]
+fn ear_cbor_test() {
+    let mut ear = Ear::new(1666529184, verifier_id());
+    ear.nonce = Some(NonceType::One(BytesType::Bytes(vec![7; 16])));
+    let mut tv = TrustVector::default();
+    tv.set(TrustCategory::InstanceIdentity, Some(TRUSTWORTHY_INSTANCE));
+    tv.set(TrustCategory::Executables, Some(UNRECOGNIZED_RUNTIME));
+    let mut appraisal = Appraisal::new(TrustTier::Affirming);
+    appraisal.trust_vector = Some(tv);
+    appraisal.update_status_from_trust_vector();
+    assert_eq!(appraisal.status, TrustTier::Warning);
+    assert!(ear.submods.insert("tee", appraisal).unwrap().is_none());
+    ear.submods
+        .insert("rot", Appraisal::new(TrustTier::Contraindicated))
+        .unwrap();
+    assert!(ear
+        .submods
+        .insert("a/b", Appraisal::new(TrustTier::None))
+        .is_err());
+    assert_eq!(ear.status(), TrustTier::Contraindicated);
+
+    let encoded = ear.to_cbor().unwrap();
+    let value: Value = ciborium::de::from_reader(encoded.as_slice()).unwrap();
+    let m = value.as_map().unwrap();
+    let status = m
+        .iter()
+        .find(|(k, _)| *k == Value::Integer(266.into()))
+        .map(|(_, v)| v)
+        .unwrap();
+    let tee = status.as_map().unwrap()[0].1.as_map().unwrap();
+    assert!(tee.contains(&(Value::Integer(1000.into()), Value::Integer(32.into()))));
+
+    let decoded = Ear::from_cbor(&encoded).unwrap();
+    assert_eq!(decoded, ear);
+    let names: Vec<&str> = decoded.submods.iter().map(|(n, _)| n).collect();
+    assert_eq!(names, vec!["tee", "rot"]);
+
+    let cbor = EarCbor::try_from(&ear).unwrap();
+    assert_eq!(
+        cbor.submods.get("rot").unwrap().status,
+        TrustTierCbor::Contraindicated
+    );
+
+    // the nonce is base64url text in JSON, as in EAT
+    let json: serde_json::Value = serde_json::from_slice(&ear.to_json().unwrap()).unwrap();
+    assert_eq!(json["eat_nonce"], "BwcHBwcHBwcHBwcHBwcHBw");
+    assert_eq!(Ear::from_json(&ear.to_json().unwrap()).unwrap(), ear);
+}
@@ -0,0 +1,43 @@
+use ear::choices::TrustTier;
+use ear::maps::TrustVector;
+use ear::trust::*;
+
+#[test]
+fn tier_test() {
+    assert_eq!(tier(NO_CLAIM), TrustTier::None);
+    assert_eq!(tier(VERIFIER_MALFUNCTION), TrustTier::None);
+    assert_eq!(tier(UNEXPECTED_EVAL_ERROR), TrustTier::None);
+    assert_eq!(tier(APPROVED_CONFIG), TrustTier::Affirming);
+    assert_eq!(tier(31), TrustTier::Affirming);
+    assert_eq!(tier(-2), TrustTier::Affirming);
+    assert_eq!(tier(UNSAFE_CONFIG), TrustTier::Warning);
+    assert_eq!(tier(-33), TrustTier::Warning);
+    assert_eq!(tier(CRYPTO_VALIDATION_FAILED), TrustTier::Contraindicated);
+    assert_eq!(tier(-128), TrustTier::Contraindicated);
+    assert_eq!(tier(127), TrustTier::Contraindicated);
+    assert!(TrustTier::Warning > TrustTier::Affirming);
+
+    assert_eq!(
+        describe(TrustCategory::Hardware, GENUINE_HARDWARE),
+        Some("genuine hardware")
+    );
+    assert_eq!(
+        describe(TrustCategory::SourcedData, CRYPTO_VALIDATION_FAILED),
+        Some("cryptographic validation failed")
+    );
+    assert_eq!(describe(TrustCategory::FileSystem, 3), None);
+    assert_eq!(TrustCategory::RuntimeOpaque.name(), "runtime-opaque");
+    assert_eq!(TrustCategory::SourcedData.key(), 7);
+
+    let mut tv = TrustVector::default();
+    assert_eq!(tv.worst_tier(), TrustTier::None);
+    tv.set(TrustCategory::Hardware, Some(GENUINE_HARDWARE));
+    assert_eq!(tv.worst_tier(), TrustTier::Affirming);
+    tv.set(TrustCategory::StorageOpaque, Some(UNENCRYPTED_SECRETS));
+    assert_eq!(tv.worst_tier(), TrustTier::Contraindicated);
+    tv.set(TrustCategory::StorageOpaque, None);
+    assert_eq!(
+        tv.claims(),
+        vec![(TrustCategory::Hardware, GENUINE_HARDWARE)]
+    );
+}
@@ -15,6 +15,7 @@ use crate::cbor_specific::SelectorCbor;
 use crate::choices::*;
 use crate::json_specific::*;
 use cbor_derive::StructToArray;
+use common::named::{named_from_value, serialize_named, NamedVisitor};
 use common::{choices::*, *};
 
 /// JSON encoding/decoding of `JSON-Selector` options suitable for inclusion in a `Detached-EAT-Bundle`
@@ -126,14 +127,14 @@ impl TryFrom<&NestedTokenCbor> for NestedToken {
 pub struct NestedTokenCbor(pub SelectorCbor);
 impl TryFrom<Value> for NestedTokenCbor {
     type Error = String;
-    fn try_from(_value: Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        NestedTokenCbor::try_from(&value)
     }
 }
 impl TryFrom<&Value> for NestedTokenCbor {
     type Error = String;
-    fn try_from(_value: &Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Ok(NestedTokenCbor(SelectorCbor::try_from(value)?))
     }
 }
 impl TryFrom<NestedToken> for NestedTokenCbor {
@@ -217,7 +218,7 @@ impl TryFrom<&WrappedClaimsSetCbor> for WrappedClaimsSet {
 ///
 /// [EAT Section 5]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-5
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-pub struct WrappedClaimsSetCbor(pub Vec<u8>);
+pub struct WrappedClaimsSetCbor(#[serde(with = "serde_bytes")] pub Vec<u8>);
 impl TryFrom<Value> for WrappedClaimsSetCbor {
     type Error = String;
     fn try_from(value: Value) -> Result<Self, Self::Error> {
@@ -274,8 +275,113 @@ impl TryFrom<&WrappedClaimsSet> for WrappedClaimsSetCbor {
 pub struct DetachedEatBundle {
     #[cbor(cbor = "true")]
     pub main_token: NestedToken,
-    #[cbor(value = "Array", cbor = "true")]
-    pub detached_claims_set: Vec<WrappedClaimsSet>,
+    #[cbor(cbor = "true")]
+    pub detached_claims_sets: DetachedClaimsSets,
+}
+
+/// JSON encoding/decoding of the `detached-claims-sets` field of `Detached-EAT-Bundle`, see
+/// [EAT Section 5]. Entries are kept in the order in which they were added or decoded.
+///
+/// Use [DetachedClaimsSetsCbor](DetachedClaimsSetsCbor) for CBOR-encoded EATs.
+///
+/// ```text
+/// detached-claims-sets: {
+///     + tstr => JC<json-wrapped-claims-set,
+///                  cbor-wrapped-claims-set>
+/// }
+/// ```
+/// [EAT Section 5]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-5
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DetachedClaimsSets(pub Vec<(String, WrappedClaimsSet)>);
+impl Serialize for DetachedClaimsSets {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_named(&self.0, serializer)
+    }
+}
+impl<'de> Deserialize<'de> for DetachedClaimsSets {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(DetachedClaimsSets(
+            deserializer.deserialize_map(NamedVisitor(PhantomData))?,
+        ))
+    }
+}
+impl TryFrom<DetachedClaimsSetsCbor> for DetachedClaimsSets {
+    type Error = String;
+    fn try_from(value: DetachedClaimsSetsCbor) -> Result<Self, Self::Error> {
+        DetachedClaimsSets::try_from(&value)
+    }
+}
+impl TryFrom<&DetachedClaimsSetsCbor> for DetachedClaimsSets {
+    type Error = String;
+    fn try_from(value: &DetachedClaimsSetsCbor) -> Result<Self, Self::Error> {
+        let mut retval = vec![];
+        for (name, wcs) in &value.0 {
+            retval.push((name.clone(), WrappedClaimsSet::try_from(wcs)?));
+        }
+        Ok(DetachedClaimsSets(retval))
+    }
+}
+
+/// CBOR encoding/decoding of the `detached-claims-sets` field of `Detached-EAT-Bundle`, see
+/// [EAT Section 5]. Entries are kept in the order in which they were added or decoded.
+///
+/// Use [DetachedClaimsSets](DetachedClaimsSets) for JSON-encoded EATs.
+///
+/// ```text
+/// detached-claims-sets: {
+///     + tstr => JC<json-wrapped-claims-set,
+///                  cbor-wrapped-claims-set>
+/// }
+/// ```
+/// [EAT Section 5]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-5
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DetachedClaimsSetsCbor(pub Vec<(String, WrappedClaimsSetCbor)>);
+impl DetachedClaimsSetsCbor {
+    /// Returns the wrapped claims set with the given name, if present.
+    pub fn get(&self, name: &str) -> Option<&WrappedClaimsSetCbor> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, w)| w)
+    }
+}
+impl Serialize for DetachedClaimsSetsCbor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_named(&self.0, serializer)
+    }
+}
+impl<'de> Deserialize<'de> for DetachedClaimsSetsCbor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        DetachedClaimsSetsCbor::try_from(&value).map_err(D::Error::custom)
+    }
+}
+impl TryFrom<Value> for DetachedClaimsSetsCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        DetachedClaimsSetsCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for DetachedClaimsSetsCbor {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Ok(DetachedClaimsSetsCbor(named_from_value(value, |v| {
+            WrappedClaimsSetCbor::try_from(v)
+        })?))
+    }
+}
+impl TryFrom<DetachedClaimsSets> for DetachedClaimsSetsCbor {
+    type Error = String;
+    fn try_from(value: DetachedClaimsSets) -> Result<Self, Self::Error> {
+        DetachedClaimsSetsCbor::try_from(&value)
+    }
+}
+impl TryFrom<&DetachedClaimsSets> for DetachedClaimsSetsCbor {
+    type Error = String;
+    fn try_from(value: &DetachedClaimsSets) -> Result<Self, Self::Error> {
+        let mut retval = vec![];
+        for (name, wcs) in &value.0 {
+            retval.push((name.clone(), WrappedClaimsSetCbor::try_from(wcs)?));
+        }
+        Ok(DetachedClaimsSetsCbor(retval))
+    }
 }
 
 /// JSON encoding/decoding of `Detached-Submodule-Digest`, see [EAT Section 4.2.18].
@@ -0,0 +1,255 @@
+//! Detached EAT bundles
+//!
+//! A Detached EAT Bundle (DEB) carries a main token together with one or more claims sets that are
+//! conveyed outside of the main token, see [EAT Section 5]. The main token protects each detached
+//! claims set by including a `Detached-Submodule-Digest` in a submodule with the same name.
+//!
+//! ```text
+//! BUNDLE-Messages = BUNDLE-Tagged-Message / BUNDLE-Untagged-Message
+//! BUNDLE-Tagged-Message   = #6.602(BUNDLE-Untagged-Message)
+//! BUNDLE-Untagged-Message = Detached-EAT-Bundle
+//! ```
+//!
+//! [DetachedBundleBuilder] computes the digests, inserts them into the main claims set and
+//! assembles a bundle around the token produced from it. [check_detached_claims_sets] recomputes
+//! each digest and matches it to the named submodule. [verify_bundle] does the same after verifying
+//! the main token. Only CBOR-encoded bundles are supported. Digests are computed over the encoded
+//! claims set carried in each `cbor-wrapped-claims-set`.
+//!
+//! [EAT Section 5]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-5
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::tag::Required;
+use ciborium::{de::from_reader, ser::into_writer, value::Value};
+
+use crate::arrays::{
+    DetachedClaimsSetsCbor, DetachedEatBundleCbor, DetachedSubmoduleDigestCbor, NestedTokenCbor,
+    WrappedClaimsSetCbor,
+};
+use crate::cbor_specific::{SelectorCbor, SubmoduleCbor, SubmodsCbor};
+use crate::cwt::{decode_claims, encode_claims, verify_cwt};
+use crate::maps::ClaimsSetClaimsCbor;
+use common::digest::HashAlgorithm;
+use common::TextOrInt;
+use cose::sign::CoseVerifier;
+
+/// CBOR tag for `BUNDLE-Tagged-Message`, see [EAT Section 5].
+///
+/// [EAT Section 5]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-5
+pub const BUNDLE_TAG: u64 = 602;
+
+/// COSE algorithm identifier for SHA-256
+pub const SHA_256: i64 = -16;
+/// COSE algorithm identifier for SHA-384
+pub const SHA_384: i64 = -43;
+/// COSE algorithm identifier for SHA-512
+pub const SHA_512: i64 = -44;
+
+/// Computes a digest over `data` using the algorithm identified by `hash_algorithm`, which may be
+/// a COSE algorithm identifier or a hash name from the IANA Named Information registry.
+pub fn compute_digest(hash_algorithm: &TextOrInt, data: &[u8]) -> Result<Vec<u8>, String> {
+    let alg = match hash_algorithm {
+        TextOrInt::Int(SHA_256) => HashAlgorithm::Sha256,
+        TextOrInt::Int(SHA_384) => HashAlgorithm::Sha384,
+        TextOrInt::Int(SHA_512) => HashAlgorithm::Sha512,
+        TextOrInt::Text(t) => HashAlgorithm::from_name(t)?,
+        _ => return Err(format!("Unsupported hash algorithm: {:?}", hash_algorithm)),
+    };
+    Ok(alg.digest(data))
+}
+
+/// Assembles a [DetachedEatBundleCbor] from a main claims set and a set of named claims sets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetachedBundleBuilder {
+    main: ClaimsSetClaimsCbor,
+    hash_algorithm: TextOrInt,
+    detached: DetachedClaimsSetsCbor,
+}
+
+impl DetachedBundleBuilder {
+    /// Creates a builder for a bundle around `main`. Digests are computed using SHA-256 unless
+    /// [hash_algorithm](DetachedBundleBuilder::hash_algorithm) is called.
+    pub fn new(main: ClaimsSetClaimsCbor) -> Self {
+        DetachedBundleBuilder {
+            main,
+            hash_algorithm: TextOrInt::Int(SHA_256),
+            detached: DetachedClaimsSetsCbor::default(),
+        }
+    }
+
+    /// Sets the algorithm used to compute digests over the detached claims sets.
+    pub fn hash_algorithm(mut self, hash_algorithm: TextOrInt) -> Result<Self, String> {
+        compute_digest(&hash_algorithm, &[])?;
+        self.hash_algorithm = hash_algorithm;
+        Ok(self)
+    }
+
+    /// Adds a detached claims set that will be referenced by the submodule named `name`.
+    pub fn detached_claims_set(
+        mut self,
+        name: &str,
+        claims: &ClaimsSetClaimsCbor,
+    ) -> Result<Self, String> {
+        if self.detached.get(name).is_some() {
+            return Err(format!("Duplicate detached claims set: {}", name));
+        }
+        self.detached.0.push((
+            name.to_string(),
+            WrappedClaimsSetCbor(encode_claims(claims)?),
+        ));
+        Ok(self)
+    }
+
+    /// Returns the main claims set with a `Detached-Submodule-Digest` submodule for each detached
+    /// claims set. Submodules already present with the same names are replaced.
+    pub fn main_claims(&self) -> Result<ClaimsSetClaimsCbor, String> {
+        let mut main = self.main.clone();
+        let mut submods = main.submods.take().unwrap_or_default();
+        for (name, wcs) in &self.detached.0 {
+            let dsd = DetachedSubmoduleDigestCbor {
+                hash_algorithm: self.hash_algorithm.clone(),
+                digest: compute_digest(&self.hash_algorithm, &wcs.0)?,
+            };
+            submods.insert(
+                name,
+                SubmoduleCbor::SelectorCbor(SelectorCbor::DetachedSubmoduleDigest(dsd)),
+            )?;
+        }
+        main.submods = Some(submods);
+        Ok(main)
+    }
+
+    /// Builds the bundle. `wrap` receives the output of [main_claims](DetachedBundleBuilder::main_claims)
+    /// and returns the encoded main token, e.g., a tagged CWT produced by
+    /// [sign_cwt](crate::cwt::sign_cwt).
+    pub fn build<F>(self, wrap: F) -> Result<DetachedEatBundleCbor, String>
+    where
+        F: FnOnce(&ClaimsSetClaimsCbor) -> Result<Vec<u8>, String>,
+    {
+        if self.detached.0.is_empty() {
+            return Err(
+                "A detached EAT bundle requires at least one detached claims set".to_string(),
+            );
+        }
+        let token = wrap(&self.main_claims()?)?;
+        Ok(DetachedEatBundleCbor {
+            main_token: NestedTokenCbor(SelectorCbor::CborTokenInsideCborToken(token)),
+            detached_claims_sets: self.detached,
+        })
+    }
+}
+
+/// Results of matching detached claims sets to the digests in a main claims set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BundleReport {
+    /// Detached claims sets whose digest matched the submodule of the same name
+    pub verified: Vec<(String, ClaimsSetClaimsCbor)>,
+    /// Names of detached claims sets whose digest did not match the submodule of the same name
+    pub mismatched: Vec<String>,
+    /// Names of submodules carrying a digest for which no detached claims set was provided
+    pub missing: Vec<String>,
+    /// Names of detached claims sets for which no submodule carries a digest
+    pub extra: Vec<String>,
+}
+
+impl BundleReport {
+    /// Returns true if every detached claims set matched a digest and every digest was matched.
+    pub fn is_valid(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// Matches each of the `detached` claims sets to the `Detached-Submodule-Digest` submodule of the
+/// same name in `main` by recomputing the digest. The claims sets are decoded only if the digest
+/// matches. An error is returned if a digest uses an unsupported algorithm.
+pub fn check_detached_claims_sets(
+    main: &ClaimsSetClaimsCbor,
+    detached: &DetachedClaimsSetsCbor,
+) -> Result<BundleReport, String> {
+    let empty = SubmodsCbor::default();
+    let submods = main.submods.as_ref().unwrap_or(&empty);
+    let mut report = BundleReport::default();
+    for (name, sm) in &submods.0 {
+        if let SubmoduleCbor::SelectorCbor(SelectorCbor::DetachedSubmoduleDigest(dsd)) = sm {
+            match detached.get(name) {
+                Some(wcs) => {
+                    if compute_digest(&dsd.hash_algorithm, &wcs.0)? == dsd.digest {
+                        report.verified.push((name.clone(), decode_claims(&wcs.0)?));
+                    } else {
+                        report.mismatched.push(name.clone());
+                    }
+                }
+                None => report.missing.push(name.clone()),
+            }
+        }
+    }
+    for (name, _) in &detached.0 {
+        if !matches!(
+            submods.get(name),
+            Some(SubmoduleCbor::SelectorCbor(
+                SelectorCbor::DetachedSubmoduleDigest(_)
+            ))
+        ) {
+            report.extra.push(name.clone());
+        }
+    }
+    Ok(report)
+}
+
+/// Verifies the main token of `bundle` using `verify_main` then matches the detached claims sets to
+/// the digests in the resulting claims set. Returns the verified main claims set and the report.
+pub fn verify_bundle<F>(
+    bundle: &DetachedEatBundleCbor,
+    verify_main: F,
+) -> Result<(ClaimsSetClaimsCbor, BundleReport), String>
+where
+    F: FnOnce(&NestedTokenCbor) -> Result<ClaimsSetClaimsCbor, String>,
+{
+    let main = verify_main(&bundle.main_token)?;
+    let report = check_detached_claims_sets(&main, &bundle.detached_claims_sets)?;
+    Ok((main, report))
+}
+
+/// Verifies a bundle whose main token is a CWT carried in a `COSE_Sign1` using `verifier`, see
+/// [verify_bundle].
+pub fn verify_cwt_bundle<V: CoseVerifier + ?Sized>(
+    bundle: &DetachedEatBundleCbor,
+    verifier: &V,
+) -> Result<(ClaimsSetClaimsCbor, BundleReport), String> {
+    verify_bundle(bundle, |token| match &token.0 {
+        SelectorCbor::CborTokenInsideCborToken(t) => verify_cwt(t, verifier),
+        _ => Err("Main token is not a CBOR-encoded token".to_string()),
+    })
+}
+
+/// Encodes `bundle`, wrapping it in the `BUNDLE-Tagged-Message` tag if `tagged` is true.
+pub fn encode_bundle(bundle: &DetachedEatBundleCbor, tagged: bool) -> Result<Vec<u8>, String> {
+    let mut encoded = vec![];
+    let result = match tagged {
+        true => into_writer(
+            &Required::<&DetachedEatBundleCbor, BUNDLE_TAG>(bundle),
+            &mut encoded,
+        ),
+        false => into_writer(bundle, &mut encoded),
+    };
+    match result {
+        Ok(_) => Ok(encoded),
+        Err(e) => Err(format!("Failed to encode detached EAT bundle: {:?}", e)),
+    }
+}
+
+/// Decodes a tagged or untagged detached EAT bundle.
+pub fn decode_bundle(encoded: &[u8]) -> Result<DetachedEatBundleCbor, String> {
+    let value: Value = match from_reader(encoded) {
+        Ok(v) => v,
+        Err(e) => return Err(format!("Failed to parse detached EAT bundle: {:?}", e)),
+    };
+    match value {
+        Value::Tag(BUNDLE_TAG, inner) => DetachedEatBundleCbor::try_from(*inner),
+        Value::Tag(t, _) => Err(format!("Unsupported detached EAT bundle tag: {}", t)),
+        v => DetachedEatBundleCbor::try_from(v),
+    }
+}
@@ -41,15 +41,17 @@
 //! and CBOR-Selector is represented by the SelectorCbor enum.
 //!
 use alloc::boxed::Box;
-use alloc::string::String;
+use alloc::string::{String, ToString};
 use alloc::{vec, vec::Vec};
 
 use ciborium::{ser::into_writer, value::Value};
+use serde::de::Error;
 use serde::{Deserialize, Serialize};
 
 use crate::arrays::DetachedSubmoduleDigestCbor;
-use crate::json_specific::{JsonSelectorValue, Submodule};
+use crate::json_specific::{JsonSelectorValue, Submodule, Submods};
 use crate::maps::ClaimsSetClaimsCbor;
+use crate::submods::{NamedMap, SubmoduleTree};
 
 // EAT-CBOR-Token = $EAT-CBOR-Tagged-Token / $EAT-CBOR-Untagged-Token
 // $EAT-CBOR-Untagged-Token /= CWT-Untagged-Message
@@ -79,9 +81,28 @@ use crate::maps::ClaimsSetClaimsCbor;
 #[allow(missing_docs)]
 pub enum SelectorCbor {
     JsonTokenInsideCborToken(String),
-    CborTokenInsideCborToken(Vec<u8>),
+    CborTokenInsideCborToken(#[serde(with = "serde_bytes")] Vec<u8>),
     DetachedSubmoduleDigest(DetachedSubmoduleDigestCbor),
 }
+impl TryFrom<Value> for SelectorCbor {
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        SelectorCbor::try_from(&value)
+    }
+}
+impl TryFrom<&Value> for SelectorCbor {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Text(s) => Ok(SelectorCbor::JsonTokenInsideCborToken(s.clone())),
+            Value::Bytes(b) => Ok(SelectorCbor::CborTokenInsideCborToken(b.clone())),
+            Value::Array(_) => Ok(SelectorCbor::DetachedSubmoduleDigest(
+                DetachedSubmoduleDigestCbor::try_from(value.clone())?,
+            )),
+            _ => Err("Failed to parse value as a SelectorCbor".to_string()),
+        }
+    }
+}
 
 /// Represents the options available for encoding Submodule claims using CBOR.
 ///
@@ -95,7 +116,7 @@ pub enum SelectorCbor {
 /// Submodule = Claims-Set / CBOR-Selector
 /// ```
 /// The SubmoduleEnum uses this alternative definition.
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 #[serde(untagged)]
 #[allow(missing_docs)]
 pub enum SubmoduleCbor {
@@ -103,16 +124,30 @@ pub enum SubmoduleCbor {
     ClaimsSet(Box<ClaimsSetClaimsCbor>),
     SelectorCbor(SelectorCbor),
 }
+impl<'de> Deserialize<'de> for SubmoduleCbor {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        SubmoduleCbor::try_from(&value).map_err(D::Error::custom)
+    }
+}
 impl TryFrom<Value> for SubmoduleCbor {
     type Error = String;
-    fn try_from(_value: Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        SubmoduleCbor::try_from(&value)
     }
 }
 impl TryFrom<&Value> for SubmoduleCbor {
     type Error = String;
-    fn try_from(_value: &Value) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(_) => Ok(SubmoduleCbor::ClaimsSet(Box::new(
+                ClaimsSetClaimsCbor::try_from(value)?,
+            ))),
+            _ => match SelectorCbor::try_from(value) {
+                Ok(s) => Ok(SubmoduleCbor::SelectorCbor(s)),
+                Err(_) => Err("Failed to parse value as a SubmoduleCbor".to_string()),
+            },
+        }
     }
 }
 impl TryFrom<Submodule> for SubmoduleCbor {
@@ -199,3 +234,51 @@ impl TryFrom<&Submodule> for SubmoduleCbor {
         }
     }
 }
+
+/// CBOR encoding/decoding of the value of the `submods` claim, i.e., a map from submodule name to
+/// [SubmoduleCbor], see [EAT Section 4.2.18]. Entries are kept in the order in which they were
+/// added or decoded.
+///
+/// Use [Submods](Submods) for JSON-encoded EATs.
+///
+/// ```text
+/// $$Claims-Set-Claims //= (submods-label => { + text => Submodule })
+/// ```
+/// Any text name is accepted when decoding, but names that are empty or contain `/` cannot be
+/// added using [NamedMap::insert] or addressed by path, see
+/// [check_submodule_name](crate::submods::check_submodule_name).
+///
+/// [EAT Section 4.2.18]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.18
+pub type SubmodsCbor = NamedMap<SubmoduleCbor>;
+
+impl SubmoduleTree for ClaimsSetClaimsCbor {
+    type Submodule = SubmoduleCbor;
+
+    fn submods(&self) -> Option<&SubmodsCbor> {
+        self.submods.as_ref()
+    }
+
+    fn submods_mut(&mut self) -> &mut Option<SubmodsCbor> {
+        &mut self.submods
+    }
+
+    fn claims_set(submodule: &SubmoduleCbor) -> Option<&Self> {
+        match submodule {
+            SubmoduleCbor::ClaimsSet(cs) => Some(cs),
+            _ => None,
+        }
+    }
+
+    fn claims_set_mut(submodule: &mut SubmoduleCbor) -> Option<&mut Self> {
+        match submodule {
+            SubmoduleCbor::ClaimsSet(cs) => Some(cs),
+            _ => None,
+        }
+    }
+}
+impl TryFrom<Submods> for SubmodsCbor {
+    type Error = String;
+    fn try_from(value: Submods) -> Result<Self, Self::Error> {
+        SubmodsCbor::try_from(&value)
+    }
+}
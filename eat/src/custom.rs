@@ -0,0 +1,409 @@
+//! Registration of custom claims
+//!
+//! Claims that are not represented by a field of [ClaimsSetClaims] are carried as untyped
+//! key/value pairs in its `other` field, see [EAT Section 4]. The [CustomClaim] trait associates a
+//! type with a claim key and a JSON claim name so that such claims can be accessed using typed
+//! getters and setters on [ClaimsSetClaims] and [ClaimsSetClaimsCbor]. This is useful for claims
+//! registered in the [IANA CWT Claims registry] after this crate was released and for private
+//! claims.
+//!
+//! Custom claims round trip through the CBOR encoding without further configuration. In a
+//! JSON-encoded claims set, a claim is a member named with its JSON claim name, which cannot be
+//! determined from the claim key alone. [ClaimRegistry] records the registered claims and
+//! performs the mapping when encoding and decoding JSON claims sets.
+//!
+//! [EAT Section 4]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4
+//! [IANA CWT Claims registry]: https://www.iana.org/assignments/cwt/cwt.xhtml
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::value::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::maps::{ClaimsSetClaims, ClaimsSetClaimsCbor};
+use crate::validate::*;
+use common::tuple::{Tuple, TupleCbor};
+
+/// Keys of the claims represented by fields of [ClaimsSetClaims], which cannot be registered.
+const RESERVED_LABELS: [i64; 28] = [
+    ISS_LABEL,
+    SUB_LABEL,
+    AUD_LABEL,
+    EXP_LABEL,
+    NBF_LABEL,
+    IAT_LABEL,
+    CTI_LABEL,
+    NONCE_LABEL,
+    UEID_LABEL,
+    SUEIDS_LABEL,
+    OEMID_LABEL,
+    HARDWARE_MODEL_LABEL,
+    HARDWARE_VERSION_LABEL,
+    SECURE_BOOT_LABEL,
+    DEBUG_STATUS_LABEL,
+    LOCATION_LABEL,
+    PROFILE_LABEL,
+    SUBMODS_LABEL,
+    UPTIME_LABEL,
+    BOOT_SEED_LABEL,
+    INTENDED_USE_LABEL,
+    DLOAS_LABEL,
+    SW_NAME_LABEL,
+    SW_VERSION_LABEL,
+    MANIFESTS_LABEL,
+    MEASUREMENTS_LABEL,
+    MEASUREMENT_RESULTS_LABEL,
+    BOOT_COUNT_LABEL,
+];
+
+/// Names of the JSON members used by [ClaimsSetClaims], which cannot be registered.
+const RESERVED_NAMES: [&str; 29] = [
+    "iss",
+    "sub",
+    "aud",
+    "exp",
+    "nbf",
+    "iat",
+    "cti",
+    "eat_nonce",
+    "bootcount",
+    "bootseed",
+    "dbgstat",
+    "dloas",
+    "hwmodel",
+    "hwversion",
+    "intuse",
+    "location",
+    "eat_profile",
+    "oemboot",
+    "swname",
+    "swversion",
+    "ueid",
+    "uptime",
+    "manifests",
+    "measurements",
+    "measres",
+    "oemid",
+    "sueids",
+    "submods",
+    "other",
+];
+
+/// A claim that is not represented by a field of [ClaimsSetClaims].
+///
+/// By default, values are converted to and from CBOR and JSON using the type's [Serialize] and
+/// [DeserializeOwned] implementations. Types whose encodings differ, e.g., byte strings that are
+/// base64url-encoded in JSON, override the conversion methods.
+pub trait CustomClaim: Sized + Serialize + DeserializeOwned {
+    /// Claim key used in CBOR-encoded claims sets
+    const LABEL: i64;
+    /// Claim name used in JSON-encoded claims sets
+    const NAME: &'static str;
+
+    /// Encodes the claim value as CBOR.
+    fn to_cbor(&self) -> Result<Value, String> {
+        match Value::serialized(self) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to encode {} claim: {:?}", Self::NAME, e)),
+        }
+    }
+
+    /// Decodes the claim value from CBOR.
+    fn from_cbor(value: &Value) -> Result<Self, String> {
+        match value.deserialized() {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to parse {} claim: {:?}", Self::NAME, e)),
+        }
+    }
+
+    /// Encodes the claim value as JSON.
+    fn to_json(&self) -> Result<serde_json::Value, String> {
+        match serde_json::to_value(self) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to encode {} claim: {:?}", Self::NAME, e)),
+        }
+    }
+
+    /// Decodes the claim value from JSON.
+    fn from_json(value: &serde_json::Value) -> Result<Self, String> {
+        match serde_json::from_value(value.clone()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to parse {} claim: {:?}", Self::NAME, e)),
+        }
+    }
+}
+
+fn is_label(key: &Value, label: i64) -> bool {
+    match key {
+        Value::Integer(i) => i128::from(*i) == i128::from(label),
+        _ => false,
+    }
+}
+
+impl ClaimsSetClaims {
+    /// Returns the value of the custom claim `T`, if present.
+    pub fn custom_claim<T: CustomClaim>(&self) -> Result<Option<T>, String> {
+        match self
+            .other
+            .iter()
+            .flatten()
+            .find(|t| is_label(&t.key, T::LABEL))
+        {
+            Some(t) => Ok(Some(T::from_cbor(&t.value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the value of the custom claim `T`, replacing any existing value.
+    pub fn set_custom_claim<T: CustomClaim>(&mut self, claim: &T) -> Result<(), String> {
+        let value = claim.to_cbor()?;
+        let other = self.other.get_or_insert_with(Vec::new);
+        match other.iter_mut().find(|t| is_label(&t.key, T::LABEL)) {
+            Some(t) => t.value = value,
+            None => other.push(Tuple {
+                key: Value::Integer(T::LABEL.into()),
+                value,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Removes the custom claim `T`, returning true if it was present.
+    pub fn remove_custom_claim<T: CustomClaim>(&mut self) -> bool {
+        let other = match self.other.as_mut() {
+            Some(o) => o,
+            None => return false,
+        };
+        let len = other.len();
+        other.retain(|t| !is_label(&t.key, T::LABEL));
+        let removed = other.len() != len;
+        if other.is_empty() {
+            self.other = None;
+        }
+        removed
+    }
+}
+
+impl ClaimsSetClaimsCbor {
+    /// Returns the value of the custom claim `T`, if present.
+    pub fn custom_claim<T: CustomClaim>(&self) -> Result<Option<T>, String> {
+        match self
+            .other
+            .iter()
+            .flatten()
+            .find(|t| is_label(&t.key, T::LABEL))
+        {
+            Some(t) => Ok(Some(T::from_cbor(&t.value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets the value of the custom claim `T`, replacing any existing value.
+    pub fn set_custom_claim<T: CustomClaim>(&mut self, claim: &T) -> Result<(), String> {
+        let value = claim.to_cbor()?;
+        let other = self.other.get_or_insert_with(Vec::new);
+        match other.iter_mut().find(|t| is_label(&t.key, T::LABEL)) {
+            Some(t) => t.value = value,
+            None => other.push(TupleCbor {
+                key: Value::Integer(T::LABEL.into()),
+                value,
+            }),
+        }
+        Ok(())
+    }
+
+    /// Removes the custom claim `T`, returning true if it was present.
+    pub fn remove_custom_claim<T: CustomClaim>(&mut self) -> bool {
+        let other = match self.other.as_mut() {
+            Some(o) => o,
+            None => return false,
+        };
+        let len = other.len();
+        other.retain(|t| !is_label(&t.key, T::LABEL));
+        let removed = other.len() != len;
+        if other.is_empty() {
+            self.other = None;
+        }
+        removed
+    }
+}
+
+/// Conversions for a registered claim with the type erased
+#[derive(Clone, Copy)]
+struct Registration {
+    label: i64,
+    name: &'static str,
+    cbor_to_json: fn(&Value) -> Result<serde_json::Value, String>,
+    json_to_cbor: fn(&serde_json::Value) -> Result<Value, String>,
+}
+
+fn cbor_to_json<T: CustomClaim>(value: &Value) -> Result<serde_json::Value, String> {
+    T::from_cbor(value)?.to_json()
+}
+
+fn json_to_cbor<T: CustomClaim>(value: &serde_json::Value) -> Result<Value, String> {
+    T::from_json(value)?.to_cbor()
+}
+
+/// Set of [CustomClaim] types used to encode and decode JSON claims sets and to check the values
+/// of custom claims.
+#[derive(Clone, Default)]
+pub struct ClaimRegistry {
+    claims: Vec<Registration>,
+}
+
+impl ClaimRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the custom claim `T`. An error is returned if its key or name is used by a claim
+    /// represented by a field of [ClaimsSetClaims] or by a claim already registered.
+    pub fn register<T: CustomClaim>(&mut self) -> Result<&mut Self, String> {
+        if RESERVED_LABELS.contains(&T::LABEL) {
+            return Err(format!("Claim key {} is reserved", T::LABEL));
+        }
+        if RESERVED_NAMES.contains(&T::NAME) {
+            return Err(format!("Claim name {} is reserved", T::NAME));
+        }
+        if self
+            .claims
+            .iter()
+            .any(|r| r.label == T::LABEL || r.name == T::NAME)
+        {
+            return Err(format!(
+                "A claim with key {} or name {} is already registered",
+                T::LABEL,
+                T::NAME
+            ));
+        }
+        self.claims.push(Registration {
+            label: T::LABEL,
+            name: T::NAME,
+            cbor_to_json: cbor_to_json::<T>,
+            json_to_cbor: json_to_cbor::<T>,
+        });
+        Ok(self)
+    }
+
+    /// Returns the key and name of each registered claim.
+    pub fn registered(&self) -> Vec<(i64, &'static str)> {
+        self.claims.iter().map(|r| (r.label, r.name)).collect()
+    }
+
+    /// Returns the name of the registered claim with key `label`.
+    pub fn name(&self, label: i64) -> Option<&'static str> {
+        self.by_label(label).map(|r| r.name)
+    }
+
+    /// Returns the key of the registered claim named `name`.
+    pub fn label(&self, name: &str) -> Option<i64> {
+        self.claims.iter().find(|r| r.name == name).map(|r| r.label)
+    }
+
+    fn by_label(&self, label: i64) -> Option<&Registration> {
+        self.claims.iter().find(|r| r.label == label)
+    }
+
+    fn by_key(&self, key: &Value) -> Option<&Registration> {
+        self.claims.iter().find(|r| is_label(key, r.label))
+    }
+
+    /// Checks that each registered claim present in `claims` can be decoded as its registered
+    /// type.
+    pub fn check(&self, claims: &ClaimsSetClaimsCbor) -> Result<(), String> {
+        for t in claims.other.iter().flatten() {
+            if let Some(r) = self.by_key(&t.key) {
+                (r.cbor_to_json)(&t.value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes a claims set as JSON. Registered claims are encoded as members named with their
+    /// claim names. Other custom claims remain in the `other` member. Absent claims are omitted.
+    pub fn encode_json(&self, claims: &ClaimsSetClaims) -> Result<Vec<u8>, String> {
+        let mut claims = claims.clone();
+        let mut named = vec![];
+        if let Some(other) = claims.other.take() {
+            let mut rest = vec![];
+            for t in other {
+                match self.by_key(&t.key) {
+                    Some(r) => named.push((r.name, (r.cbor_to_json)(&t.value)?)),
+                    None => rest.push(t),
+                }
+            }
+            if !rest.is_empty() {
+                claims.other = Some(rest);
+            }
+        }
+        let mut value: serde_json::Value =
+            match serde_json::from_slice(&crate::jwt::encode_json_claims(&claims)?) {
+                Ok(v) => v,
+                Err(e) => return Err(format!("Failed to encode claims set: {:?}", e)),
+            };
+        if let serde_json::Value::Object(m) = &mut value {
+            for (name, v) in named {
+                m.insert(name.to_string(), v);
+            }
+        }
+        match serde_json::to_vec(&value) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(format!("Failed to encode claims set: {:?}", e)),
+        }
+    }
+
+    /// Decodes a JSON claims set. Members named with the name of a registered claim are decoded
+    /// as that claim and stored in `other` using the claim key.
+    pub fn decode_json(&self, payload: &[u8]) -> Result<ClaimsSetClaims, String> {
+        let mut value: serde_json::Value = match serde_json::from_slice(payload) {
+            Ok(v) => v,
+            Err(e) => return Err(format!("Failed to parse claims set: {:?}", e)),
+        };
+        let mut custom = vec![];
+        if let serde_json::Value::Object(m) = &mut value {
+            for r in &self.claims {
+                if let Some(v) = m.remove(r.name) {
+                    custom.push(Tuple {
+                        key: Value::Integer(r.label.into()),
+                        value: (r.json_to_cbor)(&v)?,
+                    });
+                }
+            }
+        }
+        let mut claims: ClaimsSetClaims = match serde_json::from_value(value) {
+            Ok(c) => c,
+            Err(e) => return Err(format!("Failed to parse claims set: {:?}", e)),
+        };
+        if !custom.is_empty() {
+            claims.other.get_or_insert_with(Vec::new).extend(custom);
+        }
+        Ok(claims)
+    }
+
+    /// Returns the registered claims present in `claims` as JSON values keyed by claim name.
+    pub fn json_values(
+        &self,
+        claims: &ClaimsSetClaimsCbor,
+    ) -> Result<Vec<(String, serde_json::Value)>, String> {
+        let mut retval = vec![];
+        for t in claims.other.iter().flatten() {
+            if let Some(r) = self.by_key(&t.key) {
+                retval.push((r.name.to_string(), (r.cbor_to_json)(&t.value)?));
+            }
+        }
+        Ok(retval)
+    }
+}
+
+impl core::fmt::Debug for ClaimRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ClaimRegistry")
+            .field("claims", &self.registered())
+            .finish()
+    }
+}
@@ -0,0 +1,205 @@
+//! Entity Attestation Tokens encoded as CBOR Web Tokens (CWTs)
+//!
+//! A CBOR-encoded EAT is a CWT, i.e., a [ClaimsSetClaimsCbor] carried as the payload of a
+//! `COSE_Sign1` or `COSE_Mac0` structure, see [EAT Section 7.3.2] and [CWT Section 6].
+//!
+//! ```text
+//! $EAT-CBOR-Tagged-Token /= CWT-Tagged-Message
+//! $EAT-CBOR-Untagged-Token /= CWT-Untagged-Message
+//! CWT_Tag = #6.61(COSE_Sign1_Tagged / COSE_Mac0_Tagged)
+//! ```
+//!
+//! [sign_cwt] and [mac_cwt] produce tokens in any of the forms described by [CwtForm].
+//! [verify_cwt] and [verify_mac_cwt] accept any of these forms and return the verified claims.
+//! [unwrap_cwt] removes the tags without verifying anything.
+//!
+//! [EAT Section 7.3.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#name-cbor-specific-cddl
+//! [CWT Section 6]: https://datatracker.ietf.org/doc/html/rfc8392#section-6
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::tag::Required;
+use ciborium::{de::from_reader, ser::into_writer, value::Value};
+
+use crate::maps::ClaimsSetClaimsCbor;
+use common::BinaryOrNil;
+use cose::arrays::{CoseMac0Cbor, CoseSign1Cbor, TaggedCoseMac0, TaggedCoseSign1};
+use cose::maps::HeaderMapCbor;
+use cose::sign::{CoseMacKey, CoseSigner, CoseVerifier, COSE_MAC0_TAG, COSE_SIGN1_TAG};
+
+/// CBOR tag for `CWT_Tag`, see [CWT Section 6].
+///
+/// [CWT Section 6]: https://datatracker.ietf.org/doc/html/rfc8392#section-6
+pub const CWT_TAG: u64 = 61;
+
+/// Encoding options for the COSE structure that carries a CWT.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CwtForm {
+    /// COSE tag wrapped in the CWT tag, i.e., `61(18(...))` or `61(17(...))`
+    CwtTagged,
+    /// COSE tag only, i.e., `18(...)` or `17(...)`
+    CoseTagged,
+    /// Untagged COSE structure
+    Untagged,
+}
+
+/// COSE structure extracted from a CWT by [unwrap_cwt].
+#[derive(Clone, Debug, PartialEq)]
+pub enum CoseMessage {
+    /// Structure tagged as `COSE_Sign1`
+    Sign1(CoseSign1Cbor),
+    /// Structure tagged as `COSE_Mac0`
+    Mac0(CoseMac0Cbor),
+    /// Untagged structure. `COSE_Sign1` and `COSE_Mac0` share the same layout, so the type must be
+    /// known from context. The final field is available as `signature`.
+    Untagged(CoseSign1Cbor),
+}
+
+/// A CWT with the tags removed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnwrappedCwt {
+    /// True if the CWT tag was present
+    pub cwt_tagged: bool,
+    /// The COSE structure carrying the claims
+    pub message: CoseMessage,
+}
+
+impl UnwrappedCwt {
+    /// Returns the encoded claims set.
+    pub fn payload(&self) -> Result<&[u8], String> {
+        let payload = match &self.message {
+            CoseMessage::Sign1(m) | CoseMessage::Untagged(m) => &m.payload,
+            CoseMessage::Mac0(m) => &m.payload,
+        };
+        match payload {
+            BinaryOrNil::Binary(b) => Ok(b),
+            BinaryOrNil::Nil => Err("CWT does not contain a payload".to_string()),
+        }
+    }
+
+    /// Returns the claims set without verifying the signature or tag.
+    pub fn claims_unverified(&self) -> Result<ClaimsSetClaimsCbor, String> {
+        decode_claims(self.payload()?)
+    }
+}
+
+/// Encodes a claims set for use as a CWT payload.
+pub fn encode_claims(claims: &ClaimsSetClaimsCbor) -> Result<Vec<u8>, String> {
+    let mut encoded = vec![];
+    match into_writer(claims, &mut encoded) {
+        Ok(_) => Ok(encoded),
+        Err(e) => Err(format!("Failed to encode claims set: {:?}", e)),
+    }
+}
+
+/// Decodes a claims set from a CWT payload.
+pub fn decode_claims(payload: &[u8]) -> Result<ClaimsSetClaimsCbor, String> {
+    match from_reader(payload) {
+        Ok(c) => Ok(c),
+        Err(e) => Err(format!("Failed to parse claims set: {:?}", e)),
+    }
+}
+
+fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let mut encoded = vec![];
+    match into_writer(value, &mut encoded) {
+        Ok(_) => Ok(encoded),
+        Err(e) => Err(format!("Failed to encode CWT: {:?}", e)),
+    }
+}
+
+/// Signs `claims` using `signer` and returns the token encoded as indicated by `form`.
+/// `unprotected` is included in the `COSE_Sign1` as-is (e.g., to provide a key identifier).
+pub fn sign_cwt<S: CoseSigner + ?Sized>(
+    claims: &ClaimsSetClaimsCbor,
+    unprotected: HeaderMapCbor,
+    form: CwtForm,
+    signer: &S,
+) -> Result<Vec<u8>, String> {
+    let payload = encode_claims(claims)?;
+    let sign1 = CoseSign1Cbor::sign(&payload, unprotected, &[], false, signer)?;
+    match form {
+        CwtForm::CwtTagged => encode(&Required::<TaggedCoseSign1, CWT_TAG>(Required(sign1))),
+        CwtForm::CoseTagged => encode(&Required::<CoseSign1Cbor, COSE_SIGN1_TAG>(sign1)),
+        CwtForm::Untagged => encode(&sign1),
+    }
+}
+
+/// Computes a tag over `claims` using `key` and returns the token encoded as indicated by `form`.
+/// `unprotected` is included in the `COSE_Mac0` as-is (e.g., to provide a key identifier).
+pub fn mac_cwt<K: CoseMacKey + ?Sized>(
+    claims: &ClaimsSetClaimsCbor,
+    unprotected: HeaderMapCbor,
+    form: CwtForm,
+    key: &K,
+) -> Result<Vec<u8>, String> {
+    let payload = encode_claims(claims)?;
+    let mac0 = CoseMac0Cbor::mac(&payload, unprotected, &[], false, key)?;
+    match form {
+        CwtForm::CwtTagged => encode(&Required::<TaggedCoseMac0, CWT_TAG>(Required(mac0))),
+        CwtForm::CoseTagged => encode(&Required::<CoseMac0Cbor, COSE_MAC0_TAG>(mac0)),
+        CwtForm::Untagged => encode(&mac0),
+    }
+}
+
+/// Removes the CWT and COSE tags from `token` and parses the COSE structure.
+pub fn unwrap_cwt(token: &[u8]) -> Result<UnwrappedCwt, String> {
+    let value: Value = match from_reader(token) {
+        Ok(v) => v,
+        Err(e) => return Err(format!("Failed to parse CWT: {:?}", e)),
+    };
+    let (cwt_tagged, value) = match value {
+        Value::Tag(CWT_TAG, inner) => match *inner {
+            Value::Tag(..) => (true, *inner),
+            _ => return Err("CWT tag must wrap a tagged COSE structure".to_string()),
+        },
+        v => (false, v),
+    };
+    let message = match value {
+        Value::Tag(COSE_SIGN1_TAG, inner) => CoseMessage::Sign1(CoseSign1Cbor::try_from(*inner)?),
+        Value::Tag(COSE_MAC0_TAG, inner) => CoseMessage::Mac0(CoseMac0Cbor::try_from(*inner)?),
+        Value::Tag(t, _) => return Err(format!("Unsupported CWT tag: {}", t)),
+        v => CoseMessage::Untagged(CoseSign1Cbor::try_from(v)?),
+    };
+    Ok(UnwrappedCwt {
+        cwt_tagged,
+        message,
+    })
+}
+
+/// Verifies the signature on a CWT carried in a `COSE_Sign1` and returns the claims. Untagged
+/// structures are assumed to be `COSE_Sign1`.
+pub fn verify_cwt<V: CoseVerifier + ?Sized>(
+    token: &[u8],
+    verifier: &V,
+) -> Result<ClaimsSetClaimsCbor, String> {
+    let unwrapped = unwrap_cwt(token)?;
+    match &unwrapped.message {
+        CoseMessage::Sign1(m) | CoseMessage::Untagged(m) => m.verify(&[], None, verifier)?,
+        CoseMessage::Mac0(_) => return Err("Expected COSE_Sign1 but found COSE_Mac0".to_string()),
+    }
+    unwrapped.claims_unverified()
+}
+
+/// Verifies the tag on a CWT carried in a `COSE_Mac0` and returns the claims. Untagged structures
+/// are assumed to be `COSE_Mac0`.
+pub fn verify_mac_cwt<K: CoseMacKey + ?Sized>(
+    token: &[u8],
+    key: &K,
+) -> Result<ClaimsSetClaimsCbor, String> {
+    let unwrapped = unwrap_cwt(token)?;
+    match &unwrapped.message {
+        CoseMessage::Mac0(m) => m.verify(&[], None, key)?,
+        CoseMessage::Untagged(m) => CoseMac0Cbor {
+            protected: m.protected.clone(),
+            unprotected: m.unprotected.clone(),
+            payload: m.payload.clone(),
+            tag: m.signature.clone(),
+        }
+        .verify(&[], None, key)?,
+        CoseMessage::Sign1(_) => return Err("Expected COSE_Mac0 but found COSE_Sign1".to_string()),
+    }
+    unwrapped.claims_unverified()
+}
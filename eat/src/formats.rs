@@ -0,0 +1,284 @@
+//! Decoding of manifest and measurement payloads
+//!
+//! The `manifests` and `measurements` claims carry payloads whose type is identified by a CoAP
+//! content format, see [EAT Section 4.2.15] and [EAT Section 4.2.16]. [PayloadRegistry] maps
+//! content formats and CBOR tags to [PayloadDecoder] implementations that decode payloads into
+//! typed objects. Content formats take precedence; a binary payload whose content format is not
+//! registered is dispatched on its CBOR tag, if any. Payloads that match neither are returned
+//! as-is.
+//!
+//! Decoders for CoSWID and CoMID payloads are provided by [CoSwidDecoder] and [CoMidDecoder].
+//! Other formats, e.g., SUIT manifests, can be supported by registering additional decoders.
+//!
+//! [EAT Section 4.2.15]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.15
+//! [EAT Section 4.2.16]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.16
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+use core::any::Any;
+
+use ciborium::{de::from_reader, value::Value};
+
+use crate::maps::ClaimsSetClaimsCbor;
+use common::{CoapContentFormat, TextOrBinary};
+use corim::maps::{ConciseMidTag, ConciseMidTagCbor};
+use coswid::maps::{ConciseSwidTag, ConciseSwidTagCbor};
+
+/// CoAP content format for `application/swid+cbor`, see [RFC 9393 Section 6.5].
+///
+/// [RFC 9393 Section 6.5]: https://datatracker.ietf.org/doc/html/rfc9393#section-6.5
+pub const COSWID_CONTENT_FORMAT: CoapContentFormat = 258;
+
+/// CBOR tag for a CoSWID carried in a `concise-tag-type-choice`, see [CoRIM Section 1.3].
+///
+/// [CoRIM Section 1.3]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-1.3
+pub const COSWID_TAG: u64 = 505;
+
+/// CBOR tag for a `tagged-coswid`, see [RFC 9393].
+///
+/// [RFC 9393]: https://datatracker.ietf.org/doc/html/rfc9393
+pub const COSWID_RFC9393_TAG: u64 = 1398229316;
+
+/// CBOR tag for a CoMID carried in a `concise-tag-type-choice`, see [CoRIM Section 1.3].
+///
+/// [CoRIM Section 1.3]: https://datatracker.ietf.org/doc/html/draft-birkholz-rats-corim-03#section-1.3
+pub const COMID_TAG: u64 = 506;
+
+/// Payload of a `manifest-format` or `measurements-format` decoded by [PayloadRegistry]
+pub enum DecodedPayload {
+    /// CoSWID
+    CoSwid(Box<ConciseSwidTag>),
+    /// CoMID
+    CoMid(Box<ConciseMidTag>),
+    /// Object produced by a decoder registered by an application, see [DecodedPayload::custom]
+    Custom(Box<dyn Any>),
+    /// Payload with a content format and tag for which no decoder is registered
+    Raw(TextOrBinary),
+}
+
+impl DecodedPayload {
+    /// Returns the object produced by a registered decoder if it is of type `T`.
+    pub fn custom<T: Any>(&self) -> Option<&T> {
+        match self {
+            DecodedPayload::Custom(c) => c.downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for DecodedPayload {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodedPayload::CoSwid(c) => f.debug_tuple("CoSwid").field(c).finish(),
+            DecodedPayload::CoMid(c) => f.debug_tuple("CoMid").field(c).finish(),
+            DecodedPayload::Custom(_) => f.debug_tuple("Custom").finish(),
+            DecodedPayload::Raw(r) => f.debug_tuple("Raw").field(r).finish(),
+        }
+    }
+}
+
+/// Decodes payloads of one or more content formats.
+pub trait PayloadDecoder {
+    /// Returns a name for the decoder, for use in diagnostics.
+    fn name(&self) -> String;
+
+    /// Returns the CoAP content formats handled by the decoder.
+    fn content_formats(&self) -> Vec<CoapContentFormat> {
+        vec![]
+    }
+
+    /// Returns the CBOR tags handled by the decoder when the content format is not registered.
+    fn tags(&self) -> Vec<u64> {
+        vec![]
+    }
+
+    /// Decodes `payload`, which was carried with the given `content_type`. When dispatched on a
+    /// tag, the payload includes the tag.
+    fn decode(
+        &self,
+        content_type: CoapContentFormat,
+        payload: &TextOrBinary,
+    ) -> Result<DecodedPayload, String>;
+}
+
+/// Parses a binary payload, removing the tag if it is one of `tags`. A byte string within the tag,
+/// i.e., `#6.<tag>(bstr .cbor ...)`, is parsed as the payload.
+fn untag(name: &str, payload: &TextOrBinary, tags: &[u64]) -> Result<Value, String> {
+    let b = match payload {
+        TextOrBinary::Binary(b) => b,
+        TextOrBinary::Text(_) => return Err(format!("{} payload is not binary", name)),
+    };
+    let parse = |b: &[u8]| -> Result<Value, String> {
+        from_reader(b).map_err(|e| format!("Failed to parse {} payload: {:?}", name, e))
+    };
+    match parse(b)? {
+        Value::Tag(t, inner) if tags.contains(&t) => match *inner {
+            Value::Bytes(b) => parse(&b),
+            v => Ok(v),
+        },
+        Value::Tag(t, _) => Err(format!("Unexpected tag for {} payload: {}", name, t)),
+        v => Ok(v),
+    }
+}
+
+/// Decodes CBOR-encoded CoSWIDs identified by [COSWID_CONTENT_FORMAT], [COSWID_TAG] or
+/// [COSWID_RFC9393_TAG].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CoSwidDecoder;
+
+impl PayloadDecoder for CoSwidDecoder {
+    fn name(&self) -> String {
+        "CoSWID".to_string()
+    }
+
+    fn content_formats(&self) -> Vec<CoapContentFormat> {
+        vec![COSWID_CONTENT_FORMAT]
+    }
+
+    fn tags(&self) -> Vec<u64> {
+        vec![COSWID_TAG, COSWID_RFC9393_TAG]
+    }
+
+    fn decode(
+        &self,
+        _content_type: CoapContentFormat,
+        payload: &TextOrBinary,
+    ) -> Result<DecodedPayload, String> {
+        let v = untag("CoSWID", payload, &[COSWID_TAG, COSWID_RFC9393_TAG])?;
+        let c = ConciseSwidTag::try_from(ConciseSwidTagCbor::try_from(v)?)?;
+        Ok(DecodedPayload::CoSwid(Box::new(c)))
+    }
+}
+
+/// Decodes CoMIDs identified by [COMID_TAG].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CoMidDecoder;
+
+impl PayloadDecoder for CoMidDecoder {
+    fn name(&self) -> String {
+        "CoMID".to_string()
+    }
+
+    fn tags(&self) -> Vec<u64> {
+        vec![COMID_TAG]
+    }
+
+    fn decode(
+        &self,
+        _content_type: CoapContentFormat,
+        payload: &TextOrBinary,
+    ) -> Result<DecodedPayload, String> {
+        let v = untag("CoMID", payload, &[COMID_TAG])?;
+        let c = ConciseMidTag::try_from(ConciseMidTagCbor::try_from(v)?)?;
+        Ok(DecodedPayload::CoMid(Box::new(c)))
+    }
+}
+
+/// Collection of payload decoders that can be looked up by content format or tag.
+#[derive(Default)]
+pub struct PayloadRegistry {
+    decoders: Vec<Box<dyn PayloadDecoder>>,
+}
+
+impl PayloadRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing the decoders provided by this crate, i.e., [CoSwidDecoder]
+    /// and [CoMidDecoder].
+    pub fn with_builtin_decoders() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(CoSwidDecoder));
+        registry.register(Box::new(CoMidDecoder));
+        registry
+    }
+
+    /// Adds a decoder. Decoders registered later take precedence over those registered earlier
+    /// that share a content format or tag.
+    pub fn register(&mut self, decoder: Box<dyn PayloadDecoder>) {
+        self.decoders.insert(0, decoder);
+    }
+
+    /// Returns the names of the registered decoders.
+    pub fn names(&self) -> Vec<String> {
+        self.decoders.iter().map(|d| d.name()).collect()
+    }
+
+    /// Returns the decoder for `content_type`, if registered.
+    pub fn lookup(&self, content_type: CoapContentFormat) -> Option<&dyn PayloadDecoder> {
+        self.decoders
+            .iter()
+            .find(|d| d.content_formats().contains(&content_type))
+            .map(|d| d.as_ref())
+    }
+
+    /// Returns the decoder for `tag`, if registered.
+    pub fn lookup_tag(&self, tag: u64) -> Option<&dyn PayloadDecoder> {
+        self.decoders
+            .iter()
+            .find(|d| d.tags().contains(&tag))
+            .map(|d| d.as_ref())
+    }
+
+    /// Decodes `payload` using the decoder for `content_type` or, failing that, the decoder for
+    /// the tag of a binary payload. Payloads without a decoder are returned as
+    /// [DecodedPayload::Raw]. An error is returned if the selected decoder fails.
+    pub fn decode(
+        &self,
+        content_type: CoapContentFormat,
+        payload: &TextOrBinary,
+    ) -> Result<DecodedPayload, String> {
+        if let Some(d) = self.lookup(content_type) {
+            return d.decode(content_type, payload);
+        }
+        if let TextOrBinary::Binary(b) = payload {
+            if let Ok(Value::Tag(t, _)) = from_reader::<Value, _>(b.as_slice()) {
+                if let Some(d) = self.lookup_tag(t) {
+                    return d.decode(content_type, payload);
+                }
+            }
+        }
+        Ok(DecodedPayload::Raw(payload.clone()))
+    }
+
+    /// Decodes the payloads of the `manifests` claim in `claims`, in order. An empty list is
+    /// returned if the claim is absent.
+    pub fn manifests(&self, claims: &ClaimsSetClaimsCbor) -> Result<Vec<DecodedPayload>, String> {
+        match &claims.manifests {
+            Some(m) => {
+                m.0.iter()
+                    .map(|f| self.decode(f.content_type, &f.content_format))
+                    .collect()
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Decodes the payloads of the `measurements` claim in `claims`, in order. An empty list is
+    /// returned if the claim is absent.
+    pub fn measurements(
+        &self,
+        claims: &ClaimsSetClaimsCbor,
+    ) -> Result<Vec<DecodedPayload>, String> {
+        match &claims.measurements {
+            Some(m) => {
+                m.0.iter()
+                    .map(|f| self.decode(f.content_type, &f.content_format))
+                    .collect()
+            }
+            None => Ok(vec![]),
+        }
+    }
+}
+
+impl core::fmt::Debug for PayloadRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PayloadRegistry")
+            .field("decoders", &self.names())
+            .finish()
+    }
+}
@@ -26,8 +26,9 @@ use serde::__private::de::Content;
 use serde::{Deserialize, Serialize};
 
 use crate::arrays::{DetachedEatBundle, DetachedSubmoduleDigest};
-use crate::cbor_specific::{SelectorCbor, SubmoduleCbor};
+use crate::cbor_specific::{SelectorCbor, SubmoduleCbor, SubmodsCbor};
 use crate::maps::{ClaimsSetClaims, ClaimsSetClaimsCbor};
+use crate::submods::{NamedMap, SubmoduleTree};
 
 // EAT-JSON-Token = $EAT-JSON-Token-Formats
 //
@@ -245,14 +246,16 @@ pub struct SelectorForDeb {
 #[serde(untagged)]
 #[allow(missing_docs)]
 pub enum Submodule {
+    // JsonSelector must be tried first since every claim in a claims set is optional, i.e., any
+    // JSON object can be parsed as a ClaimsSetClaims
+    JsonSelector(JsonSelector),
     //todo consider changing ClaimsSetClaims to be an enum with ClaimsSet accepting duplicates
     ClaimsSet(Box<ClaimsSetClaims>),
-    JsonSelector(JsonSelector),
 }
 impl TryFrom<SubmoduleCbor> for Submodule {
     type Error = String;
-    fn try_from(_value: SubmoduleCbor) -> Result<Self, Self::Error> {
-        todo!()
+    fn try_from(value: SubmoduleCbor) -> Result<Self, Self::Error> {
+        Submodule::try_from(&value)
     }
 }
 impl TryFrom<&SubmoduleCbor> for Submodule {
@@ -282,7 +285,7 @@ impl TryFrom<&SubmoduleCbor> for Submodule {
             SubmoduleCbor::SelectorCbor(SelectorCbor::DetachedSubmoduleDigest(dsm)) => {
                 //todo unwrap
                 let js = JsonSelector {
-                    token_type: JsonSelectorType::Bundle,
+                    token_type: JsonSelectorType::Digest,
                     nested_token: JsonSelectorValue::DetachedSubmoduleDigest(
                         dsm.try_into().unwrap(),
                     ),
@@ -292,3 +295,51 @@ impl TryFrom<&SubmoduleCbor> for Submodule {
         }
     }
 }
+
+/// JSON encoding/decoding of the value of the `submods` claim, i.e., a map from submodule name to
+/// [Submodule], see [EAT Section 4.2.18]. Entries are kept in the order in which they were added
+/// or decoded.
+///
+/// Use [SubmodsCbor](SubmodsCbor) for CBOR-encoded EATs.
+///
+/// ```text
+/// $$Claims-Set-Claims //= (submods-label => { + text => Submodule })
+/// ```
+/// Any text name is accepted when decoding, but names that are empty or contain `/` cannot be
+/// added using [NamedMap::insert] or addressed by path, see
+/// [check_submodule_name](crate::submods::check_submodule_name).
+///
+/// [EAT Section 4.2.18]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.18
+pub type Submods = NamedMap<Submodule>;
+
+impl SubmoduleTree for ClaimsSetClaims {
+    type Submodule = Submodule;
+
+    fn submods(&self) -> Option<&Submods> {
+        self.submods.as_ref()
+    }
+
+    fn submods_mut(&mut self) -> &mut Option<Submods> {
+        &mut self.submods
+    }
+
+    fn claims_set(submodule: &Submodule) -> Option<&Self> {
+        match submodule {
+            Submodule::ClaimsSet(cs) => Some(cs),
+            _ => None,
+        }
+    }
+
+    fn claims_set_mut(submodule: &mut Submodule) -> Option<&mut Self> {
+        match submodule {
+            Submodule::ClaimsSet(cs) => Some(cs),
+            _ => None,
+        }
+    }
+}
+impl TryFrom<SubmodsCbor> for Submods {
+    type Error = String;
+    fn try_from(value: SubmodsCbor) -> Result<Self, Self::Error> {
+        Submods::try_from(&value)
+    }
+}
@@ -0,0 +1,221 @@
+//! Entity Attestation Tokens encoded as JSON Web Tokens (JWTs)
+//!
+//! A JSON-encoded EAT is a JWT, i.e., a [ClaimsSetClaims] carried as the payload of a JWS using
+//! the compact serialization, see [EAT Section 7.3.3] and [JWT Section 3].
+//!
+//! ```text
+//! $EAT-JSON-Token-Formats /= JWT-Message
+//! JWT-Message = text .regexp "[A-Za-z0-9_=-]+\\.[A-Za-z0-9_=-]+\\.[A-Za-z0-9_=-]+"
+//! ```
+//!
+//! Signatures are produced and verified using the [CoseSigner] and [CoseVerifier] traits from the
+//! `cose` crate, which operate on raw bytes and produce signatures in the form used by both COSE
+//! and JWS. ES256 and EdDSA are supported.
+//!
+//! [EAT Section 7.3.3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#name-json-specific-cddl
+//! [JWT Section 3]: https://datatracker.ietf.org/doc/html/rfc7519#section-3
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+use crate::json_specific::{JsonSelectorType, JsonSelectorValue, Submodule};
+use crate::maps::{ClaimsSetClaims, BASE64_URL};
+use crate::submods::SubmoduleTree;
+use cose::sign::{CoseSigner, CoseVerifier, ALG_EDDSA, ALG_ES256};
+
+/// JOSE header of a JWS, see [JWS Section 4.1]. Only the parameters used with EATs are
+/// represented. Tokens that carry a `crit` parameter are rejected.
+///
+/// [JWS Section 4.1]: https://datatracker.ietf.org/doc/html/rfc7515#section-4.1
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct JwsHeader {
+    pub alg: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crit: Option<Vec<String>>,
+}
+
+/// Returns the JWS `alg` value corresponding to a COSE algorithm identifier.
+pub fn jws_algorithm(cose_alg: i64) -> Result<&'static str, String> {
+    match cose_alg {
+        ALG_ES256 => Ok("ES256"),
+        ALG_EDDSA => Ok("EdDSA"),
+        _ => Err(format!(
+            "No JWS algorithm corresponds to COSE algorithm {}",
+            cose_alg
+        )),
+    }
+}
+
+/// Returns the COSE algorithm identifier corresponding to a JWS `alg` value.
+pub fn cose_algorithm(jws_alg: &str) -> Result<i64, String> {
+    match jws_alg {
+        "ES256" => Ok(ALG_ES256),
+        "EdDSA" => Ok(ALG_EDDSA),
+        _ => Err(format!("Unsupported JWS algorithm: {}", jws_alg)),
+    }
+}
+
+fn b64_decode(s: &str, what: &str) -> Result<Vec<u8>, String> {
+    match base64::decode_engine(s, &BASE64_URL) {
+        Ok(b) => Ok(b),
+        Err(e) => Err(format!("Failed to base64url decode JWT {}: {:?}", what, e)),
+    }
+}
+
+/// Removes members with null values from JSON objects, recursively. Absent claims are serialized
+/// as null by serde but must be omitted from a JWT.
+fn remove_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(m) => {
+            m.retain(|_, v| !v.is_null());
+            m.values_mut().for_each(remove_nulls);
+        }
+        serde_json::Value::Array(a) => a.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+/// Encodes a claims set as JSON for use as a JWT payload. Absent claims are omitted.
+pub fn encode_json_claims(claims: &ClaimsSetClaims) -> Result<Vec<u8>, String> {
+    let mut value = match serde_json::to_value(claims) {
+        Ok(v) => v,
+        Err(e) => return Err(format!("Failed to encode claims set: {:?}", e)),
+    };
+    remove_nulls(&mut value);
+    match serde_json::to_vec(&value) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(format!("Failed to encode claims set: {:?}", e)),
+    }
+}
+
+/// Decodes a claims set from a JWT payload.
+pub fn decode_json_claims(payload: &[u8]) -> Result<ClaimsSetClaims, String> {
+    match serde_json::from_slice(payload) {
+        Ok(c) => Ok(c),
+        Err(e) => Err(format!("Failed to parse claims set: {:?}", e)),
+    }
+}
+
+/// A JWS compact serialization split into its parts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnwrappedJwt {
+    /// Decoded JOSE header
+    pub header: JwsHeader,
+    /// Decoded payload
+    pub payload: Vec<u8>,
+    /// Decoded signature
+    pub signature: Vec<u8>,
+    /// The encoded header and payload separated by a period, i.e., the JWS signing input
+    pub signing_input: String,
+}
+
+impl UnwrappedJwt {
+    /// Returns the claims set without verifying the signature.
+    pub fn claims_unverified(&self) -> Result<ClaimsSetClaims, String> {
+        decode_json_claims(&self.payload)
+    }
+
+    /// Verifies the signature using `verifier`, which must support the algorithm indicated in the
+    /// header.
+    pub fn verify<V: CoseVerifier + ?Sized>(&self, verifier: &V) -> Result<(), String> {
+        if self.header.crit.is_some() {
+            return Err("JWS crit header parameter is not supported".to_string());
+        }
+        let alg = cose_algorithm(&self.header.alg)?;
+        if alg != verifier.algorithm() {
+            return Err(format!(
+                "Algorithm mismatch: token uses {} but verifier supports {}",
+                self.header.alg,
+                jws_algorithm(verifier.algorithm()).unwrap_or("an unsupported algorithm")
+            ));
+        }
+        verifier.verify(self.signing_input.as_bytes(), &self.signature)
+    }
+}
+
+/// Signs `claims` using `signer` and returns the JWS compact serialization. `kid` is included in
+/// the header if present.
+pub fn sign_jwt<S: CoseSigner + ?Sized>(
+    claims: &ClaimsSetClaims,
+    kid: Option<&str>,
+    signer: &S,
+) -> Result<String, String> {
+    let header = JwsHeader {
+        alg: jws_algorithm(signer.algorithm())?.to_string(),
+        typ: Some("JWT".to_string()),
+        kid: kid.map(|k| k.to_string()),
+        ..Default::default()
+    };
+    let header = match serde_json::to_vec(&header) {
+        Ok(h) => h,
+        Err(e) => return Err(format!("Failed to encode JWS header: {:?}", e)),
+    };
+    let signing_input = format!(
+        "{}.{}",
+        base64::encode_engine(header, &BASE64_URL),
+        base64::encode_engine(encode_json_claims(claims)?, &BASE64_URL)
+    );
+    let signature = signer.sign(signing_input.as_bytes())?;
+    Ok(format!(
+        "{}.{}",
+        signing_input,
+        base64::encode_engine(signature, &BASE64_URL)
+    ))
+}
+
+/// Splits a JWS compact serialization into its parts without verifying the signature.
+pub fn unwrap_jwt(token: &str) -> Result<UnwrappedJwt, String> {
+    let parts: Vec<&str> = token.split('.').collect();
+    if parts.len() != 3 {
+        return Err(format!(
+            "Expected JWS compact serialization with 3 parts but found {}",
+            parts.len()
+        ));
+    }
+    let header = match serde_json::from_slice(&b64_decode(parts[0], "header")?) {
+        Ok(h) => h,
+        Err(e) => return Err(format!("Failed to parse JWS header: {:?}", e)),
+    };
+    Ok(UnwrappedJwt {
+        header,
+        payload: b64_decode(parts[1], "payload")?,
+        signature: b64_decode(parts[2], "signature")?,
+        signing_input: format!("{}.{}", parts[0], parts[1]),
+    })
+}
+
+/// Verifies the signature on a JWT and returns the claims.
+pub fn verify_jwt<V: CoseVerifier + ?Sized>(
+    token: &str,
+    verifier: &V,
+) -> Result<ClaimsSetClaims, String> {
+    let unwrapped = unwrap_jwt(token)?;
+    unwrapped.verify(verifier)?;
+    unwrapped.claims_unverified()
+}
+
+/// Returns the JWTs nested in the submodules of `claims`, including those in submodules of
+/// submodules that are carried as claims sets. Nested tokens are unwrapped but not verified.
+pub fn nested_jwts(claims: &ClaimsSetClaims) -> Result<Vec<UnwrappedJwt>, String> {
+    let mut retval = vec![];
+    for (_, sm) in claims.submodules() {
+        if let Submodule::JsonSelector(js) = sm {
+            if let (JsonSelectorType::Jwt, JsonSelectorValue::JwtMessage(jwt)) =
+                (&js.token_type, &js.nested_token)
+            {
+                retval.push(unwrap_jwt(jwt)?);
+            }
+        }
+    }
+    Ok(retval)
+}
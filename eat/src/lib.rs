@@ -7,7 +7,18 @@
 extern crate alloc;
 
 pub mod arrays;
+pub mod bundle;
 pub mod cbor_specific;
 pub mod choices;
+pub mod custom;
+pub mod cwt;
+pub mod formats;
 pub mod json_specific;
+pub mod jwt;
 pub mod maps;
+pub mod nested;
+pub mod profile;
+pub mod psa;
+pub mod submods;
+pub mod uccs;
+pub mod validate;
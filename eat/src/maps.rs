@@ -6,6 +6,8 @@ use alloc::string::{String, ToString};
 use alloc::{vec, vec::Vec};
 use core::{fmt, marker::PhantomData};
 
+use base64::alphabet::URL_SAFE;
+use base64::engine::fast_portable::{FastPortable, NO_PAD};
 use ciborium::{cbor, value::Value};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde::{
@@ -20,9 +22,172 @@ use common::*;
 use corim::choices::ProfileTypeChoice;
 
 use crate::arrays::*;
-use crate::cbor_specific::SubmoduleCbor;
+use crate::cbor_specific::SubmodsCbor;
 use crate::choices::*;
-use crate::json_specific::Submodule;
+use crate::json_specific::Submods;
+
+pub(crate) const BASE64_URL: FastPortable = FastPortable::from(&URL_SAFE, NO_PAD);
+
+/// Serde adapters, for use with `#[serde(with = "...")]`, that encode byte-valued claims as
+/// base64url text in JSON-encoded claims sets, see [EAT Section 7.3.3].
+///
+/// [EAT Section 7.3.3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#name-json-specific-cddl
+pub mod base64url {
+    use super::BASE64_URL;
+    use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use common::{BytesType, NonceType, UeidType};
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::choices::Oemid;
+
+    fn encode(value: &[u8]) -> String {
+        base64::encode_engine(value, &BASE64_URL)
+    }
+
+    fn decode<E: Error>(value: &str) -> Result<Vec<u8>, E> {
+        base64::decode_engine(value, &BASE64_URL)
+            .map_err(|e| E::custom(format!("Failed to base64url decode claim: {:?}", e)))
+    }
+
+    /// Adapter for claims of type `bytes`
+    pub mod bytes {
+        use super::*;
+
+        /// Serializes the claim value, if any, with bytes as base64url text.
+        pub fn serialize<S: Serializer>(
+            value: &Option<Vec<u8>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            value.as_ref().map(|v| encode(v)).serialize(serializer)
+        }
+
+        /// Deserializes the claim value, if any, with bytes as base64url text.
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Vec<u8>>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|s| decode(&s))
+                .transpose()
+        }
+    }
+
+    /// Adapter for the `ueid` claim
+    pub mod ueid {
+        use super::*;
+
+        /// Serializes the claim value, if any, with bytes as base64url text.
+        pub fn serialize<S: Serializer>(
+            value: &Option<UeidType>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            value
+                .as_ref()
+                .map(|UeidType::Ueid(v)| encode(v))
+                .serialize(serializer)
+        }
+
+        /// Deserializes the claim value, if any, with bytes as base64url text.
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<UeidType>, D::Error> {
+            Ok(bytes::deserialize(deserializer)?.map(UeidType::Ueid))
+        }
+    }
+
+    /// Adapter for the `eat_nonce` claim, which is a single nonce or an array of nonces
+    pub mod nonce {
+        use super::*;
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(untagged)]
+        enum JsonNonce {
+            One(String),
+            More(Vec<String>),
+        }
+
+        /// Serializes the claim value, if any, with bytes as base64url text.
+        pub fn serialize<S: Serializer>(
+            value: &Option<NonceType>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let encode_one = |BytesType::Bytes(b): &BytesType| encode(b);
+            value
+                .as_ref()
+                .map(|n| match n {
+                    NonceType::One(b) => JsonNonce::One(encode_one(b)),
+                    NonceType::More(v) => JsonNonce::More(v.iter().map(encode_one).collect()),
+                })
+                .serialize(serializer)
+        }
+
+        /// Deserializes the claim value, if any, with bytes as base64url text.
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<NonceType>, D::Error> {
+            let decode_one = |s: &String| decode(s).map(BytesType::Bytes);
+            Option::<JsonNonce>::deserialize(deserializer)?
+                .map(|n| match n {
+                    JsonNonce::One(s) => decode_one(&s).map(NonceType::One),
+                    JsonNonce::More(v) => v
+                        .iter()
+                        .map(decode_one)
+                        .collect::<Result<Vec<BytesType>, D::Error>>()
+                        .map(NonceType::More),
+                })
+                .transpose()
+        }
+    }
+
+    /// Adapter for the `oemid` claim, which is a PEN or a byte string
+    pub mod oemid {
+        use super::*;
+
+        #[derive(Serialize, Deserialize)]
+        #[serde(untagged)]
+        enum JsonOemid {
+            Pen(i64),
+            Bytes(String),
+        }
+
+        /// Serializes the claim value, if any, with bytes as base64url text.
+        pub fn serialize<S: Serializer>(
+            value: &Option<Oemid>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            value
+                .as_ref()
+                .map(|o| match o {
+                    Oemid::Pen(p) => JsonOemid::Pen(*p),
+                    Oemid::Ieee(b) | Oemid::Random(b) => JsonOemid::Bytes(encode(b)),
+                })
+                .serialize(serializer)
+        }
+
+        /// Deserializes the claim value, if any, with bytes as base64url text.
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Oemid>, D::Error> {
+            Option::<JsonOemid>::deserialize(deserializer)?
+                .map(|o| match o {
+                    JsonOemid::Pen(p) => Ok(Oemid::Pen(p)),
+                    JsonOemid::Bytes(s) => {
+                        let b = decode::<D::Error>(&s)?;
+                        match b.len() {
+                            3 => Ok(Oemid::Ieee(b)),
+                            16 => Ok(Oemid::Random(b)),
+                            _ => Err(D::Error::custom(format!(
+                                "Invalid oemid length: {}",
+                                b.len()
+                            ))),
+                        }
+                    }
+                })
+                .transpose()
+        }
+    }
+}
 
 /// JSON encoding/decoding of `Claims-Set-Claims`, see [EAT Section 4.2].
 ///
@@ -125,7 +290,11 @@ use crate::json_specific::Submodule;
 ///        * Claim-Label .feature "extended-claims-label" => any
 ///    }
 /// ```
+/// When encoded as JSON, claims use the JWT claim names defined in [EAT Section 10.1] (e.g.,
+/// `eat_nonce`, `ueid` and `oemid`) rather than the field names.
+///
 /// [EAT Section 4.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2
+/// [EAT Section 10.1]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-10.1
 #[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct ClaimsSetClaims {
@@ -136,40 +305,53 @@ pub struct ClaimsSetClaims {
     #[cbor(tag = "3", value = "Text")]
     pub aud: Option<String>,
     #[cbor(tag = "4", cbor = "true")]
-    pub exp: Option<Time>,
+    pub exp: Option<NumericDate>,
     #[cbor(tag = "5", cbor = "true")]
-    pub nbf: Option<Time>,
+    pub nbf: Option<NumericDate>,
     #[cbor(tag = "6", cbor = "true")]
-    pub iat: Option<Time>,
+    pub iat: Option<NumericDate>,
     #[cbor(tag = "7", value = "Bytes")]
+    #[serde(with = "base64url::bytes", default)]
     pub cti: Option<Vec<u8>>,
     #[cbor(tag = "10")]
+    #[serde(rename = "eat_nonce", with = "base64url::nonce", default)]
     pub nonce: Option<NonceType>,
     #[cbor(tag = "276", value = "Integer")]
+    #[serde(rename = "bootcount")]
     pub boot_count: Option<u64>,
     #[cbor(tag = "268", value = "Bytes")]
+    #[serde(rename = "bootseed", with = "base64url::bytes", default)]
     pub boot_seed: Option<Vec<u8>>,
     #[cbor(tag = "263")]
+    #[serde(rename = "dbgstat")]
     pub debug_status: Option<DebugStatusType>,
     #[cbor(tag = "270", value = "Array", cbor = "true")]
     pub dloas: Option<Vec<DloaType>>,
     #[cbor(tag = "259", value = "Bytes")]
+    #[serde(rename = "hwmodel", with = "base64url::bytes", default)]
     pub hardware_model: Option<Vec<u8>>,
     #[cbor(tag = "260", cbor = "true")]
+    #[serde(rename = "hwversion")]
     pub hardware_version: Option<HardwareVersionType>,
     #[cbor(tag = "269")]
+    #[serde(rename = "intuse")]
     pub intended_use: Option<IntendedUseType>,
     #[cbor(tag = "264", cbor = "true")]
     pub location: Option<LocationType>,
     #[cbor(tag = "265")]
+    #[serde(rename = "eat_profile")]
     pub profile: Option<ProfileTypeChoice>,
     #[cbor(tag = "262", value = "Bool")]
+    #[serde(rename = "oemboot")]
     pub secure_boot: Option<bool>,
     #[cbor(tag = "271", value = "Text")]
+    #[serde(rename = "swname")]
     pub sw_name: Option<String>,
     #[cbor(tag = "272", cbor = "true")]
+    #[serde(rename = "swversion")]
     pub sw_version: Option<SwVersionType>,
     #[cbor(tag = "256")]
+    #[serde(with = "base64url::ueid", default)]
     pub ueid: Option<UeidType>,
     #[cbor(tag = "267", value = "Integer")]
     pub uptime: Option<u64>,
@@ -178,13 +360,15 @@ pub struct ClaimsSetClaims {
     #[cbor(tag = "274", cbor = "true")]
     pub measurements: Option<MeasurementsType>,
     #[cbor(tag = "275", cbor = "true")]
+    #[serde(rename = "measres")]
     pub measurement_results: Option<MeasurementResultsGroupArray>,
     #[cbor(tag = "258")]
+    #[serde(with = "base64url::oemid", default)]
     pub oemid: Option<Oemid>,
     #[cbor(tag = "257", cbor = "true")]
     pub sueids: Option<TupleMap>,
     #[cbor(tag = "266", cbor = "true")]
-    pub submods: Option<Submodule>,
+    pub submods: Option<Submods>,
     #[cbor(value = "Array", cbor = "true")]
     pub other: Option<Vec<Tuple>>,
 }
@@ -217,25 +401,25 @@ pub struct ClaimsSetClaims {
 /// age               = 9
 /// ```
 /// [EAT Section 4.2.10]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.10
-#[derive(Clone, Debug, Eq, PartialEq, StructToMap, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, StructToMap, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct LocationType {
-    #[cbor(tag = "1", value = "Integer")]
-    pub latitude: u64,
-    #[cbor(tag = "2", value = "Integer")]
-    pub longitude: u64,
-    #[cbor(tag = "3", value = "Integer")]
-    pub altitude: Option<u64>,
-    #[cbor(tag = "4", value = "Integer")]
-    pub accuracy: Option<u64>,
-    #[cbor(tag = "5", value = "Integer")]
-    pub altitude_accuracy: Option<u64>,
-    #[cbor(tag = "6", value = "Integer")]
-    pub heading: Option<u64>,
-    #[cbor(tag = "7", value = "Integer")]
-    pub speed: Option<u64>,
+    #[cbor(tag = "1", value = "Number")]
+    pub latitude: Number,
+    #[cbor(tag = "2", value = "Number")]
+    pub longitude: Number,
+    #[cbor(tag = "3", value = "Number")]
+    pub altitude: Option<Number>,
+    #[cbor(tag = "4", value = "Number")]
+    pub accuracy: Option<Number>,
+    #[cbor(tag = "5", value = "Number")]
+    pub altitude_accuracy: Option<Number>,
+    #[cbor(tag = "6", value = "Number")]
+    pub heading: Option<Number>,
+    #[cbor(tag = "7", value = "Number")]
+    pub speed: Option<Number>,
     #[cbor(tag = "8", cbor = "true")]
-    pub timestamp: Option<Time>,
+    pub timestamp: Option<NumericDate>,
     #[cbor(tag = "9", value = "Integer")]
     pub age: Option<u64>,
 }
@@ -250,7 +434,7 @@ pub struct LocationType {
 /// }
 /// ```
 /// [EAT Section 4.2.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.2
-pub struct SueidsType(TupleMap);
+pub struct SueidsType(pub TupleMap);
 
 /// CBOR encoding/decoding of `sueids-type`, see [EAT Section 4.2.2].
 ///
@@ -262,4 +446,4 @@ pub struct SueidsType(TupleMap);
 /// }
 /// ```
 /// [EAT Section 4.2.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.2
-pub struct SueidsTypeCbor(TupleMapCbor);
+pub struct SueidsTypeCbor(pub TupleMapCbor);
@@ -0,0 +1,414 @@
+//! Recursive verification of nested tokens
+//!
+//! Submodules may carry complete tokens, see [EAT Section 4.2.18]. [NestedTokenVerifier] verifies
+//! a token, then walks its submodules and verifies each nested CWT, JWT or detached EAT bundle
+//! using keys obtained from a [KeyResolver]. The result is a tree of [TokenNode] values that
+//! mirrors the submodule structure and records the status of each node.
+//!
+//! Unprotected CWT claims sets (UCCS, see [crate::uccs]) carry no signature. They are reported as
+//! failed unless the verifier is configured with [NestedTokenVerifier::allow_uccs], in which case
+//! they are reported as [NodeStatus::Unprotected]. Such trees are never trusted, see
+//! [TokenNode::is_trusted] and [TokenNode::is_accepted].
+//!
+//! Submodules of a node are only examined if the node verified successfully. Nodes below the
+//! configured depth limit are reported as [NodeStatus::DepthExceeded] without being decoded.
+//!
+//! [EAT Section 4.2.18]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.18
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::{de::from_reader, value::Value};
+
+use crate::arrays::DetachedEatBundleCbor;
+use crate::bundle::{check_detached_claims_sets, BUNDLE_TAG};
+use crate::cbor_specific::{SelectorCbor, SubmoduleCbor};
+use crate::cwt::{unwrap_cwt, CoseMessage};
+use crate::json_specific::{JsonSelector, JsonSelectorType, JsonSelectorValue, Submodule};
+use crate::jwt::unwrap_jwt;
+use crate::maps::{ClaimsSetClaims, ClaimsSetClaimsCbor};
+use crate::uccs::UCCS_TAG;
+use common::TextOrBinary;
+use cose::maps::HeaderMapCbor;
+use cose::sign::CoseVerifier;
+
+/// Default value for the maximum nesting depth enforced by [NestedTokenVerifier]
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+
+/// Provides keys for verifying nested tokens.
+pub trait KeyResolver {
+    /// Returns the key to use to verify the token found at `path`, which is the `/`-separated list
+    /// of submodule names leading to the token (empty for the outermost token). `kid` is the key
+    /// identifier from the token header, if present.
+    fn resolve(&self, path: &str, kid: Option<&[u8]>) -> Option<&dyn CoseVerifier>;
+}
+
+/// Claims set obtained from a verified node
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub enum NodeClaims {
+    Cbor(ClaimsSetClaimsCbor),
+    Json(ClaimsSetClaims),
+}
+
+/// Status of a [TokenNode]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NodeStatus {
+    /// The token was verified or, for a detached claims set, matched a digest in a verified token
+    Verified,
+    /// The claims set was carried directly in the enclosing claims set and is protected by it
+    Inline,
+    /// The token is a UCCS, which was accepted because the verifier allows unprotected tokens
+    Unprotected,
+    /// The submodule carries a digest and no corresponding detached claims set is available
+    Digest,
+    /// The key resolver did not provide a key
+    NoKey,
+    /// The token could not be decoded or verified
+    Failed(String),
+    /// The node is nested more deeply than permitted
+    DepthExceeded,
+}
+
+/// Result of verifying a token or submodule
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenNode {
+    /// Submodule name, empty for the outermost token
+    pub name: String,
+    /// Verification status
+    pub status: NodeStatus,
+    /// Claims set, present when the status is [NodeStatus::Verified], [NodeStatus::Inline] or
+    /// [NodeStatus::Unprotected]
+    pub claims: Option<NodeClaims>,
+    /// Nodes for the submodules of the claims set
+    pub children: Vec<TokenNode>,
+}
+
+impl TokenNode {
+    fn new(name: &str, status: NodeStatus) -> Self {
+        TokenNode {
+            name: name.to_string(),
+            status,
+            claims: None,
+            children: vec![],
+        }
+    }
+
+    /// Returns true if this node and every node below it is verified, inline or a digest.
+    pub fn is_trusted(&self) -> bool {
+        matches!(
+            self.status,
+            NodeStatus::Verified | NodeStatus::Inline | NodeStatus::Digest
+        ) && self.children.iter().all(|c| c.is_trusted())
+    }
+
+    /// Returns true if this node and every node below it is verified, inline, unprotected or a
+    /// digest. Unlike [is_trusted](TokenNode::is_trusted), this accepts UCCS, which only occur if
+    /// the verifier was configured to accept them.
+    pub fn is_accepted(&self) -> bool {
+        matches!(
+            self.status,
+            NodeStatus::Verified
+                | NodeStatus::Inline
+                | NodeStatus::Unprotected
+                | NodeStatus::Digest
+        ) && self.children.iter().all(|c| c.is_accepted())
+    }
+
+    /// Returns the node at `path`, i.e., a `/`-separated list of submodule names relative to this
+    /// node.
+    pub fn find(&self, path: &str) -> Option<&TokenNode> {
+        let mut node = self;
+        for name in path.split('/').filter(|n| !n.is_empty()) {
+            node = node.children.iter().find(|c| c.name == name)?;
+        }
+        Some(node)
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    match path.is_empty() {
+        true => name.to_string(),
+        false => format!("{}/{}", path, name),
+    }
+}
+
+fn key_id(protected: &HeaderMapCbor, unprotected: &HeaderMapCbor) -> Option<Vec<u8>> {
+    match protected.key_id.as_ref().or(unprotected.key_id.as_ref()) {
+        Some(TextOrBinary::Binary(b)) => Some(b.clone()),
+        Some(TextOrBinary::Text(t)) => Some(t.as_bytes().to_vec()),
+        None => None,
+    }
+}
+
+/// Verifies a token and the tokens nested within its submodules.
+pub struct NestedTokenVerifier<'a, R: KeyResolver + ?Sized> {
+    resolver: &'a R,
+    max_depth: usize,
+    allow_uccs: bool,
+}
+
+impl<'a, R: KeyResolver + ?Sized> NestedTokenVerifier<'a, R> {
+    /// Creates a verifier that obtains keys from `resolver` and uses [DEFAULT_MAX_DEPTH].
+    pub fn new(resolver: &'a R) -> Self {
+        NestedTokenVerifier {
+            resolver,
+            max_depth: DEFAULT_MAX_DEPTH,
+            allow_uccs: false,
+        }
+    }
+
+    /// Sets the maximum nesting depth. The outermost token is at depth 0, and each submodule is one
+    /// level deeper than the claims set that contains it.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets whether unprotected tokens (UCCS) are accepted. UCCS are rejected by default.
+    pub fn allow_uccs(mut self, allow_uccs: bool) -> Self {
+        self.allow_uccs = allow_uccs;
+        self
+    }
+
+    /// Verifies a CBOR-encoded token, i.e., a CWT carried in a `COSE_Sign1`, a detached EAT
+    /// bundle or, if permitted, a UCCS, and its nested tokens.
+    pub fn verify_cbor(&self, token: &[u8]) -> TokenNode {
+        self.cbor_node("", "", token, 0)
+    }
+
+    /// Verifies a JWT and its nested tokens.
+    pub fn verify_json(&self, token: &str) -> TokenNode {
+        self.jwt_node("", "", token, 0)
+    }
+
+    fn cbor_node(&self, name: &str, path: &str, token: &[u8], depth: usize) -> TokenNode {
+        let value: Value = match from_reader(token) {
+            Ok(v) => v,
+            Err(e) => {
+                let msg = format!("Failed to parse nested token: {:?}", e);
+                return TokenNode::new(name, NodeStatus::Failed(msg));
+            }
+        };
+        if let Value::Tag(BUNDLE_TAG, inner) = value {
+            return match DetachedEatBundleCbor::try_from(*inner) {
+                Ok(deb) => self.bundle_node(name, path, &deb, depth),
+                Err(e) => TokenNode::new(name, NodeStatus::Failed(e)),
+            };
+        }
+        if let Value::Tag(UCCS_TAG, inner) = value {
+            if !self.allow_uccs {
+                let msg = "Unprotected tokens (UCCS) are not accepted".to_string();
+                return TokenNode::new(name, NodeStatus::Failed(msg));
+            }
+            return match ClaimsSetClaimsCbor::try_from(*inner) {
+                Ok(claims) => {
+                    self.claims_node(name, path, NodeStatus::Unprotected, claims, depth, None)
+                }
+                Err(e) => TokenNode::new(name, NodeStatus::Failed(e)),
+            };
+        }
+        match self.verify_cwt(path, token) {
+            Ok(claims) => self.claims_node(name, path, NodeStatus::Verified, claims, depth, None),
+            Err(status) => TokenNode::new(name, status),
+        }
+    }
+
+    fn verify_cwt(&self, path: &str, token: &[u8]) -> Result<ClaimsSetClaimsCbor, NodeStatus> {
+        let unwrapped = unwrap_cwt(token).map_err(NodeStatus::Failed)?;
+        let sign1 = match &unwrapped.message {
+            CoseMessage::Sign1(m) | CoseMessage::Untagged(m) => m,
+            CoseMessage::Mac0(_) => {
+                let msg = "Nested COSE_Mac0 tokens are not supported".to_string();
+                return Err(NodeStatus::Failed(msg));
+            }
+        };
+        let protected = sign1.protected_header().map_err(NodeStatus::Failed)?;
+        let kid = key_id(&protected, &sign1.unprotected);
+        let verifier = match self.resolver.resolve(path, kid.as_deref()) {
+            Some(v) => v,
+            None => return Err(NodeStatus::NoKey),
+        };
+        sign1
+            .verify(&[], None, verifier)
+            .map_err(NodeStatus::Failed)?;
+        unwrapped.claims_unverified().map_err(NodeStatus::Failed)
+    }
+
+    fn bundle_node(
+        &self,
+        name: &str,
+        path: &str,
+        deb: &DetachedEatBundleCbor,
+        depth: usize,
+    ) -> TokenNode {
+        let main = match &deb.main_token.0 {
+            SelectorCbor::CborTokenInsideCborToken(t) => self.verify_cwt(path, t),
+            _ => {
+                let msg = "Main token of a nested bundle is not a CWT".to_string();
+                Err(NodeStatus::Failed(msg))
+            }
+        };
+        let main = match main {
+            Ok(m) => m,
+            Err(status) => return TokenNode::new(name, status),
+        };
+        let report = match check_detached_claims_sets(&main, &deb.detached_claims_sets) {
+            Ok(r) => r,
+            Err(e) => return TokenNode::new(name, NodeStatus::Failed(e)),
+        };
+        if !report.is_valid() {
+            let msg = format!(
+                "Detached claims sets do not match digests: mismatched {:?}, missing {:?}, extra {:?}",
+                report.mismatched, report.missing, report.extra
+            );
+            return TokenNode::new(name, NodeStatus::Failed(msg));
+        }
+        self.claims_node(
+            name,
+            path,
+            NodeStatus::Verified,
+            main,
+            depth,
+            Some(&report.verified),
+        )
+    }
+
+    fn claims_node(
+        &self,
+        name: &str,
+        path: &str,
+        status: NodeStatus,
+        claims: ClaimsSetClaimsCbor,
+        depth: usize,
+        detached: Option<&[(String, ClaimsSetClaimsCbor)]>,
+    ) -> TokenNode {
+        let mut node = TokenNode::new(name, status);
+        if let Some(submods) = &claims.submods {
+            for (sm_name, sm) in &submods.0 {
+                let sm_path = join(path, sm_name);
+                let child = if depth + 1 > self.max_depth {
+                    TokenNode::new(sm_name, NodeStatus::DepthExceeded)
+                } else {
+                    match sm {
+                        SubmoduleCbor::ClaimsSet(cs) => self.claims_node(
+                            sm_name,
+                            &sm_path,
+                            NodeStatus::Inline,
+                            cs.as_ref().clone(),
+                            depth + 1,
+                            None,
+                        ),
+                        SubmoduleCbor::SelectorCbor(SelectorCbor::CborTokenInsideCborToken(t)) => {
+                            self.cbor_node(sm_name, &sm_path, t, depth + 1)
+                        }
+                        SubmoduleCbor::SelectorCbor(SelectorCbor::JsonTokenInsideCborToken(t)) => {
+                            self.jwt_node(sm_name, &sm_path, t, depth + 1)
+                        }
+                        SubmoduleCbor::SelectorCbor(SelectorCbor::DetachedSubmoduleDigest(_)) => {
+                            match detached.and_then(|d| d.iter().find(|(n, _)| n == sm_name)) {
+                                Some((_, cs)) => self.claims_node(
+                                    sm_name,
+                                    &sm_path,
+                                    NodeStatus::Verified,
+                                    cs.clone(),
+                                    depth + 1,
+                                    None,
+                                ),
+                                None => TokenNode::new(sm_name, NodeStatus::Digest),
+                            }
+                        }
+                    }
+                };
+                node.children.push(child);
+            }
+        }
+        node.claims = Some(NodeClaims::Cbor(claims));
+        node
+    }
+
+    fn jwt_node(&self, name: &str, path: &str, token: &str, depth: usize) -> TokenNode {
+        let unwrapped = match unwrap_jwt(token) {
+            Ok(u) => u,
+            Err(e) => return TokenNode::new(name, NodeStatus::Failed(e)),
+        };
+        let kid = unwrapped.header.kid.as_ref().map(|k| k.as_bytes());
+        let verifier = match self.resolver.resolve(path, kid) {
+            Some(v) => v,
+            None => return TokenNode::new(name, NodeStatus::NoKey),
+        };
+        if let Err(e) = unwrapped.verify(verifier) {
+            return TokenNode::new(name, NodeStatus::Failed(e));
+        }
+        match unwrapped.claims_unverified() {
+            Ok(claims) => self.json_claims_node(name, path, NodeStatus::Verified, claims, depth),
+            Err(e) => TokenNode::new(name, NodeStatus::Failed(e)),
+        }
+    }
+
+    fn json_claims_node(
+        &self,
+        name: &str,
+        path: &str,
+        status: NodeStatus,
+        claims: ClaimsSetClaims,
+        depth: usize,
+    ) -> TokenNode {
+        let mut node = TokenNode::new(name, status);
+        if let Some(submods) = &claims.submods {
+            for (sm_name, sm) in &submods.0 {
+                let sm_path = join(path, sm_name);
+                let child = if depth + 1 > self.max_depth {
+                    TokenNode::new(sm_name, NodeStatus::DepthExceeded)
+                } else {
+                    match sm {
+                        Submodule::ClaimsSet(cs) => self.json_claims_node(
+                            sm_name,
+                            &sm_path,
+                            NodeStatus::Inline,
+                            cs.as_ref().clone(),
+                            depth + 1,
+                        ),
+                        Submodule::JsonSelector(js) => {
+                            self.json_selector_node(sm_name, &sm_path, js, depth + 1)
+                        }
+                    }
+                };
+                node.children.push(child);
+            }
+        }
+        node.claims = Some(NodeClaims::Json(claims));
+        node
+    }
+
+    fn json_selector_node(
+        &self,
+        name: &str,
+        path: &str,
+        js: &JsonSelector,
+        depth: usize,
+    ) -> TokenNode {
+        match (&js.token_type, &js.nested_token) {
+            (JsonSelectorType::Jwt, JsonSelectorValue::JwtMessage(t)) => {
+                self.jwt_node(name, path, t, depth)
+            }
+            (JsonSelectorType::Cbor, JsonSelectorValue::CborTokenInsideJsonToken(t)) => {
+                match base64::decode(t) {
+                    Ok(b) => self.cbor_node(name, path, &b, depth),
+                    Err(e) => {
+                        let msg = format!("Failed to decode nested CBOR token: {:?}", e);
+                        TokenNode::new(name, NodeStatus::Failed(msg))
+                    }
+                }
+            }
+            (JsonSelectorType::Digest, JsonSelectorValue::DetachedSubmoduleDigest(_)) => {
+                TokenNode::new(name, NodeStatus::Digest)
+            }
+            _ => {
+                let msg = format!("Unsupported nested token type: {:?}", js.token_type);
+                TokenNode::new(name, NodeStatus::Failed(msg))
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Selection of EAT profiles
+//!
+//! The `profile` claim identifies the profile a token conforms to using a URI or an OID, see
+//! [EAT Section 4.3.2]. [ProfileRegistry] maps these identifiers to [ClaimsProfile]
+//! implementations so that a verifier can validate a token against the profile it claims.
+//!
+//! [EAT Section 4.3.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.3.2
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::maps::ClaimsSetClaimsCbor;
+use crate::psa::PsaProfile;
+use crate::validate::{ClaimsProfile, ClaimsValidator, ValidationReport};
+use common::OidType;
+use corim::choices::ProfileTypeChoice;
+
+/// Returns true if `a` and `b` identify the same profile. OIDs match regardless of whether they
+/// were tagged.
+pub fn same_profile(a: &ProfileTypeChoice, b: &ProfileTypeChoice) -> bool {
+    match (a, b) {
+        (ProfileTypeChoice::Uri(a), ProfileTypeChoice::Uri(b)) => a == b,
+        (
+            ProfileTypeChoice::Oid(OidType::Oid(a)) | ProfileTypeChoice::Oid2(OidType::Oid(a)),
+            ProfileTypeChoice::Oid(OidType::Oid(b)) | ProfileTypeChoice::Oid2(OidType::Oid(b)),
+        ) => a == b,
+        _ => false,
+    }
+}
+
+/// Collection of profiles that can be looked up by identifier.
+#[derive(Default)]
+pub struct ProfileRegistry {
+    profiles: Vec<Box<dyn ClaimsProfile>>,
+}
+
+impl ProfileRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry containing the profiles provided by this crate, i.e., [PsaProfile].
+    pub fn with_builtin_profiles() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(PsaProfile));
+        registry
+    }
+
+    /// Adds a profile. Profiles registered later take precedence over those registered earlier
+    /// that share an identifier.
+    pub fn register(&mut self, profile: Box<dyn ClaimsProfile>) {
+        self.profiles.insert(0, profile);
+    }
+
+    /// Returns the names of the registered profiles.
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.iter().map(|p| p.name()).collect()
+    }
+
+    /// Returns the profile identified by `id`, if registered.
+    pub fn lookup(&self, id: &ProfileTypeChoice) -> Option<&dyn ClaimsProfile> {
+        self.profiles
+            .iter()
+            .find(|p| p.identifiers().iter().any(|i| same_profile(i, id)))
+            .map(|p| p.as_ref())
+    }
+
+    /// Returns the profile identified by the `profile` claim in `claims`. An error is returned if
+    /// the claim is absent or the profile is not registered.
+    pub fn profile_for(&self, claims: &ClaimsSetClaimsCbor) -> Result<&dyn ClaimsProfile, String> {
+        match &claims.profile {
+            Some(id) => match self.lookup(id) {
+                Some(p) => Ok(p),
+                None => Err(format!("Unrecognized profile: {:?}", id)),
+            },
+            None => Err("Claims set does not contain a profile claim".to_string()),
+        }
+    }
+
+    /// Validates `claims` as of `now` using `validator` and the profile identified by the
+    /// `profile` claim, see [ClaimsValidator::validate_with_profile].
+    pub fn validate(
+        &self,
+        validator: &ClaimsValidator,
+        claims: &ClaimsSetClaimsCbor,
+        now: i64,
+    ) -> Result<ValidationReport, String> {
+        validator.validate_with_profile(claims, now, self.profile_for(claims)?)
+    }
+}
+
+impl core::fmt::Debug for ProfileRegistry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ProfileRegistry")
+            .field("profiles", &self.names())
+            .finish()
+    }
+}
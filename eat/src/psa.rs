@@ -0,0 +1,321 @@
+//! Arm Platform Security Architecture (PSA) attestation token profile
+//!
+//! The PSA attestation token is an EAT profile defined in [RFC 9783]. In addition to the `nonce`,
+//! `ueid` and `profile` claims, it defines the claims below, which are carried in the `other`
+//! field of [ClaimsSetClaimsCbor]. [PsaProfile] checks a claims set against the profile and
+//! [PsaClaims] provides typed access to the PSA claims.
+//!
+//! ```text
+//! psa-client-id-key                 = 2394
+//! psa-security-lifecycle-key        = 2395
+//! psa-implementation-id-key         = 2396
+//! psa-boot-seed-key                 = 2397
+//! psa-certification-reference-key   = 2398
+//! psa-software-components-key       = 2399
+//! psa-verification-service-indicator-key = 2400
+//!
+//! psa-software-component = {
+//!   ? &(measurement-type: 1) => text
+//!     &(measurement-value: 2) => psa-hash-type
+//!   ? &(version: 4) => text
+//!     &(signer-id: 5) => psa-hash-type
+//!   ? &(measurement-desc: 6) => text
+//! }
+//! ```
+//!
+//! [RFC 9783]: https://datatracker.ietf.org/doc/html/rfc9783
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::value::Value;
+
+use crate::maps::ClaimsSetClaimsCbor;
+use crate::validate::{
+    claim_values, ClaimKind, ClaimSpec, ClaimsProfile, NONCE_LABEL, PROFILE_LABEL, UEID_LABEL,
+};
+use common::TextOrInt;
+use corim::choices::ProfileTypeChoice;
+
+/// Value of the `profile` claim identifying the PSA attestation token profile
+pub const PSA_PROFILE_URI: &str = "tag:psacertified.org,2023:psa#tfm";
+
+/// Label of the `psa-client-id` claim
+pub const PSA_CLIENT_ID_LABEL: i64 = 2394;
+/// Label of the `psa-security-lifecycle` claim
+pub const PSA_SECURITY_LIFECYCLE_LABEL: i64 = 2395;
+/// Label of the `psa-implementation-id` claim
+pub const PSA_IMPLEMENTATION_ID_LABEL: i64 = 2396;
+/// Label of the `psa-boot-seed` claim
+pub const PSA_BOOT_SEED_LABEL: i64 = 2397;
+/// Label of the `psa-certification-reference` claim
+pub const PSA_CERTIFICATION_REFERENCE_LABEL: i64 = 2398;
+/// Label of the `psa-software-components` claim
+pub const PSA_SOFTWARE_COMPONENTS_LABEL: i64 = 2399;
+/// Label of the `psa-verification-service-indicator` claim
+pub const PSA_VERIFICATION_SERVICE_LABEL: i64 = 2400;
+
+const HASH_SIZES: [usize; 3] = [32, 48, 64];
+
+/// Security lifecycle state of a PSA device, see [RFC 9783 Section 4.3.1].
+///
+/// Each state occupies a range of 256 values. The low byte is implementation defined.
+///
+/// [RFC 9783 Section 4.3.1]: https://datatracker.ietf.org/doc/html/rfc9783#section-4.3.1
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum PsaSecurityLifecycle {
+    Unknown,
+    AssemblyAndTest,
+    PsaRotProvisioning,
+    Secured,
+    NonPsaRotDebug,
+    RecoverablePsaRotDebug,
+    Decommissioned,
+}
+
+impl TryFrom<u64> for PsaSecurityLifecycle {
+    type Error = String;
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match value >> 8 {
+            0x00 => Ok(Self::Unknown),
+            0x10 => Ok(Self::AssemblyAndTest),
+            0x20 => Ok(Self::PsaRotProvisioning),
+            0x30 => Ok(Self::Secured),
+            0x40 => Ok(Self::NonPsaRotDebug),
+            0x50 => Ok(Self::RecoverablePsaRotDebug),
+            0x60 => Ok(Self::Decommissioned),
+            _ => Err(format!(
+                "Unrecognized PSA security lifecycle: {:#06x}",
+                value
+            )),
+        }
+    }
+}
+
+/// Measured software component, see [RFC 9783 Section 4.4.1].
+///
+/// [RFC 9783 Section 4.4.1]: https://datatracker.ietf.org/doc/html/rfc9783#section-4.4.1
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PsaSoftwareComponent {
+    /// Role of the component, e.g., "BL" or "PRoT"
+    pub measurement_type: Option<String>,
+    /// Hash of the component
+    pub measurement_value: Vec<u8>,
+    /// Version of the component
+    pub version: Option<String>,
+    /// Hash of the public key used to sign the component
+    pub signer_id: Vec<u8>,
+    /// Name of the algorithm used to compute the measurement value
+    pub measurement_desc: Option<String>,
+}
+
+fn int_key(value: &Value) -> Option<i128> {
+    match value {
+        Value::Integer(i) => Some(i128::from(*i)),
+        _ => None,
+    }
+}
+
+fn hash_field(value: Option<&Value>, name: &str) -> Result<Vec<u8>, String> {
+    match value {
+        Some(Value::Bytes(b)) if HASH_SIZES.contains(&b.len()) => Ok(b.clone()),
+        Some(Value::Bytes(b)) => Err(format!(
+            "{} must be 32, 48 or 64 bytes but is {}",
+            name,
+            b.len()
+        )),
+        Some(_) => Err(format!("{} must be a byte string", name)),
+        None => Err(format!("Software component is missing {}", name)),
+    }
+}
+
+fn text_field(value: Option<&Value>, name: &str) -> Result<Option<String>, String> {
+    match value {
+        Some(Value::Text(t)) => Ok(Some(t.clone())),
+        Some(_) => Err(format!("{} must be a text string", name)),
+        None => Ok(None),
+    }
+}
+
+impl TryFrom<&Value> for PsaSoftwareComponent {
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let m = match value {
+            Value::Map(m) => m,
+            _ => return Err("Software component must be a map".to_string()),
+        };
+        let get = |key: i128| {
+            m.iter()
+                .find(|(k, _)| int_key(k) == Some(key))
+                .map(|(_, v)| v)
+        };
+        Ok(PsaSoftwareComponent {
+            measurement_type: text_field(get(1), "measurement-type")?,
+            measurement_value: hash_field(get(2), "measurement-value")?,
+            version: text_field(get(4), "version")?,
+            signer_id: hash_field(get(5), "signer-id")?,
+            measurement_desc: text_field(get(6), "measurement-desc")?,
+        })
+    }
+}
+
+/// Typed view of the claims defined by the PSA attestation token profile
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PsaClaims {
+    /// Value of the `nonce` claim
+    pub nonce: Vec<u8>,
+    /// Value of the `ueid` claim, i.e., the instance ID
+    pub instance_id: Vec<u8>,
+    /// Identifier of the partition that requested the token
+    pub client_id: i64,
+    /// Raw value of the `psa-security-lifecycle` claim
+    pub security_lifecycle: u64,
+    /// Identifier of the implementation of the immutable PSA RoT
+    pub implementation_id: Vec<u8>,
+    /// Value that changes on each boot
+    pub boot_seed: Option<Vec<u8>>,
+    /// Certification reference, e.g., a PSA Certified EAN-13
+    pub certification_reference: Option<String>,
+    /// Measured software components
+    pub software_components: Vec<PsaSoftwareComponent>,
+    /// Hint used to locate the verification service
+    pub verification_service: Option<String>,
+}
+
+impl PsaClaims {
+    /// Returns the security lifecycle state.
+    pub fn lifecycle(&self) -> Result<PsaSecurityLifecycle, String> {
+        PsaSecurityLifecycle::try_from(self.security_lifecycle)
+    }
+}
+
+impl TryFrom<&ClaimsSetClaimsCbor> for PsaClaims {
+    type Error = String;
+    fn try_from(claims: &ClaimsSetClaimsCbor) -> Result<Self, Self::Error> {
+        let values = claim_values(claims)?;
+        let get = |label: i64| {
+            values
+                .iter()
+                .find(|(k, _)| *k == TextOrInt::Int(label))
+                .map(|(_, v)| v)
+        };
+        let bytes = |label: i64, name: &str| match get(label) {
+            Some(Value::Bytes(b)) => Ok(Some(b.clone())),
+            Some(_) => Err(format!("{} must be a byte string", name)),
+            None => Ok(None),
+        };
+        let required = |v: Option<Vec<u8>>, name: &str| match v {
+            Some(v) => Ok(v),
+            None => Err(format!("Missing {} claim", name)),
+        };
+        let client_id = match get(PSA_CLIENT_ID_LABEL).and_then(int_key) {
+            Some(i) => i64::try_from(i).map_err(|_| "psa-client-id is out of range".to_string())?,
+            None => return Err("Missing or invalid psa-client-id claim".to_string()),
+        };
+        let security_lifecycle = match get(PSA_SECURITY_LIFECYCLE_LABEL).and_then(int_key) {
+            Some(i) => u64::try_from(i)
+                .map_err(|_| "psa-security-lifecycle is out of range".to_string())?,
+            None => return Err("Missing or invalid psa-security-lifecycle claim".to_string()),
+        };
+        let software_components = match get(PSA_SOFTWARE_COMPONENTS_LABEL) {
+            Some(Value::Array(a)) => a
+                .iter()
+                .map(PsaSoftwareComponent::try_from)
+                .collect::<Result<Vec<_>, String>>()?,
+            _ => return Err("Missing or invalid psa-software-components claim".to_string()),
+        };
+        Ok(PsaClaims {
+            nonce: required(bytes(NONCE_LABEL, "nonce")?, "nonce")?,
+            instance_id: required(bytes(UEID_LABEL, "ueid")?, "ueid")?,
+            client_id,
+            security_lifecycle,
+            implementation_id: required(
+                bytes(PSA_IMPLEMENTATION_ID_LABEL, "psa-implementation-id")?,
+                "psa-implementation-id",
+            )?,
+            boot_seed: bytes(PSA_BOOT_SEED_LABEL, "psa-boot-seed")?,
+            certification_reference: text_field(
+                get(PSA_CERTIFICATION_REFERENCE_LABEL),
+                "psa-certification-reference",
+            )?,
+            software_components,
+            verification_service: text_field(
+                get(PSA_VERIFICATION_SERVICE_LABEL),
+                "psa-verification-service-indicator",
+            )?,
+        })
+    }
+}
+
+/// The PSA attestation token profile, identified by [PSA_PROFILE_URI]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PsaProfile;
+
+impl ClaimsProfile for PsaProfile {
+    fn name(&self) -> String {
+        "PSA".to_string()
+    }
+
+    fn identifiers(&self) -> Vec<ProfileTypeChoice> {
+        vec![ProfileTypeChoice::Uri(PSA_PROFILE_URI.to_string())]
+    }
+
+    fn claims(&self) -> Vec<ClaimSpec> {
+        vec![
+            ClaimSpec::required(NONCE_LABEL, "nonce", ClaimKind::Bytes(HASH_SIZES.to_vec())),
+            ClaimSpec::required(UEID_LABEL, "ueid", ClaimKind::Bytes(vec![33])),
+            ClaimSpec::required(PROFILE_LABEL, "profile", ClaimKind::Text),
+            ClaimSpec::required(PSA_CLIENT_ID_LABEL, "psa-client-id", ClaimKind::Int),
+            ClaimSpec::required(
+                PSA_SECURITY_LIFECYCLE_LABEL,
+                "psa-security-lifecycle",
+                ClaimKind::Uint,
+            ),
+            ClaimSpec::required(
+                PSA_IMPLEMENTATION_ID_LABEL,
+                "psa-implementation-id",
+                ClaimKind::Bytes(vec![32]),
+            ),
+            ClaimSpec::optional(
+                PSA_BOOT_SEED_LABEL,
+                "psa-boot-seed",
+                ClaimKind::Bytes((8..=32).collect()),
+            ),
+            ClaimSpec::optional(
+                PSA_CERTIFICATION_REFERENCE_LABEL,
+                "psa-certification-reference",
+                ClaimKind::Text,
+            ),
+            ClaimSpec::required(
+                PSA_SOFTWARE_COMPONENTS_LABEL,
+                "psa-software-components",
+                ClaimKind::Array,
+            ),
+            ClaimSpec::optional(
+                PSA_VERIFICATION_SERVICE_LABEL,
+                "psa-verification-service-indicator",
+                ClaimKind::Text,
+            ),
+        ]
+    }
+
+    fn check(&self, claims: &ClaimsSetClaimsCbor) -> Vec<String> {
+        let psa = match PsaClaims::try_from(claims) {
+            Ok(psa) => psa,
+            Err(e) => return vec![e],
+        };
+        let mut failures = vec![];
+        if psa.instance_id.first() != Some(&0x01) {
+            failures.push("Instance ID must be a random UEID (type 0x01)".to_string());
+        }
+        if let Err(e) = psa.lifecycle() {
+            failures.push(e);
+        }
+        if psa.software_components.is_empty() {
+            failures.push("psa-software-components must not be empty".to_string());
+        }
+        failures
+    }
+}
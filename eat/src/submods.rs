@@ -0,0 +1,244 @@
+//! Access to submodules by name and by path
+//!
+//! The value of the `submods` claim is a map from submodule name to submodule, see
+//! [EAT Section 4.2.18]. [NamedMap] holds such a map for either encoding, i.e.,
+//! [Submods](crate::json_specific::Submods) and [SubmodsCbor](crate::cbor_specific::SubmodsCbor).
+//! It may be used for other maps keyed by submodule name as well, such as the `submods` claim of an
+//! EAR.
+//!
+//! [SubmoduleTree] provides access to submodules by path, i.e., a `/`-separated list of submodule
+//! names such as `tee/secure-boot`. Each name other than the last must identify a submodule that
+//! carries a claims set. As `/` separates names in a path, submodules whose names contain `/` or
+//! are empty cannot be addressed by path. Such names are accepted when decoding, as the CDDL allows
+//! any text, but cannot be added using [NamedMap::insert] or [SubmoduleTree::insert_submodule].
+//!
+//! [EAT Section 4.2.18]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4.2.18
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::value::Value;
+use core::marker::PhantomData;
+use serde::{Deserialize, Serialize};
+
+use common::named::{named_from_value, serialize_named, NamedVisitor};
+
+/// Returns an error if `name` cannot be used as a submodule name, i.e., is empty or contains `/`.
+pub fn check_submodule_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains('/') {
+        return Err(format!("Invalid submodule name: {:?}", name));
+    }
+    Ok(())
+}
+
+/// Map from submodule name to submodule. Entries are kept in the order in which they were added or
+/// decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedMap<T>(pub Vec<(String, T)>);
+
+impl<T> Default for NamedMap<T> {
+    fn default() -> Self {
+        NamedMap(vec![])
+    }
+}
+
+impl<T> From<Vec<(String, T)>> for NamedMap<T> {
+    fn from(entries: Vec<(String, T)>) -> Self {
+        NamedMap(entries)
+    }
+}
+
+impl<T> NamedMap<T> {
+    /// Returns the submodule with the given name, if present.
+    pub fn get(&self, name: &str) -> Option<&T> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, s)| s)
+    }
+
+    /// Returns the submodule with the given name for modification, if present.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut T> {
+        self.0.iter_mut().find(|(n, _)| n == name).map(|(_, s)| s)
+    }
+
+    /// Adds a submodule with the given name, replacing (in place) and returning any submodule that
+    /// was already present with that name. An error is returned if the name is not valid (see
+    /// [check_submodule_name]).
+    pub fn insert(&mut self, name: &str, submodule: T) -> Result<Option<T>, String> {
+        check_submodule_name(name)?;
+        match self.get_mut(name) {
+            Some(s) => Ok(Some(core::mem::replace(s, submodule))),
+            None => {
+                self.0.push((name.to_string(), submodule));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes and returns the submodule with the given name, if present.
+    pub fn remove(&mut self, name: &str) -> Option<T> {
+        let index = self.0.iter().position(|(n, _)| n == name)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Returns an iterator over the names and submodules in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.0.iter().map(|(n, s)| (n.as_str(), s))
+    }
+
+    /// Returns the number of submodules.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no submodules.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<T: Serialize> Serialize for NamedMap<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_named(&self.0, serializer)
+    }
+}
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for NamedMap<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(NamedMap(
+            deserializer.deserialize_map(NamedVisitor(PhantomData))?,
+        ))
+    }
+}
+impl<T> TryFrom<Value> for NamedMap<T>
+where
+    T: for<'a> TryFrom<&'a Value, Error = String>,
+{
+    type Error = String;
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        NamedMap::try_from(&value)
+    }
+}
+impl<T> TryFrom<&Value> for NamedMap<T>
+where
+    T: for<'a> TryFrom<&'a Value, Error = String>,
+{
+    type Error = String;
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        Ok(NamedMap(named_from_value(value, |v| T::try_from(v))?))
+    }
+}
+/// Converts between the JSON and CBOR forms of a map, e.g., from
+/// [Submods](crate::json_specific::Submods) to [SubmodsCbor](crate::cbor_specific::SubmodsCbor).
+impl<'a, T, U> TryFrom<&'a NamedMap<U>> for NamedMap<T>
+where
+    T: TryFrom<&'a U, Error = String>,
+{
+    type Error = String;
+    fn try_from(value: &'a NamedMap<U>) -> Result<Self, Self::Error> {
+        let mut retval = vec![];
+        for (name, entry) in &value.0 {
+            retval.push((name.clone(), T::try_from(entry)?));
+        }
+        Ok(NamedMap(retval))
+    }
+}
+
+/// Claims sets that carry submodules, some of which may themselves be claims sets.
+pub trait SubmoduleTree: Sized {
+    /// Type of the submodules carried by the claims set
+    type Submodule;
+
+    /// Returns the `submods` claim.
+    fn submods(&self) -> Option<&NamedMap<Self::Submodule>>;
+
+    /// Returns the `submods` claim for modification.
+    fn submods_mut(&mut self) -> &mut Option<NamedMap<Self::Submodule>>;
+
+    /// Returns the claims set carried by `submodule`, if any.
+    fn claims_set(submodule: &Self::Submodule) -> Option<&Self>;
+
+    /// Returns the claims set carried by `submodule` for modification, if any.
+    fn claims_set_mut(submodule: &mut Self::Submodule) -> Option<&mut Self>;
+
+    /// Returns the submodule at `path`, if present.
+    fn submodule(&self, path: &str) -> Option<&Self::Submodule> {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (Self::claims_set(self.submodule(parent)?)?, name),
+            None => (self, path),
+        };
+        parent.submods()?.get(name)
+    }
+
+    /// Returns the submodule at `path` for modification, if present.
+    fn submodule_mut(&mut self, path: &str) -> Option<&mut Self::Submodule> {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (Self::claims_set_mut(self.submodule_mut(parent)?)?, name),
+            None => (self, path),
+        };
+        parent.submods_mut().as_mut()?.get_mut(name)
+    }
+
+    /// Adds a submodule at `path`, replacing and returning any submodule already present. An error
+    /// is returned if the parent of `path` is not a claims set or the last name in `path` is not
+    /// valid.
+    fn insert_submodule(
+        &mut self,
+        path: &str,
+        submodule: Self::Submodule,
+    ) -> Result<Option<Self::Submodule>, String> {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => match self.submodule_mut(parent).and_then(Self::claims_set_mut)
+            {
+                Some(p) => (p, name),
+                None => return Err(format!("No claims set submodule at {}", parent)),
+            },
+            None => (self, path),
+        };
+        check_submodule_name(name)?;
+        parent
+            .submods_mut()
+            .get_or_insert_with(Default::default)
+            .insert(name, submodule)
+    }
+
+    /// Removes and returns the submodule at `path`, if present.
+    fn remove_submodule(&mut self, path: &str) -> Option<Self::Submodule> {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (Self::claims_set_mut(self.submodule_mut(parent)?)?, name),
+            None => (self, path),
+        };
+        let claim = parent.submods_mut();
+        let submods = claim.as_mut()?;
+        let retval = submods.remove(name);
+        if submods.is_empty() {
+            *claim = None;
+        }
+        retval
+    }
+
+    /// Returns the path and submodule of every submodule, including those within submodules that
+    /// carry claims sets, in depth-first order.
+    fn submodules(&self) -> Vec<(String, &Self::Submodule)> {
+        let mut retval = vec![];
+        collect_submodules(self, "", &mut retval);
+        retval
+    }
+}
+
+fn collect_submodules<'a, C: SubmoduleTree>(
+    claims: &'a C,
+    prefix: &str,
+    retval: &mut Vec<(String, &'a C::Submodule)>,
+) {
+    if let Some(submods) = claims.submods() {
+        for (name, sm) in submods.iter() {
+            let path = match prefix.is_empty() {
+                true => name.to_string(),
+                false => format!("{}/{}", prefix, name),
+            };
+            retval.push((path.clone(), sm));
+            if let Some(cs) = C::claims_set(sm) {
+                collect_submodules(cs, &path, retval);
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Unprotected CWT Claims Sets (UCCS)
+//!
+//! A UCCS is a claims set carried without any cryptographic protection, see [UCCS Section 3].
+//! It is intended for use where the claims are protected by other means, e.g., a secure channel
+//! between attester and verifier, and is commonly used in test setups.
+//!
+//! ```text
+//! $EAT-CBOR-Tagged-Token /= UCCS-Tagged-Message
+//! UCCS-Tagged-Message = #6.601(UCCS-Untagged-Message)
+//! UCCS-Untagged-Message = Claims-Set
+//! ```
+//!
+//! An untagged UCCS cannot be distinguished from other CBOR data, so [decode_uccs] only accepts
+//! tagged messages. [NestedTokenVerifier](crate::nested::NestedTokenVerifier) rejects UCCS unless
+//! explicitly configured to accept them.
+//!
+//! [UCCS Section 3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-uccs#section-3
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::{string::String, vec, vec::Vec};
+
+use ciborium::tag::Required;
+use ciborium::{de::from_reader, ser::into_writer, value::Value};
+
+use crate::maps::ClaimsSetClaimsCbor;
+
+/// CBOR tag for `UCCS-Tagged-Message`, see [UCCS Section 3].
+///
+/// [UCCS Section 3]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-uccs#section-3
+pub const UCCS_TAG: u64 = 601;
+
+/// Encodes `claims` as a `UCCS-Tagged-Message`.
+pub fn encode_uccs(claims: &ClaimsSetClaimsCbor) -> Result<Vec<u8>, String> {
+    let mut encoded = vec![];
+    match into_writer(
+        &Required::<&ClaimsSetClaimsCbor, UCCS_TAG>(claims),
+        &mut encoded,
+    ) {
+        Ok(_) => Ok(encoded),
+        Err(e) => Err(format!("Failed to encode UCCS: {:?}", e)),
+    }
+}
+
+/// Decodes a `UCCS-Tagged-Message` and returns the claims set.
+pub fn decode_uccs(token: &[u8]) -> Result<ClaimsSetClaimsCbor, String> {
+    let value: Value = match from_reader(token) {
+        Ok(v) => v,
+        Err(e) => return Err(format!("Failed to parse UCCS: {:?}", e)),
+    };
+    match value {
+        Value::Tag(UCCS_TAG, inner) => ClaimsSetClaimsCbor::try_from(*inner),
+        Value::Tag(t, _) => Err(format!("Unsupported UCCS tag: {}", t)),
+        _ => Err("UCCS is not tagged".to_string()),
+    }
+}
@@ -0,0 +1,653 @@
+//! Validation of EAT claims
+//!
+//! Decoding and signature verification establish where a claims set came from. [ClaimsValidator]
+//! checks what it says: that the token is within its validity period, that it echoes the expected
+//! nonce(s), that it was issued by and for the expected parties, that claims with size constraints
+//! in [EAT Section 4] observe them and that the claims required or forbidden by a [ClaimsProfile]
+//! are present or absent. All failures are collected in a [ValidationReport] rather than stopping
+//! at the first one.
+//!
+//! Claims are identified by their CBOR labels, see [EAT Section 10.2]. Constants are provided for
+//! the labels of the claims defined by CWT and EAT.
+//!
+//! [EAT Section 4]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-4
+//! [EAT Section 10.2]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-10.2
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::{vec, vec::Vec};
+
+use ciborium::{de::from_reader, value::Value};
+
+use crate::choices::Oemid;
+use crate::cwt::encode_claims;
+use crate::maps::{ClaimsSetClaims, ClaimsSetClaimsCbor};
+use common::{BytesType, NonceType, TextOrInt, UeidType};
+use corim::choices::ProfileTypeChoice;
+
+/// Label of the `iss` claim
+pub const ISS_LABEL: i64 = 1;
+/// Label of the `sub` claim
+pub const SUB_LABEL: i64 = 2;
+/// Label of the `aud` claim
+pub const AUD_LABEL: i64 = 3;
+/// Label of the `exp` claim
+pub const EXP_LABEL: i64 = 4;
+/// Label of the `nbf` claim
+pub const NBF_LABEL: i64 = 5;
+/// Label of the `iat` claim
+pub const IAT_LABEL: i64 = 6;
+/// Label of the `cti` claim
+pub const CTI_LABEL: i64 = 7;
+/// Label of the `eat_nonce` claim
+pub const NONCE_LABEL: i64 = 10;
+/// Label of the `ueid` claim
+pub const UEID_LABEL: i64 = 256;
+/// Label of the `sueids` claim
+pub const SUEIDS_LABEL: i64 = 257;
+/// Label of the `oemid` claim
+pub const OEMID_LABEL: i64 = 258;
+/// Label of the `hwmodel` claim
+pub const HARDWARE_MODEL_LABEL: i64 = 259;
+/// Label of the `hwversion` claim
+pub const HARDWARE_VERSION_LABEL: i64 = 260;
+/// Label of the `oemboot` claim
+pub const SECURE_BOOT_LABEL: i64 = 262;
+/// Label of the `dbgstat` claim
+pub const DEBUG_STATUS_LABEL: i64 = 263;
+/// Label of the `location` claim
+pub const LOCATION_LABEL: i64 = 264;
+/// Label of the `eat_profile` claim
+pub const PROFILE_LABEL: i64 = 265;
+/// Label of the `submods` claim
+pub const SUBMODS_LABEL: i64 = 266;
+/// Label of the `uptime` claim
+pub const UPTIME_LABEL: i64 = 267;
+/// Label of the `bootseed` claim
+pub const BOOT_SEED_LABEL: i64 = 268;
+/// Label of the `intuse` claim
+pub const INTENDED_USE_LABEL: i64 = 269;
+/// Label of the `dloas` claim
+pub const DLOAS_LABEL: i64 = 270;
+/// Label of the `swname` claim
+pub const SW_NAME_LABEL: i64 = 271;
+/// Label of the `swversion` claim
+pub const SW_VERSION_LABEL: i64 = 272;
+/// Label of the `manifests` claim
+pub const MANIFESTS_LABEL: i64 = 273;
+/// Label of the `measurements` claim
+pub const MEASUREMENTS_LABEL: i64 = 274;
+/// Label of the `measres` claim
+pub const MEASUREMENT_RESULTS_LABEL: i64 = 275;
+/// Label of the `bootcount` claim
+pub const BOOT_COUNT_LABEL: i64 = 276;
+
+/// Reason a claims set failed validation
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationFailure {
+    /// The `exp` claim is earlier than the current time, allowing for clock skew
+    Expired {
+        /// Value of the `exp` claim
+        exp: i64,
+        /// Time used for validation
+        now: i64,
+    },
+    /// The `nbf` claim is later than the current time, allowing for clock skew
+    NotYetValid {
+        /// Value of the `nbf` claim
+        nbf: i64,
+        /// Time used for validation
+        now: i64,
+    },
+    /// The `iat` claim is later than the current time, allowing for clock skew
+    IssuedInFuture {
+        /// Value of the `iat` claim
+        iat: i64,
+        /// Time used for validation
+        now: i64,
+    },
+    /// The `iat` claim is older than the maximum age, allowing for clock skew
+    TooOld {
+        /// Value of the `iat` claim
+        iat: i64,
+        /// Time used for validation
+        now: i64,
+    },
+    /// A nonce was expected but the `nonce` claim is absent
+    MissingNonce,
+    /// An expected nonce does not appear in the `nonce` claim
+    NonceMismatch(Vec<u8>),
+    /// A nonce in the `nonce` claim is not between 8 and 64 bytes long
+    InvalidNonceSize(usize),
+    /// The `aud` claim is absent or does not match an expected audience
+    AudienceMismatch(Option<String>),
+    /// The `iss` claim is absent or does not match the expected issuer
+    IssuerMismatch(Option<String>),
+    /// The `ueid` claim does not observe the size and type rules
+    InvalidUeid(String),
+    /// The `oemid` claim does not observe the size rules
+    InvalidOemid(String),
+    /// The `hwmodel` claim is not between 1 and 32 bytes long
+    InvalidHardwareModel(usize),
+    /// A claim required by a profile is absent
+    MissingClaim {
+        /// Profile name
+        profile: String,
+        /// Claim label
+        label: TextOrInt,
+    },
+    /// A claim forbidden by a profile is present
+    ForbiddenClaim {
+        /// Profile name
+        profile: String,
+        /// Claim label
+        label: TextOrInt,
+    },
+    /// A claim declared by a profile does not have the declared type or size
+    InvalidClaim {
+        /// Profile name
+        profile: String,
+        /// Claim label
+        label: TextOrInt,
+        /// Description of the failure
+        reason: String,
+    },
+    /// A profile-specific check failed
+    Profile {
+        /// Profile name
+        profile: String,
+        /// Description of the failure
+        reason: String,
+    },
+}
+
+/// Results of validating a claims set
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    /// Names of the checks that were performed, e.g., `exp` or a profile name
+    pub checked: Vec<String>,
+    /// Failures, in the order in which they were found
+    pub failures: Vec<ValidationFailure>,
+}
+
+impl ValidationReport {
+    /// Returns true if no failures were found.
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn check(&mut self, name: &str) {
+        self.checked.push(name.to_string());
+    }
+
+    fn fail(&mut self, failure: ValidationFailure) {
+        self.failures.push(failure);
+    }
+}
+
+/// Type expected for a claim declared by a [ClaimsProfile]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ClaimKind {
+    /// Any value
+    Any,
+    /// Signed integer
+    Int,
+    /// Unsigned integer
+    Uint,
+    /// Text string
+    Text,
+    /// Byte string with one of the listed lengths, or any length if the list is empty
+    Bytes(Vec<usize>),
+    /// Boolean
+    Bool,
+    /// Array
+    Array,
+    /// Map
+    Map,
+}
+
+impl ClaimKind {
+    /// Returns a description of the mismatch if `value` is not of this kind.
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        let matched = match (self, value) {
+            (ClaimKind::Any, _) => true,
+            (ClaimKind::Int, Value::Integer(_)) => true,
+            (ClaimKind::Uint, Value::Integer(i)) => u64::try_from(*i).is_ok(),
+            (ClaimKind::Text, Value::Text(_)) => true,
+            (ClaimKind::Bytes(sizes), Value::Bytes(b)) => {
+                if sizes.is_empty() || sizes.contains(&b.len()) {
+                    true
+                } else {
+                    return Err(format!(
+                        "Expected one of {:?} bytes but found {}",
+                        sizes,
+                        b.len()
+                    ));
+                }
+            }
+            (ClaimKind::Bool, Value::Bool(_)) => true,
+            (ClaimKind::Array, Value::Array(_)) => true,
+            (ClaimKind::Map, Value::Map(_)) => true,
+            _ => false,
+        };
+        match matched {
+            true => Ok(()),
+            false => Err(format!("Expected {:?} but found {:?}", self, value)),
+        }
+    }
+}
+
+/// Whether a claim declared by a [ClaimsProfile] must be present
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub enum ClaimPresence {
+    Required,
+    Optional,
+}
+
+/// Declaration of a claim expected by a [ClaimsProfile]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimSpec {
+    /// Claim label
+    pub label: TextOrInt,
+    /// Claim name, for use in diagnostics
+    pub name: String,
+    /// Whether the claim must be present
+    pub presence: ClaimPresence,
+    /// Type of the claim value
+    pub kind: ClaimKind,
+}
+
+impl ClaimSpec {
+    /// Declares a required claim with an integer label.
+    pub fn required(label: i64, name: &str, kind: ClaimKind) -> Self {
+        ClaimSpec {
+            label: TextOrInt::Int(label),
+            name: name.to_string(),
+            presence: ClaimPresence::Required,
+            kind,
+        }
+    }
+
+    /// Declares an optional claim with an integer label.
+    pub fn optional(label: i64, name: &str, kind: ClaimKind) -> Self {
+        ClaimSpec {
+            presence: ClaimPresence::Optional,
+            ..ClaimSpec::required(label, name, kind)
+        }
+    }
+}
+
+/// Requirements imposed on a claims set by an EAT profile, see [EAT Section 6].
+///
+/// A profile may declare the claims it expects using [claims](ClaimsProfile::claims), in which
+/// case the declared claims that are present are checked against the declared types and the
+/// required claims are reported if absent. Profiles may also list required and forbidden claims
+/// directly and perform arbitrary additional checks.
+///
+/// [EAT Section 6]: https://datatracker.ietf.org/doc/html/draft-ietf-rats-eat#section-6
+pub trait ClaimsProfile {
+    /// Name used to identify the profile in a [ValidationReport]
+    fn name(&self) -> String;
+
+    /// Values of the `profile` claim that identify this profile
+    fn identifiers(&self) -> Vec<ProfileTypeChoice> {
+        vec![]
+    }
+
+    /// Claims expected by the profile
+    fn claims(&self) -> Vec<ClaimSpec> {
+        vec![]
+    }
+
+    /// Labels of claims that must be present. By default, these are the required claims returned
+    /// by [claims](ClaimsProfile::claims).
+    fn required_claims(&self) -> Vec<TextOrInt> {
+        self.claims()
+            .into_iter()
+            .filter(|c| c.presence == ClaimPresence::Required)
+            .map(|c| c.label)
+            .collect()
+    }
+
+    /// Labels of claims that must not be present
+    fn forbidden_claims(&self) -> Vec<TextOrInt> {
+        vec![]
+    }
+
+    /// Performs additional checks, returning a description of each failure.
+    fn check(&self, _claims: &ClaimsSetClaimsCbor) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Returns the labels and values of the claims present in `claims`, in encoded order.
+pub fn claim_values(claims: &ClaimsSetClaimsCbor) -> Result<Vec<(TextOrInt, Value)>, String> {
+    let encoded = encode_claims(claims)?;
+    let value: Value = match from_reader(encoded.as_slice()) {
+        Ok(v) => v,
+        Err(e) => return Err(format!("Failed to parse claims set: {:?}", e)),
+    };
+    let mut retval = vec![];
+    if let Value::Map(m) = value {
+        for (k, v) in m {
+            retval.push((TextOrInt::try_from(&k)?, v));
+        }
+    }
+    Ok(retval)
+}
+
+/// Returns the labels of the claims present in `claims`.
+pub fn claim_labels(claims: &ClaimsSetClaimsCbor) -> Result<Vec<TextOrInt>, String> {
+    Ok(claim_values(claims)?.into_iter().map(|(k, _)| k).collect())
+}
+
+fn nonces(nonce: &NonceType) -> Vec<&Vec<u8>> {
+    match nonce {
+        NonceType::One(BytesType::Bytes(b)) => vec![b],
+        NonceType::More(v) => v.iter().map(|BytesType::Bytes(b)| b).collect(),
+    }
+}
+
+fn check_ueid(ueid: &[u8]) -> Result<(), String> {
+    if !(7..=33).contains(&ueid.len()) {
+        return Err(format!(
+            "UEID must be between 7 and 33 bytes but is {}",
+            ueid.len()
+        ));
+    }
+    let valid = match ueid[0] {
+        // RAND: 16, 24 or 32 random bytes
+        0x01 => [17, 25, 33].contains(&ueid.len()),
+        // IEEE EUI: EUI-48 or EUI-64
+        0x02 => [7, 9].contains(&ueid.len()),
+        // IMEI: 14 digits, one per byte
+        0x03 => ueid.len() == 15,
+        t => return Err(format!("Unrecognized UEID type: {}", t)),
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(format!(
+            "UEID of type {} cannot be {} bytes",
+            ueid[0],
+            ueid.len()
+        )),
+    }
+}
+
+/// Validates claims sets against time, nonce, audience, issuer, size and profile requirements.
+///
+/// Checks are only performed for the options that have been configured, except that size rules
+/// are always enforced for the `nonce`, `ueid`, `oemid` and `hwmodel` claims.
+#[derive(Default)]
+pub struct ClaimsValidator {
+    clock_skew: i64,
+    max_age: Option<i64>,
+    expected_nonces: Vec<Vec<u8>>,
+    expected_audiences: Vec<String>,
+    expected_issuer: Option<String>,
+    profiles: Vec<Box<dyn ClaimsProfile>>,
+}
+
+impl ClaimsValidator {
+    /// Creates a validator with no clock skew allowance and no expectations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of seconds by which the clocks of the attester and verifier may differ.
+    pub fn clock_skew(mut self, seconds: i64) -> Self {
+        self.clock_skew = seconds;
+        self
+    }
+
+    /// Requires the `iat` claim, if present, to be no more than `seconds` old.
+    pub fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Adds a nonce that the `nonce` claim must contain. When several nonces are expected, each
+    /// must appear in the claim.
+    pub fn expected_nonce(mut self, nonce: &[u8]) -> Self {
+        self.expected_nonces.push(nonce.to_vec());
+        self
+    }
+
+    /// Adds an acceptable value for the `aud` claim.
+    pub fn expected_audience(mut self, audience: &str) -> Self {
+        self.expected_audiences.push(audience.to_string());
+        self
+    }
+
+    /// Sets the required value of the `iss` claim.
+    pub fn expected_issuer(mut self, issuer: &str) -> Self {
+        self.expected_issuer = Some(issuer.to_string());
+        self
+    }
+
+    /// Adds a profile whose requirements are checked.
+    pub fn profile(mut self, profile: Box<dyn ClaimsProfile>) -> Self {
+        self.profiles.push(profile);
+        self
+    }
+
+    /// Validates `claims` as of `now`, given as seconds since the epoch. An error is returned only
+    /// if the claims set cannot be processed.
+    pub fn validate(
+        &self,
+        claims: &ClaimsSetClaimsCbor,
+        now: i64,
+    ) -> Result<ValidationReport, String> {
+        let mut report = ValidationReport::default();
+        self.check_times(claims, now, &mut report)?;
+        self.check_nonce(claims, &mut report);
+        self.check_parties(claims, &mut report);
+        self.check_sizes(claims, &mut report);
+        self.check_profiles(claims, &mut report)?;
+        Ok(report)
+    }
+
+    /// Validates a JSON-encoded claims set, see [validate](ClaimsValidator::validate).
+    pub fn validate_json(
+        &self,
+        claims: &ClaimsSetClaims,
+        now: i64,
+    ) -> Result<ValidationReport, String> {
+        self.validate(&ClaimsSetClaimsCbor::try_from(claims)?, now)
+    }
+
+    /// Validates `claims` as of the current system time.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn validate_now(&self, claims: &ClaimsSetClaimsCbor) -> Result<ValidationReport, String> {
+        let now = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => return Err(format!("Failed to read system time: {:?}", e)),
+        };
+        self.validate(claims, now)
+    }
+
+    fn check_times(
+        &self,
+        claims: &ClaimsSetClaimsCbor,
+        now: i64,
+        report: &mut ValidationReport,
+    ) -> Result<(), String> {
+        if let Some(exp) = &claims.exp {
+            report.check("exp");
+            let exp = i64::try_from(exp)?;
+            if now > exp.saturating_add(self.clock_skew) {
+                report.fail(ValidationFailure::Expired { exp, now });
+            }
+        }
+        if let Some(nbf) = &claims.nbf {
+            report.check("nbf");
+            let nbf = i64::try_from(nbf)?;
+            if now.saturating_add(self.clock_skew) < nbf {
+                report.fail(ValidationFailure::NotYetValid { nbf, now });
+            }
+        }
+        if let Some(iat) = &claims.iat {
+            report.check("iat");
+            let iat = i64::try_from(iat)?;
+            if now.saturating_add(self.clock_skew) < iat {
+                report.fail(ValidationFailure::IssuedInFuture { iat, now });
+            }
+            if let Some(max_age) = self.max_age {
+                if now.saturating_sub(iat) > max_age.saturating_add(self.clock_skew) {
+                    report.fail(ValidationFailure::TooOld { iat, now });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_nonce(&self, claims: &ClaimsSetClaimsCbor, report: &mut ValidationReport) {
+        let present = claims.nonce.as_ref().map(nonces).unwrap_or_default();
+        if !present.is_empty() {
+            report.check("nonce size");
+        }
+        for n in &present {
+            if !(8..=64).contains(&n.len()) {
+                report.fail(ValidationFailure::InvalidNonceSize(n.len()));
+            }
+        }
+        if self.expected_nonces.is_empty() {
+            return;
+        }
+        report.check("nonce");
+        if claims.nonce.is_none() {
+            report.fail(ValidationFailure::MissingNonce);
+            return;
+        }
+        for expected in &self.expected_nonces {
+            if !present.contains(&expected) {
+                report.fail(ValidationFailure::NonceMismatch(expected.clone()));
+            }
+        }
+    }
+
+    fn check_parties(&self, claims: &ClaimsSetClaimsCbor, report: &mut ValidationReport) {
+        if !self.expected_audiences.is_empty() {
+            report.check("aud");
+            let matched = match &claims.aud {
+                Some(aud) => self.expected_audiences.contains(aud),
+                None => false,
+            };
+            if !matched {
+                report.fail(ValidationFailure::AudienceMismatch(claims.aud.clone()));
+            }
+        }
+        if let Some(expected) = &self.expected_issuer {
+            report.check("iss");
+            if claims.iss.as_ref() != Some(expected) {
+                report.fail(ValidationFailure::IssuerMismatch(claims.iss.clone()));
+            }
+        }
+    }
+
+    fn check_sizes(&self, claims: &ClaimsSetClaimsCbor, report: &mut ValidationReport) {
+        if let Some(UeidType::Ueid(ueid)) = &claims.ueid {
+            report.check("ueid");
+            if let Err(e) = check_ueid(ueid) {
+                report.fail(ValidationFailure::InvalidUeid(e));
+            }
+        }
+        if let Some(oemid) = &claims.oemid {
+            report.check("oemid");
+            let failure = match oemid {
+                Oemid::Ieee(v) if v.len() != 3 => {
+                    Some(format!("IEEE OEM ID must be 3 bytes but is {}", v.len()))
+                }
+                Oemid::Random(v) if v.len() != 16 => {
+                    Some(format!("Random OEM ID must be 16 bytes but is {}", v.len()))
+                }
+                _ => None,
+            };
+            if let Some(f) = failure {
+                report.fail(ValidationFailure::InvalidOemid(f));
+            }
+        }
+        if let Some(hwmodel) = &claims.hardware_model {
+            report.check("hwmodel");
+            if !(1..=32).contains(&hwmodel.len()) {
+                report.fail(ValidationFailure::InvalidHardwareModel(hwmodel.len()));
+            }
+        }
+    }
+
+    /// Validates `claims` as of `now` as with [validate](ClaimsValidator::validate), additionally
+    /// checking the requirements of `profile`. This is useful when the profile is selected based
+    /// on the claims set, e.g., using a [ProfileRegistry](crate::profile::ProfileRegistry).
+    pub fn validate_with_profile(
+        &self,
+        claims: &ClaimsSetClaimsCbor,
+        now: i64,
+        profile: &dyn ClaimsProfile,
+    ) -> Result<ValidationReport, String> {
+        let mut report = self.validate(claims, now)?;
+        check_profile(profile, &claim_values(claims)?, claims, &mut report);
+        Ok(report)
+    }
+
+    fn check_profiles(
+        &self,
+        claims: &ClaimsSetClaimsCbor,
+        report: &mut ValidationReport,
+    ) -> Result<(), String> {
+        if self.profiles.is_empty() {
+            return Ok(());
+        }
+        let values = claim_values(claims)?;
+        for profile in &self.profiles {
+            check_profile(profile.as_ref(), &values, claims, report);
+        }
+        Ok(())
+    }
+}
+
+fn check_profile(
+    profile: &dyn ClaimsProfile,
+    values: &[(TextOrInt, Value)],
+    claims: &ClaimsSetClaimsCbor,
+    report: &mut ValidationReport,
+) {
+    let name = profile.name();
+    report.check(&name);
+    let get = |label: &TextOrInt| values.iter().find(|(k, _)| k == label).map(|(_, v)| v);
+    for label in profile.required_claims() {
+        if get(&label).is_none() {
+            report.fail(ValidationFailure::MissingClaim {
+                profile: name.clone(),
+                label,
+            });
+        }
+    }
+    for label in profile.forbidden_claims() {
+        if get(&label).is_some() {
+            report.fail(ValidationFailure::ForbiddenClaim {
+                profile: name.clone(),
+                label,
+            });
+        }
+    }
+    for spec in profile.claims() {
+        if let Some(value) = get(&spec.label) {
+            if let Err(e) = spec.kind.check(value) {
+                report.fail(ValidationFailure::InvalidClaim {
+                    profile: name.clone(),
+                    label: spec.label,
+                    reason: format!("{}: {}", spec.name, e),
+                });
+            }
+        }
+    }
+    for reason in profile.check(claims) {
+        report.fail(ValidationFailure::Profile {
+            profile: name.clone(),
+            reason,
+        });
+    }
+}
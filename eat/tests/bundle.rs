@@ -0,0 +1,120 @@
+use common::TextOrInt;
+use eat::arrays::*;
+use eat::bundle::*;
+use eat::cbor_specific::*;
+use eat::cwt::*;
+use hex_literal::hex;
+
+mod utils;
+use utils::*;
+
+fn bundle(sk: &ed25519_dalek::SigningKey) -> DetachedEatBundleCbor {
+    DetachedBundleBuilder::new(claims("main"))
+        .detached_claims_set("tee", &claims("tee"))
+        .unwrap()
+        .detached_claims_set("ree", &claims("ree"))
+        .unwrap()
+        .build(|c| sign_cwt(c, empty_header(), CwtForm::CwtTagged, sk))
+        .unwrap()
+}
+
+fn empty_header() -> cose::maps::HeaderMapCbor {
+    cose::sign::empty_header_map()
+}
+
+#[test]
+fn bundle_round_trip_test() {
+    let sk = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+    let deb = bundle(&sk);
+
+    for tagged in [true, false] {
+        let encoded = encode_bundle(&deb, tagged).unwrap();
+        assert_eq!(encoded.starts_with(&hex!("d9025a")), tagged);
+        assert_eq!(decode_bundle(&encoded).unwrap(), deb);
+    }
+
+    let (main, report) = verify_cwt_bundle(&deb, &sk.verifying_key()).unwrap();
+    assert!(report.is_valid());
+    assert_eq!(main.sw_name, Some("main".to_string()));
+    assert_eq!(report.verified.len(), 2);
+    assert_eq!(report.verified[0], ("tee".to_string(), claims("tee")));
+    assert_eq!(report.verified[1], ("ree".to_string(), claims("ree")));
+
+    // the main token carries a SHA-256 digest over each wrapped claims set
+    let submods = main.submods.unwrap();
+    let wcs = deb.detached_claims_sets.get("tee").unwrap();
+    match submods.get("tee") {
+        Some(SubmoduleCbor::SelectorCbor(SelectorCbor::DetachedSubmoduleDigest(dsd))) => {
+            assert_eq!(dsd.hash_algorithm, TextOrInt::Int(SHA_256));
+            assert_eq!(
+                dsd.digest,
+                compute_digest(&TextOrInt::Text("sha-256".to_string()), &wcs.0).unwrap()
+            );
+        }
+        _ => panic!(),
+    }
+
+    // the wrong key is rejected
+    let other = ed25519_dalek::SigningKey::from_bytes(&[6; 32]);
+    assert!(verify_cwt_bundle(&deb, &other.verifying_key()).is_err());
+
+    // other hash algorithms can be used and empty bundles are rejected
+    let builder = DetachedBundleBuilder::new(claims("main"))
+        .hash_algorithm(TextOrInt::Int(SHA_512))
+        .unwrap()
+        .detached_claims_set("tee", &claims("tee"))
+        .unwrap();
+    assert!(builder
+        .clone()
+        .detached_claims_set("tee", &claims("tee"))
+        .is_err());
+    let main = builder.main_claims().unwrap();
+    let deb = builder.build(|c| Ok(encode_claims(c).unwrap())).unwrap();
+    let report = check_detached_claims_sets(&main, &deb.detached_claims_sets).unwrap();
+    assert!(report.is_valid());
+    assert!(DetachedBundleBuilder::new(claims("main"))
+        .hash_algorithm(TextOrInt::Int(1))
+        .is_err());
+    assert!(DetachedBundleBuilder::new(claims("main"))
+        .hash_algorithm(TextOrInt::Text("md5".to_string()))
+        .is_err());
+    assert_eq!(
+        compute_digest(&TextOrInt::Int(SHA_384), b"abc").unwrap(),
+        compute_digest(&TextOrInt::Text("sha-384".to_string()), b"abc").unwrap()
+    );
+    assert!(DetachedBundleBuilder::new(claims("main"))
+        .build(|c| Ok(encode_claims(c).unwrap()))
+        .is_err());
+}
+
+#[test]
+fn bundle_mismatch_test() {
+    let sk = ed25519_dalek::SigningKey::from_bytes(&[5; 32]);
+    let mut deb = bundle(&sk);
+
+    // substitute the tee claims set, drop the ree claims set and add one that is not referenced
+    let substitute = encode_claims(&claims("evil")).unwrap();
+    deb.detached_claims_sets.0 = vec![
+        ("tee".to_string(), WrappedClaimsSetCbor(substitute)),
+        (
+            "extra".to_string(),
+            WrappedClaimsSetCbor(encode_claims(&claims("extra")).unwrap()),
+        ),
+    ];
+    let (_, report) = verify_cwt_bundle(&deb, &sk.verifying_key()).unwrap();
+    assert!(!report.is_valid());
+    assert!(report.verified.is_empty());
+    assert_eq!(report.mismatched, vec!["tee".to_string()]);
+    assert_eq!(report.missing, vec!["ree".to_string()]);
+    assert_eq!(report.extra, vec!["extra".to_string()]);
+
+    // names that refer to a submodule that does not carry a digest are extra
+    let mut main = claims("main");
+    main.submods = Some(SubmodsCbor::from(vec![(
+        "extra".to_string(),
+        SubmoduleCbor::ClaimsSet(Box::new(claims("inline"))),
+    )]));
+    let report = check_detached_claims_sets(&main, &deb.detached_claims_sets).unwrap();
+    assert_eq!(report.extra, vec!["tee".to_string(), "extra".to_string()]);
+    assert!(report.missing.is_empty());
+}
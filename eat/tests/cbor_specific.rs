@@ -0,0 +1,141 @@
+use ciborium::de::from_reader;
+use ciborium::ser::into_writer;
+use eat::cbor_specific::*;
+use eat::json_specific::*;
+use eat::maps::*;
+use eat::submods::*;
+use hex_literal::hex;
+
+mod utils;
+use utils::*;
+
+fn inline(sw_name: &str) -> SubmoduleCbor {
+    SubmoduleCbor::ClaimsSet(Box::new(claims(sw_name)))
+}
+
+fn sw_name(sm: Option<&SubmoduleCbor>) -> Option<String> {
+    match sm {
+        Some(SubmoduleCbor::ClaimsSet(cs)) => cs.sw_name.clone(),
+        _ => None,
+    }
+}
+
+#[test]
+fn submodule_path_test() {
+    let mut token = claims("root");
+    assert!(token.submodule("tee").is_none());
+    assert_eq!(token.insert_submodule("tee", inline("tee")), Ok(None));
+    assert_eq!(token.insert_submodule("ree", inline("ree")), Ok(None));
+    assert_eq!(
+        token.insert_submodule("tee/secure-boot", inline("secure-boot")),
+        Ok(None)
+    );
+    let jwt =
+        SubmoduleCbor::SelectorCbor(SelectorCbor::JsonTokenInsideCborToken("a.b.c".to_string()));
+    assert_eq!(
+        token.insert_submodule("tee/secure-boot/jwt", jwt.clone()),
+        Ok(None)
+    );
+
+    // lookup by path
+    assert_eq!(sw_name(token.submodule("tee")), Some("tee".to_string()));
+    assert_eq!(
+        sw_name(token.submodule("tee/secure-boot")),
+        Some("secure-boot".to_string())
+    );
+    assert_eq!(token.submodule("tee/secure-boot/jwt"), Some(&jwt));
+    assert!(token.submodule("tee/missing").is_none());
+    assert!(token.submodule("ree/secure-boot").is_none());
+
+    // insertion below a nested token or a missing submodule fails
+    assert!(token
+        .insert_submodule("tee/secure-boot/jwt/x", inline("x"))
+        .is_err());
+    assert!(token.insert_submodule("missing/x", inline("x")).is_err());
+    assert!(token.insert_submodule("tee/", inline("x")).is_err());
+
+    // replacement keeps the position of the submodule
+    let replaced = token.insert_submodule("tee", inline("tee2")).unwrap();
+    assert_eq!(sw_name(replaced.as_ref()), Some("tee".to_string()));
+    let paths: Vec<String> = token.submodules().into_iter().map(|(p, _)| p).collect();
+    assert_eq!(paths, vec!["tee", "ree"]);
+    token.insert_submodule("tee", replaced.unwrap()).unwrap();
+
+    // iteration is depth-first and in insertion order
+    let paths: Vec<String> = token.submodules().into_iter().map(|(p, _)| p).collect();
+    assert_eq!(
+        paths,
+        vec!["tee", "tee/secure-boot", "tee/secure-boot/jwt", "ree"]
+    );
+    let names: Vec<&str> = token
+        .submods
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|(n, _)| n)
+        .collect();
+    assert_eq!(names, vec!["tee", "ree"]);
+
+    // more than one submodule survives CBOR and JSON round trips in order
+    let mut encoded = vec![];
+    into_writer(&token, &mut encoded).unwrap();
+    let decoded: ClaimsSetClaimsCbor = from_reader(encoded.as_slice()).unwrap();
+    assert_eq!(decoded, token);
+    let json: ClaimsSetClaims = (&token).try_into().unwrap();
+    assert!(matches!(
+        json.submodule("tee/secure-boot/jwt"),
+        Some(Submodule::JsonSelector(_))
+    ));
+    let cbor: ClaimsSetClaimsCbor = json.try_into().unwrap();
+    assert_eq!(cbor, token);
+
+    // removal
+    assert_eq!(token.remove_submodule("tee/secure-boot/jwt"), Some(jwt));
+    assert!(token.submodule("tee/secure-boot").is_some());
+    assert!(token.remove_submodule("tee/secure-boot/jwt").is_none());
+    assert!(token.remove_submodule("tee").is_some());
+    assert!(token.remove_submodule("ree").is_some());
+    assert!(token.submods.is_none());
+}
+
+#[test]
+fn submods_decode_test() {
+    // a claims set with two submodules: a CBOR token and a detached submodule digest
+    let submods = hex!("a263746565430102036664696765737482104401020304");
+    let decoded: SubmodsCbor = from_reader(submods.as_slice()).unwrap();
+    assert_eq!(decoded.len(), 2);
+    assert!(matches!(
+        decoded.get("tee"),
+        Some(SubmoduleCbor::SelectorCbor(
+            SelectorCbor::CborTokenInsideCborToken(_)
+        ))
+    ));
+    assert!(matches!(
+        decoded.get("digest"),
+        Some(SubmoduleCbor::SelectorCbor(
+            SelectorCbor::DetachedSubmoduleDigest(_)
+        ))
+    ));
+    let mut encoded = vec![];
+    into_writer(&decoded, &mut encoded).unwrap();
+    assert_eq!(encoded, submods);
+
+    // duplicate names and non-text keys are rejected
+    let duplicate = hex!("a2616143010203616143010203");
+    assert!(from_reader::<SubmodsCbor, _>(duplicate.as_slice()).is_err());
+    let int_key = hex!("a101430102");
+    assert!(from_reader::<SubmodsCbor, _>(int_key.as_slice()).is_err());
+
+    // names that cannot be used in a path are decoded but cannot be inserted
+    let slash = hex!("a163612f6243010203");
+    let decoded: SubmodsCbor = from_reader(slash.as_slice()).unwrap();
+    assert!(decoded.get("a/b").is_some());
+    let empty = hex!("a16043010203");
+    let decoded: SubmodsCbor = from_reader(empty.as_slice()).unwrap();
+    assert!(decoded.get("").is_some());
+    let mut submods = SubmodsCbor::default();
+    assert!(submods.insert("a/b", inline("x")).is_err());
+    assert!(submods.insert("", inline("x")).is_err());
+    assert!(submods.is_empty());
+    assert!(check_submodule_name("a-b").is_ok());
+}
@@ -0,0 +1,161 @@
+use ciborium::{de::from_reader, ser::into_writer};
+use eat::custom::*;
+use eat::maps::*;
+use hex_literal::hex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct FirmwareId(String);
+impl CustomClaim for FirmwareId {
+    const LABEL: i64 = -70001;
+    const NAME: &'static str = "acme_fwid";
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Counters {
+    boots: u64,
+    resets: u64,
+}
+impl CustomClaim for Counters {
+    const LABEL: i64 = -70002;
+    const NAME: &'static str = "acme_counters";
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Reserved(bool);
+impl CustomClaim for Reserved {
+    const LABEL: i64 = 262;
+    const NAME: &'static str = "acme_secure_boot";
+}
+
+fn claims() -> ClaimsSetClaimsCbor {
+    // {1: "Acme Inc."}
+    let iss = hex!("a1016941636d6520496e632e");
+    from_reader(iss.as_slice()).unwrap()
+}
+
+#[test]
+fn custom_claim_cbor_test() {
+    let mut claims = claims();
+    assert_eq!(claims.custom_claim::<FirmwareId>().unwrap(), None);
+    claims
+        .set_custom_claim(&FirmwareId("fw-1".to_string()))
+        .unwrap();
+    claims
+        .set_custom_claim(&Counters {
+            boots: 3,
+            resets: 1,
+        })
+        .unwrap();
+    claims
+        .set_custom_claim(&FirmwareId("fw-2".to_string()))
+        .unwrap();
+    assert_eq!(claims.other.as_ref().unwrap().len(), 2);
+
+    let mut encoded = vec![];
+    into_writer(&claims, &mut encoded).unwrap();
+    let decoded: ClaimsSetClaimsCbor = from_reader(encoded.as_slice()).unwrap();
+    assert_eq!(
+        decoded.custom_claim::<FirmwareId>().unwrap(),
+        Some(FirmwareId("fw-2".to_string()))
+    );
+    assert_eq!(
+        decoded.custom_claim::<Counters>().unwrap(),
+        Some(Counters {
+            boots: 3,
+            resets: 1
+        })
+    );
+
+    let mut json = ClaimsSetClaims::try_from(&decoded).unwrap();
+    assert_eq!(
+        json.custom_claim::<FirmwareId>().unwrap(),
+        Some(FirmwareId("fw-2".to_string()))
+    );
+    assert!(json.remove_custom_claim::<FirmwareId>());
+    assert!(!json.remove_custom_claim::<FirmwareId>());
+    assert!(json.remove_custom_claim::<Counters>());
+    assert_eq!(json.other, None);
+
+    // a value of the wrong type is reported when read
+    let mut registry = ClaimRegistry::new();
+    registry.register::<Counters>().unwrap();
+    assert!(registry.check(&decoded).is_ok());
+    let mut wrong = decoded.clone();
+    for t in wrong.other.iter_mut().flatten() {
+        if t.key == ciborium::value::Value::Integer(Counters::LABEL.into()) {
+            t.value = ciborium::value::Value::Text("three".to_string());
+        }
+    }
+    assert!(wrong.custom_claim::<Counters>().is_err());
+    assert!(registry.check(&wrong).is_err());
+}
+
+#[test]
+fn claim_registry_json_test() {
+    let mut registry = ClaimRegistry::new();
+    registry
+        .register::<FirmwareId>()
+        .unwrap()
+        .register::<Counters>()
+        .unwrap();
+    assert!(registry.register::<FirmwareId>().is_err());
+    assert!(ClaimRegistry::new().register::<Reserved>().is_err());
+    assert_eq!(registry.name(-70002), Some("acme_counters"));
+    assert_eq!(registry.label("acme_fwid"), Some(-70001));
+
+    let mut claims = ClaimsSetClaims::try_from(claims()).unwrap();
+    claims
+        .set_custom_claim(&FirmwareId("fw-1".to_string()))
+        .unwrap();
+    claims
+        .set_custom_claim(&Counters {
+            boots: 3,
+            resets: 1,
+        })
+        .unwrap();
+
+    let encoded = registry.encode_json(&claims).unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&encoded).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "iss": "Acme Inc.",
+            "acme_fwid": "fw-1",
+            "acme_counters": {"boots": 3, "resets": 1}
+        })
+    );
+
+    let decoded = registry.decode_json(&encoded).unwrap();
+    assert_eq!(
+        decoded.custom_claim::<FirmwareId>().unwrap(),
+        Some(FirmwareId("fw-1".to_string()))
+    );
+    assert_eq!(
+        decoded.custom_claim::<Counters>().unwrap(),
+        Some(Counters {
+            boots: 3,
+            resets: 1
+        })
+    );
+    let cbor = ClaimsSetClaimsCbor::try_from(&decoded).unwrap();
+    assert_eq!(
+        registry.json_values(&cbor).unwrap(),
+        vec![
+            ("acme_fwid".to_string(), serde_json::json!("fw-1")),
+            (
+                "acme_counters".to_string(),
+                serde_json::json!({"boots": 3, "resets": 1})
+            ),
+        ]
+    );
+
+    // claims that are not registered do not survive as named members
+    let mut partial = ClaimRegistry::new();
+    partial.register::<FirmwareId>().unwrap();
+    let decoded = partial.decode_json(&encoded).unwrap();
+    assert_eq!(decoded.custom_claim::<Counters>().unwrap(), None);
+    assert!(partial
+        .decode_json(br#"{"iss": "Acme Inc.", "acme_fwid": 7}"#)
+        .is_err());
+}
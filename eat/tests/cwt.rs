@@ -0,0 +1,88 @@
+use ciborium::de::from_reader;
+use common::TextOrBinary;
+use cose::maps::HeaderMapCbor;
+use cose::sign::*;
+use eat::cwt::*;
+use eat::maps::*;
+use hex_literal::hex;
+
+// signed and MACed CWTs from RFC 8392 Appendix A.3 and A.4 with the keys from Appendix A.2
+const RFC8392_SIGNED: [u8; 175] = hex!("d28443a10126a104524173796d6d657472696345434453413235365850a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b7158405427c1ff28d23fbad1f29c4c7c6a555e601d6fa29f9179bc3d7438bacaca5acd08c8d4d4f96131680c429a01f85951ecee743a52b9b63632c57209120e1c9e30");
+const RFC8392_EC_D: [u8; 32] =
+    hex!("6c1382765aec5358f117733d281c1c7bdc39884d04a45a1e6c67c858bc206c19");
+const RFC8392_MACED: [u8; 114] = hex!("d83dd18443a10104a1044c53796d6d65747269633235365850a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b7148093101ef6d789200");
+const RFC8392_MAC_KEY: [u8; 32] =
+    hex!("403697de87af64611c1d32a05dab0fe1fcb715a86ab435f1ec99192d79569388");
+
+mod utils;
+use utils::*;
+
+fn kid(kid: &[u8]) -> HeaderMapCbor {
+    HeaderMapCbor {
+        key_id: Some(TextOrBinary::Binary(kid.to_vec())),
+        ..empty_header_map()
+    }
+}
+
+#[test]
+fn rfc8392_cwt_test() {
+    let sk = p256::ecdsa::SigningKey::from_slice(&RFC8392_EC_D).unwrap();
+    let claims = verify_cwt(&RFC8392_SIGNED, sk.verifying_key()).unwrap();
+    assert_eq!(claims.iss, Some("coap://as.example.com".to_string()));
+    assert_eq!(claims.sub, Some("erikw".to_string()));
+    assert_eq!(claims.cti, Some(hex!("0b71").to_vec()));
+
+    let unwrapped = unwrap_cwt(&RFC8392_SIGNED).unwrap();
+    assert!(!unwrapped.cwt_tagged);
+    assert!(matches!(unwrapped.message, CoseMessage::Sign1(_)));
+    assert_eq!(unwrapped.claims_unverified().unwrap(), claims);
+
+    let key = HmacKey::new(ALG_HMAC_256_64, &RFC8392_MAC_KEY).unwrap();
+    let maced = verify_mac_cwt(&RFC8392_MACED, &key).unwrap();
+    assert_eq!(maced, claims);
+    assert!(unwrap_cwt(&RFC8392_MACED).unwrap().cwt_tagged);
+
+    // the wrong type of structure is rejected
+    assert!(verify_cwt(&RFC8392_MACED, sk.verifying_key()).is_err());
+    assert!(verify_mac_cwt(&RFC8392_SIGNED, &key).is_err());
+
+    // a modified payload fails verification
+    let mut tampered = RFC8392_SIGNED;
+    tampered[40] ^= 1;
+    assert!(verify_cwt(&tampered, sk.verifying_key()).is_err());
+}
+
+#[test]
+fn cwt_forms_test() {
+    let claims: ClaimsSetClaimsCbor = from_reader(CLAIMS.as_slice()).unwrap();
+    let sk = ed25519_dalek::SigningKey::from_bytes(&[3; 32]);
+    let vk = sk.verifying_key();
+    let key = HmacKey::new(ALG_HMAC_256_256, &[9; 32]).unwrap();
+
+    for (form, prefix) in [
+        (CwtForm::CwtTagged, vec![0xd8, 0x3d, 0xd2]),
+        (CwtForm::CoseTagged, vec![0xd2]),
+        (CwtForm::Untagged, vec![0x84]),
+    ] {
+        let token = sign_cwt(&claims, kid(b"ed"), form, &sk).unwrap();
+        assert!(token.starts_with(&prefix));
+        assert_eq!(verify_cwt(&token, &vk).unwrap(), claims);
+        let unwrapped = unwrap_cwt(&token).unwrap();
+        assert_eq!(unwrapped.cwt_tagged, form == CwtForm::CwtTagged);
+        assert_eq!(unwrapped.payload().unwrap(), CLAIMS.as_slice());
+
+        let token = mac_cwt(&claims, empty_header_map(), form, &key).unwrap();
+        assert_eq!(verify_mac_cwt(&token, &key).unwrap(), claims);
+        let other = HmacKey::new(ALG_HMAC_256_256, &[8; 32]).unwrap();
+        assert!(verify_mac_cwt(&token, &other).is_err());
+    }
+
+    let other = ed25519_dalek::SigningKey::from_bytes(&[4; 32]);
+    let token = sign_cwt(&claims, empty_header_map(), CwtForm::CwtTagged, &sk).unwrap();
+    assert!(verify_cwt(&token, &other.verifying_key()).is_err());
+
+    // the CWT tag may only wrap a tagged COSE structure
+    let untagged = sign_cwt(&claims, empty_header_map(), CwtForm::Untagged, &sk).unwrap();
+    let bad = [vec![0xd8, 0x3d], untagged].concat();
+    assert!(unwrap_cwt(&bad).is_err());
+}
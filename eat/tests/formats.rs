@@ -0,0 +1,169 @@
+use ciborium::de::from_reader;
+use ciborium::ser::into_writer;
+use ciborium::value::Value;
+use common::{CoapContentFormat, TextOrBinary};
+use eat::arrays::*;
+use eat::formats::*;
+use eat::maps::*;
+use hex_literal::hex;
+use std::path::Path;
+
+mod utils;
+use utils::*;
+
+// {1:"Acme Inc."}
+const CLAIMS: [u8; 12] = hex!("a1016941636d6520496e632e");
+
+struct Suit(Vec<u8>);
+
+// stands in for a SUIT envelope decoder
+struct SuitDecoder;
+impl PayloadDecoder for SuitDecoder {
+    fn name(&self) -> String {
+        "SUIT".to_string()
+    }
+    fn content_formats(&self) -> Vec<CoapContentFormat> {
+        vec![60000]
+    }
+    fn decode(
+        &self,
+        _content_type: CoapContentFormat,
+        payload: &TextOrBinary,
+    ) -> Result<DecodedPayload, String> {
+        match payload {
+            TextOrBinary::Binary(b) => Ok(DecodedPayload::Custom(Box::new(Suit(b.clone())))),
+            TextOrBinary::Text(_) => Err("SUIT payload is not binary".to_string()),
+        }
+    }
+}
+
+#[test]
+fn measurements_test() {
+    let coswid = get_file_as_byte_vec(Path::new("tests/examples/coswid_1.cbor"));
+    // the CoMID wrapped in tag 506
+    let mut comid = hex!("d901fa").to_vec();
+    let comid_bytes = get_file_as_byte_vec(Path::new("../corim/tests/examples/comid_1.cbor"));
+    comid.extend(&comid_bytes);
+    // the CoMID wrapped in a byte string within tag 506
+    let mut wrapped = vec![];
+    into_writer(
+        &Value::Tag(COMID_TAG, Box::new(Value::Bytes(comid_bytes))),
+        &mut wrapped,
+    )
+    .unwrap();
+
+    let mut claims: ClaimsSetClaimsCbor = from_reader(CLAIMS.as_slice()).unwrap();
+    claims.measurements = Some(MeasurementsTypeCbor(vec![
+        MeasurementsFormatCbor {
+            content_type: COSWID_CONTENT_FORMAT,
+            content_format: TextOrBinary::Binary(coswid),
+        },
+        MeasurementsFormatCbor {
+            content_type: 60,
+            content_format: TextOrBinary::Binary(comid),
+        },
+        MeasurementsFormatCbor {
+            content_type: 60,
+            content_format: TextOrBinary::Binary(hex!("d9ffff00").to_vec()),
+        },
+        MeasurementsFormatCbor {
+            content_type: 60,
+            content_format: TextOrBinary::Binary(wrapped),
+        },
+    ]));
+    let mut encoded = vec![];
+    into_writer(&claims, &mut encoded).unwrap();
+    let claims: ClaimsSetClaimsCbor = from_reader(encoded.as_slice()).unwrap();
+
+    let registry = PayloadRegistry::with_builtin_decoders();
+    assert_eq!(registry.names(), vec!["CoMID", "CoSWID"]);
+    let decoded = registry.measurements(&claims).unwrap();
+    assert_eq!(decoded.len(), 4);
+    match &decoded[0] {
+        DecodedPayload::CoSwid(c) => {
+            assert_eq!(
+                c.software_name,
+                "ACME Roadrunner Detector 2013 Coyote Edition SP1"
+            )
+        }
+        d => panic!("unexpected payload {:?}", d),
+    }
+    match &decoded[1] {
+        DecodedPayload::CoMid(c) => assert_eq!(c.language, Some("en-GB".to_string())),
+        d => panic!("unexpected payload {:?}", d),
+    }
+    // unknown content format and tag
+    assert!(matches!(
+        &decoded[2],
+        DecodedPayload::Raw(TextOrBinary::Binary(b)) if b == &hex!("d9ffff00")
+    ));
+    match (&decoded[1], &decoded[3]) {
+        (DecodedPayload::CoMid(c), DecodedPayload::CoMid(w)) => assert_eq!(c, w),
+        d => panic!("unexpected payloads {:?}", d),
+    }
+    assert!(registry.manifests(&claims).unwrap().is_empty());
+
+    // a payload that does not match its content format is an error
+    let bad = TextOrBinary::Binary(hex!("80").to_vec());
+    assert!(registry.decode(COSWID_CONTENT_FORMAT, &bad).is_err());
+    let text = TextOrBinary::Text("swid".to_string());
+    assert!(registry.decode(COSWID_CONTENT_FORMAT, &text).is_err());
+}
+
+#[test]
+fn tagged_coswid_test() {
+    let coswid = get_file_as_byte_vec(Path::new("tests/examples/coswid_1.cbor"));
+    let registry = PayloadRegistry::with_builtin_decoders();
+    let untagged = registry
+        .decode(COSWID_CONTENT_FORMAT, &TextOrBinary::Binary(coswid.clone()))
+        .unwrap();
+    // the CoSWID wrapped in the RFC 9393 tag
+    let mut tagged = hex!("da53574944").to_vec();
+    tagged.extend(&coswid);
+    assert_eq!(
+        registry.lookup_tag(COSWID_RFC9393_TAG).unwrap().name(),
+        "CoSWID"
+    );
+    for content_type in [COSWID_CONTENT_FORMAT, 60] {
+        let decoded = registry
+            .decode(content_type, &TextOrBinary::Binary(tagged.clone()))
+            .unwrap();
+        match (&untagged, &decoded) {
+            (DecodedPayload::CoSwid(u), DecodedPayload::CoSwid(t)) => assert_eq!(u, t),
+            d => panic!("unexpected payloads {:?}", d),
+        }
+    }
+}
+
+#[test]
+fn manifests_test() {
+    let mut claims: ClaimsSetClaimsCbor = from_reader(CLAIMS.as_slice()).unwrap();
+    claims.manifests = Some(ManifestsTypeCbor(vec![
+        ManifestFormatCbor {
+            content_type: 60000,
+            content_format: TextOrBinary::Binary(vec![1, 2, 3]),
+        },
+        ManifestFormatCbor {
+            content_type: 0,
+            content_format: TextOrBinary::Text("manifest".to_string()),
+        },
+    ]));
+
+    let mut registry = PayloadRegistry::with_builtin_decoders();
+    let decoded = registry.manifests(&claims).unwrap();
+    assert!(matches!(
+        &decoded[0],
+        DecodedPayload::Raw(TextOrBinary::Binary(_))
+    ));
+
+    registry.register(Box::new(SuitDecoder));
+    assert_eq!(registry.lookup(60000).unwrap().name(), "SUIT");
+    assert_eq!(registry.lookup_tag(COMID_TAG).unwrap().name(), "CoMID");
+    let decoded = registry.manifests(&claims).unwrap();
+    assert_eq!(decoded[0].custom::<Suit>().unwrap().0, vec![1, 2, 3]);
+    assert!(decoded[0].custom::<String>().is_none());
+    assert!(matches!(
+        &decoded[1],
+        DecodedPayload::Raw(TextOrBinary::Text(t)) if t == "manifest"
+    ));
+}
@@ -6,6 +6,8 @@ use hex_literal::hex;
 use std::path::Path;
 
 use eat::json_specific::*;
+use eat::maps::ClaimsSetClaims;
+use eat::submods::SubmoduleTree;
 
 mod utils;
 use utils::*;
@@ -62,7 +64,10 @@ fn json_selector_value_test() {
     let nc = NestedToken(Box::new(sel));
     let deb = DetachedEatBundle {
         main_token: nc,
-        detached_claims_set: vec![WrappedClaimsSet(eatbase64.to_string())],
+        detached_claims_sets: DetachedClaimsSets(vec![(
+            "detached".to_string(),
+            WrappedClaimsSet(eatbase64.to_string()),
+        )]),
     };
 
     //todo replace with actual detached submodule digest
@@ -115,3 +120,38 @@ fn json_selector_for_deb_value_test() {
         assert_eq!(scratch, decoded_json);
     }
 }
+
+#[test]
+fn submods_json_test() {
+    let json = r#"{"submods":{"tee":{"swname":"tee","submods":{"secure-boot":["JWT","a.b.c"]}},"ree":{"swname":"ree"}}}"#;
+    let mut claims: ClaimsSetClaims = serde_json::from_str(json).unwrap();
+    let submods = claims.submods.as_ref().unwrap();
+    assert_eq!(submods.len(), 2);
+    let names: Vec<&str> = submods.iter().map(|(n, _)| n).collect();
+    assert_eq!(names, vec!["tee", "ree"]);
+    assert!(matches!(
+        claims.submodule("tee/secure-boot"),
+        Some(Submodule::JsonSelector(_))
+    ));
+    let paths: Vec<String> = claims.submodules().into_iter().map(|(p, _)| p).collect();
+    assert_eq!(paths, vec!["tee", "tee/secure-boot", "ree"]);
+
+    let mut fw: ClaimsSetClaims = serde_json::from_str("{}").unwrap();
+    fw.sw_name = Some("fw".to_string());
+    assert_eq!(
+        claims.insert_submodule("ree/fw", Submodule::ClaimsSet(Box::new(fw.clone()))),
+        Ok(None)
+    );
+    assert!(claims.submodule("ree/fw").is_some());
+    assert!(claims
+        .insert_submodule("tee/secure-boot/x", Submodule::ClaimsSet(Box::new(fw)))
+        .is_err());
+
+    let encoded = serde_json::to_string(&claims).unwrap();
+    let decoded: ClaimsSetClaims = serde_json::from_str(&encoded).unwrap();
+    assert_eq!(decoded, claims);
+
+    // duplicate names are rejected
+    let duplicate = r#"{"submods":{"a":{},"a":{}}}"#;
+    assert!(serde_json::from_str::<ClaimsSetClaims>(duplicate).is_err());
+}
@@ -4,8 +4,8 @@ use ciborium::tag::Required;
 use ciborium::value::Value;
 use common::tuple::*;
 use common::tuple_map::*;
-use common::{TextOrBinary, TimeCbor, UeidType};
-use eat::cbor_specific::SubmoduleCbor;
+use common::{NumericDateCbor, Number, TextOrBinary, UeidType};
+use eat::cbor_specific::{SubmoduleCbor, SubmodsCbor};
 use eat::choices::{DebugStatusType, Oemid, ResultType};
 use hex_literal::hex;
 
@@ -229,10 +229,21 @@ fn exp_test() {
         assert_eq!(encoded_token2, v.to_vec());
     }
 
+    // untagged numeric dates, as used by CWT, are retained as such
+    let untagged = hex!("A1041A63921172");
+    let csc_d: ClaimsSetClaimsCbor = from_reader(untagged.as_slice()).unwrap();
+    assert_eq!(csc_d.exp, Some(NumericDateCbor::Untagged(0x63921172)));
+    let mut encoded_token = vec![];
+    let _ = into_writer(&csc_d, &mut encoded_token);
+    assert_eq!(untagged.to_vec(), encoded_token);
+    let csc_json: ClaimsSetClaims = csc_d.try_into().unwrap();
+    assert_eq!(csc_json.exp, Some(0x63921172));
+
     let invalid = vec![
         hex!("8104C11A63921172").to_vec(), // map not array
         hex!("A104C11A639211").to_vec(),   // value too short
         hex!("A104F6").to_vec(),           // not an iss value
+        hex!("A104C21A63921172").to_vec(), // not tagged as time
     ];
     for v in invalid {
         let csc_d: Result<ClaimsSetClaimsCbor, _> = from_reader(v.clone().as_slice());
@@ -645,8 +656,10 @@ fn location_test() {
     let valid = vec![
         hex!("A1190108A9010002010302040305040605070608C11A63923B9A0907").to_vec(),
         hex!("A1190108A70100020103020504060508C11A63923B9A0907").to_vec(),
+        // southern and western hemisphere coordinates as floats, negative altitude as an int
+        hex!("A1190108A301FBC040EF34D6A161E502FBC062E6B295E9E1B1032B").to_vec(),
     ];
-    for v in valid {
+    for v in &valid {
         let csc_d: ClaimsSetClaimsCbor = from_reader(v.clone().as_slice()).unwrap();
         let mut encoded_token = vec![];
         let _ = into_writer(&csc_d, &mut encoded_token);
@@ -659,6 +672,21 @@ fn location_test() {
         assert_eq!(encoded_token2, v.to_vec());
     }
 
+    let csc_d: ClaimsSetClaimsCbor = from_reader(valid[2].as_slice()).unwrap();
+    let location = csc_d.location.unwrap();
+    assert_eq!(location.latitude, Number::Float(-33.8688));
+    assert_eq!(location.longitude.as_f64(), -151.2093);
+    assert_eq!(location.altitude, Some(Number::Int(-12)));
+
+    let csc_json: ClaimsSetClaims = serde_json::from_str(
+        r#"{"location": {"latitude": -33.8688, "longitude": -151.2093, "altitude": -12}}"#,
+    )
+    .unwrap();
+    let csc_cbor: ClaimsSetClaimsCbor = csc_json.try_into().unwrap();
+    let mut encoded_token = vec![];
+    let _ = into_writer(&csc_cbor, &mut encoded_token);
+    assert_eq!(encoded_token, valid[2]);
+
     let invalid = vec![
         hex!("81190108A9010002010302040305040605070608C11A63923B9A0907").to_vec(), // map not array
         hex!("A1190108A9010002010302040305040605070608C11A63923B9A").to_vec(), // value too short
@@ -919,14 +947,14 @@ fn location_type_test() {
         hardware_version: None,
         intended_use: None,
         location: Some(LocationTypeCbor {
-            latitude: 0,
-            longitude: 1,
-            altitude: Some(2),
-            accuracy: Some(3),
-            altitude_accuracy: Some(4),
-            heading: Some(5),
-            speed: Some(6),
-            timestamp: Some(TimeCbor::T(Required(1670527898))),
+            latitude: Number::Int(0),
+            longitude: Number::Int(1),
+            altitude: Some(Number::Int(2)),
+            accuracy: Some(Number::Int(3)),
+            altitude_accuracy: Some(Number::Int(4)),
+            heading: Some(Number::Int(5)),
+            speed: Some(Number::Int(6)),
+            timestamp: Some(NumericDateCbor::T(Required(1670527898))),
             age: Some(7),
         }),
         profile: None,
@@ -1133,14 +1161,14 @@ fn submods_type_test() {
         hardware_version: None,
         intended_use: None,
         location: Some(LocationTypeCbor {
-            latitude: 0,
-            longitude: 1,
-            altitude: Some(2),
-            accuracy: Some(3),
-            altitude_accuracy: Some(4),
-            heading: Some(5),
-            speed: Some(6),
-            timestamp: Some(TimeCbor::T(Required(1670527898))),
+            latitude: Number::Int(0),
+            longitude: Number::Int(1),
+            altitude: Some(Number::Int(2)),
+            accuracy: Some(Number::Int(3)),
+            altitude_accuracy: Some(Number::Int(4)),
+            heading: Some(Number::Int(5)),
+            speed: Some(Number::Int(6)),
+            timestamp: Some(NumericDateCbor::T(Required(1670527898))),
             age: Some(7),
         }),
         profile: None,
@@ -1157,7 +1185,10 @@ fn submods_type_test() {
         submods: None,
         other: None,
     };
-    let sm = SubmoduleCbor::ClaimsSet(Box::new(sm_csc));
+    let sm = SubmodsCbor::from(vec![(
+        "location".to_string(),
+        SubmoduleCbor::ClaimsSet(Box::new(sm_csc)),
+    )]);
 
     let csc = ClaimsSetClaimsCbor {
         iss: None,
@@ -1201,4 +1232,12 @@ fn submods_type_test() {
     let mut encoded_token2 = vec![];
     let _ = into_writer(&csc_cbor, &mut encoded_token2);
     assert_eq!(encoded_token2, encoded_token);
+
+    // submods decode as a map from name to submodule
+    let decoded: ClaimsSetClaimsCbor = from_reader(encoded_token.as_slice()).unwrap();
+    assert_eq!(decoded, csc_cbor);
+    assert!(matches!(
+        decoded.submods.unwrap().get("location"),
+        Some(SubmoduleCbor::ClaimsSet(_))
+    ));
 }
@@ -0,0 +1,185 @@
+use common::TextOrBinary;
+use cose::maps::HeaderMapCbor;
+use cose::sign::{empty_header_map, CoseVerifier};
+use eat::bundle::*;
+use eat::cbor_specific::*;
+use eat::cwt::*;
+use eat::json_specific::*;
+use eat::jwt::*;
+use eat::maps::*;
+use eat::nested::*;
+
+mod utils;
+use utils::*;
+
+fn kid(kid: &[u8]) -> HeaderMapCbor {
+    HeaderMapCbor {
+        key_id: Some(TextOrBinary::Binary(kid.to_vec())),
+        ..empty_header_map()
+    }
+}
+
+fn key(seed: u8) -> ed25519_dalek::SigningKey {
+    ed25519_dalek::SigningKey::from_bytes(&[seed; 32])
+}
+
+// resolves keys by key identifier, ignoring the path
+struct Keys(Vec<(Vec<u8>, ed25519_dalek::VerifyingKey)>);
+impl KeyResolver for Keys {
+    fn resolve(&self, _path: &str, kid: Option<&[u8]>) -> Option<&dyn CoseVerifier> {
+        let kid = kid?;
+        self.0
+            .iter()
+            .find(|(k, _)| k == kid)
+            .map(|(_, v)| v as &dyn CoseVerifier)
+    }
+}
+
+fn keys() -> Keys {
+    Keys(vec![
+        (b"root".to_vec(), key(1).verifying_key()),
+        (b"tee".to_vec(), key(2).verifying_key()),
+        (b"sb".to_vec(), key(3).verifying_key()),
+    ])
+}
+
+// root CWT -> "tee" CWT -> "secure-boot" JWT -> "fw" inline claims set
+fn composite_token() -> Vec<u8> {
+    let mut fw: ClaimsSetClaims = serde_json::from_str("{}").unwrap();
+    fw.sw_name = Some("fw".to_string());
+    let mut sb: ClaimsSetClaims = serde_json::from_str("{}").unwrap();
+    sb.sw_name = Some("secure-boot".to_string());
+    sb.submods = Some(Submods::from(vec![(
+        "fw".to_string(),
+        Submodule::ClaimsSet(Box::new(fw)),
+    )]));
+    let sb_token = sign_jwt(&sb, Some("sb"), &key(3)).unwrap();
+
+    let mut tee = claims("tee");
+    tee.submods = Some(SubmodsCbor::from(vec![(
+        "secure-boot".to_string(),
+        SubmoduleCbor::SelectorCbor(SelectorCbor::JsonTokenInsideCborToken(sb_token)),
+    )]));
+    let tee_token = sign_cwt(&tee, kid(b"tee"), CwtForm::CwtTagged, &key(2)).unwrap();
+
+    let mut root = claims("root");
+    root.submods = Some(SubmodsCbor::from(vec![(
+        "tee".to_string(),
+        SubmoduleCbor::SelectorCbor(SelectorCbor::CborTokenInsideCborToken(tee_token)),
+    )]));
+    sign_cwt(&root, kid(b"root"), CwtForm::CwtTagged, &key(1)).unwrap()
+}
+
+#[test]
+fn nested_cwt_jwt_test() {
+    let token = composite_token();
+    let keys = keys();
+    let tree = NestedTokenVerifier::new(&keys).verify_cbor(&token);
+    assert!(tree.is_trusted());
+    assert!(tree.is_accepted());
+    assert_eq!(tree.status, NodeStatus::Verified);
+
+    let tee = tree.find("tee").unwrap();
+    assert_eq!(tee.status, NodeStatus::Verified);
+    match &tee.claims {
+        Some(NodeClaims::Cbor(c)) => assert_eq!(c.sw_name, Some("tee".to_string())),
+        _ => panic!(),
+    }
+    let sb = tree.find("tee/secure-boot").unwrap();
+    assert_eq!(sb.status, NodeStatus::Verified);
+    assert!(matches!(&sb.claims, Some(NodeClaims::Json(_))));
+    let fw = tree.find("tee/secure-boot/fw").unwrap();
+    assert_eq!(fw.status, NodeStatus::Inline);
+    assert!(fw.children.is_empty());
+    assert!(tree.find("tee/missing").is_none());
+
+    // the depth limit stops the walk
+    let tree = NestedTokenVerifier::new(&keys)
+        .max_depth(2)
+        .verify_cbor(&token);
+    assert!(!tree.is_trusted());
+    assert_eq!(
+        tree.find("tee/secure-boot").unwrap().status,
+        NodeStatus::Verified
+    );
+    let fw = tree.find("tee/secure-boot/fw").unwrap();
+    assert_eq!(fw.status, NodeStatus::DepthExceeded);
+    assert!(fw.claims.is_none());
+
+    // a missing key is reported for the affected node and nothing below it is examined
+    let partial = Keys(keys.0[..2].to_vec());
+    let tree = NestedTokenVerifier::new(&partial).verify_cbor(&token);
+    assert_eq!(tree.find("tee").unwrap().status, NodeStatus::Verified);
+    let sb = tree.find("tee/secure-boot").unwrap();
+    assert_eq!(sb.status, NodeStatus::NoKey);
+    assert!(sb.children.is_empty());
+
+    // the wrong key causes verification to fail
+    let wrong = Keys(vec![
+        (b"root".to_vec(), key(1).verifying_key()),
+        (b"tee".to_vec(), key(9).verifying_key()),
+    ]);
+    let tree = NestedTokenVerifier::new(&wrong).verify_cbor(&token);
+    assert!(matches!(
+        tree.find("tee").unwrap().status,
+        NodeStatus::Failed(_)
+    ));
+    assert!(!tree.is_trusted());
+}
+
+#[test]
+fn nested_bundle_test() {
+    let deb = DetachedBundleBuilder::new(claims("tee"))
+        .detached_claims_set("app", &claims("app"))
+        .unwrap()
+        .build(|c| sign_cwt(c, kid(b"tee"), CwtForm::CwtTagged, &key(2)))
+        .unwrap();
+    let mut root = claims("root");
+    root.submods = Some(SubmodsCbor::from(vec![
+        (
+            "tee".to_string(),
+            SubmoduleCbor::SelectorCbor(SelectorCbor::CborTokenInsideCborToken(
+                encode_bundle(&deb, true).unwrap(),
+            )),
+        ),
+        ("digest".to_string(), deb_digest_submodule(&claims("other"))),
+    ]));
+    let token = sign_cwt(&root, kid(b"root"), CwtForm::CoseTagged, &key(1)).unwrap();
+
+    let keys = keys();
+    let tree = NestedTokenVerifier::new(&keys).verify_cbor(&token);
+    assert!(tree.is_trusted());
+    assert_eq!(tree.find("tee").unwrap().status, NodeStatus::Verified);
+    let app = tree.find("tee/app").unwrap();
+    assert_eq!(app.status, NodeStatus::Verified);
+    assert_eq!(app.claims, Some(NodeClaims::Cbor(claims("app"))));
+    assert_eq!(tree.find("digest").unwrap().status, NodeStatus::Digest);
+
+    // a bundle with a substituted detached claims set fails
+    let mut bad = deb.clone();
+    bad.detached_claims_sets.0[0].1 =
+        eat::arrays::WrappedClaimsSetCbor(encode_claims(&claims("evil")).unwrap());
+    root.submods = Some(SubmodsCbor::from(vec![(
+        "tee".to_string(),
+        SubmoduleCbor::SelectorCbor(SelectorCbor::CborTokenInsideCborToken(
+            encode_bundle(&bad, true).unwrap(),
+        )),
+    )]));
+    let token = sign_cwt(&root, kid(b"root"), CwtForm::CoseTagged, &key(1)).unwrap();
+    let tree = NestedTokenVerifier::new(&keys).verify_cbor(&token);
+    assert!(matches!(
+        tree.find("tee").unwrap().status,
+        NodeStatus::Failed(_)
+    ));
+}
+
+fn deb_digest_submodule(claims: &ClaimsSetClaimsCbor) -> SubmoduleCbor {
+    let hash_algorithm = common::TextOrInt::Int(SHA_256);
+    let digest = compute_digest(&hash_algorithm, &encode_claims(claims).unwrap()).unwrap();
+    SubmoduleCbor::SelectorCbor(SelectorCbor::DetachedSubmoduleDigest(
+        eat::arrays::DetachedSubmoduleDigestCbor {
+            hash_algorithm,
+            digest,
+        },
+    ))
+}
@@ -0,0 +1,151 @@
+use ciborium::{de::from_reader, ser::into_writer, value::Value};
+use common::{OidType, TextOrInt};
+use corim::choices::ProfileTypeChoice;
+use eat::maps::*;
+use eat::profile::*;
+use eat::psa::*;
+use eat::validate::*;
+
+const NOW: i64 = 1_700_000_000;
+
+fn int(i: i64) -> Value {
+    Value::Integer(i.into())
+}
+
+fn component(measurement_type: &str) -> Value {
+    Value::Map(vec![
+        (int(1), Value::Text(measurement_type.to_string())),
+        (int(2), Value::Bytes(vec![0x11; 32])),
+        (int(4), Value::Text("1.2.3".to_string())),
+        (int(5), Value::Bytes(vec![0x22; 32])),
+    ])
+}
+
+fn psa_token(lifecycle: i64, instance_id_type: u8) -> ClaimsSetClaimsCbor {
+    let token = Value::Map(vec![
+        (int(NONCE_LABEL), Value::Bytes(vec![0xaa; 32])),
+        (
+            int(UEID_LABEL),
+            Value::Bytes([vec![instance_id_type], vec![0xbb; 32]].concat()),
+        ),
+        (int(PROFILE_LABEL), Value::Text(PSA_PROFILE_URI.to_string())),
+        (int(PSA_CLIENT_ID_LABEL), int(-1)),
+        (int(PSA_SECURITY_LIFECYCLE_LABEL), int(lifecycle)),
+        (
+            int(PSA_IMPLEMENTATION_ID_LABEL),
+            Value::Bytes(vec![0xcc; 32]),
+        ),
+        (
+            int(PSA_CERTIFICATION_REFERENCE_LABEL),
+            Value::Text("1234567890123-12345".to_string()),
+        ),
+        (
+            int(PSA_SOFTWARE_COMPONENTS_LABEL),
+            Value::Array(vec![component("BL"), component("PRoT")]),
+        ),
+    ]);
+    let mut encoded = vec![];
+    into_writer(&token, &mut encoded).unwrap();
+    from_reader(encoded.as_slice()).unwrap()
+}
+
+#[test]
+fn psa_claims_test() {
+    let claims = psa_token(0x3000, 0x01);
+    let psa = PsaClaims::try_from(&claims).unwrap();
+    assert_eq!(psa.client_id, -1);
+    assert_eq!(psa.lifecycle(), Ok(PsaSecurityLifecycle::Secured));
+    assert_eq!(psa.implementation_id, vec![0xcc; 32]);
+    assert_eq!(psa.software_components.len(), 2);
+    assert_eq!(
+        psa.software_components[1].measurement_type,
+        Some("PRoT".to_string())
+    );
+    assert_eq!(psa.software_components[0].signer_id, vec![0x22; 32]);
+    assert_eq!(psa.boot_seed, None);
+
+    assert_eq!(
+        PsaSecurityLifecycle::try_from(0x10ff),
+        Ok(PsaSecurityLifecycle::AssemblyAndTest)
+    );
+    assert!(PsaSecurityLifecycle::try_from(0x7000).is_err());
+}
+
+#[test]
+fn profile_registry_test() {
+    let registry = ProfileRegistry::with_builtin_profiles();
+    assert_eq!(registry.names(), vec!["PSA".to_string()]);
+    let psa_uri = ProfileTypeChoice::Uri(PSA_PROFILE_URI.to_string());
+    assert!(registry.lookup(&psa_uri).is_some());
+    assert!(registry
+        .lookup(&ProfileTypeChoice::Uri(
+            "tag:example.com,2024:other".to_string()
+        ))
+        .is_none());
+    assert!(same_profile(
+        &ProfileTypeChoice::Oid(OidType::Oid(vec![0x2a, 0x03])),
+        &ProfileTypeChoice::Oid2(OidType::Oid(vec![0x2a, 0x03]))
+    ));
+
+    let validator = ClaimsValidator::new();
+    let report = registry
+        .validate(&validator, &psa_token(0x3000, 0x01), NOW)
+        .unwrap();
+    assert!(report.is_valid(), "{:?}", report.failures);
+    assert!(report.checked.contains(&"PSA".to_string()));
+
+    let report = registry
+        .validate(&validator, &psa_token(0x7000, 0x02), NOW)
+        .unwrap();
+    let profile_failures = report
+        .failures
+        .iter()
+        .filter(|f| matches!(f, ValidationFailure::Profile { profile, .. } if profile == "PSA"))
+        .count();
+    assert_eq!(profile_failures, 2);
+
+    let mut claims = psa_token(0x3000, 0x01);
+    claims.profile = Some(ProfileTypeChoice::Uri(
+        "tag:example.com,2024:other".to_string(),
+    ));
+    assert!(registry.validate(&validator, &claims, NOW).is_err());
+    claims.profile = None;
+    assert!(registry.profile_for(&claims).is_err());
+}
+
+#[test]
+fn psa_profile_claims_test() {
+    // A PSA token without an implementation ID, and with a nonce of the wrong size
+    let token = psa_token(0x3000, 0x01);
+    let mut values = claim_values(&token).unwrap();
+    values.retain(|(k, _)| *k != TextOrInt::Int(PSA_IMPLEMENTATION_ID_LABEL));
+    for (k, v) in values.iter_mut() {
+        if *k == TextOrInt::Int(NONCE_LABEL) {
+            *v = Value::Bytes(vec![0xaa; 16]);
+        }
+    }
+    let token = Value::Map(
+        values
+            .into_iter()
+            .map(|(k, v)| match k {
+                TextOrInt::Int(i) => (int(i), v),
+                TextOrInt::Text(t) => (Value::Text(t), v),
+            })
+            .collect(),
+    );
+    let mut encoded = vec![];
+    into_writer(&token, &mut encoded).unwrap();
+    let claims: ClaimsSetClaimsCbor = from_reader(encoded.as_slice()).unwrap();
+
+    let report = ClaimsValidator::new()
+        .validate_with_profile(&claims, NOW, &PsaProfile)
+        .unwrap();
+    assert!(report.failures.contains(&ValidationFailure::MissingClaim {
+        profile: "PSA".to_string(),
+        label: TextOrInt::Int(PSA_IMPLEMENTATION_ID_LABEL),
+    }));
+    assert!(report.failures.iter().any(|f| matches!(
+        f,
+        ValidationFailure::InvalidClaim { label, .. } if *label == TextOrInt::Int(NONCE_LABEL)
+    )));
+}
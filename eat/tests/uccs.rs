@@ -0,0 +1,88 @@
+use ciborium::de::from_reader;
+use cose::sign::CoseVerifier;
+use eat::cbor_specific::*;
+use eat::cwt::*;
+use eat::maps::*;
+use eat::nested::*;
+use eat::uccs::*;
+use hex_literal::hex;
+use std::path::Path;
+
+mod utils;
+use utils::*;
+
+struct NoKeys;
+impl KeyResolver for NoKeys {
+    fn resolve(&self, _path: &str, _kid: Option<&[u8]>) -> Option<&dyn CoseVerifier> {
+        None
+    }
+}
+
+#[test]
+fn uccs_encode_decode_test() {
+    let claims: ClaimsSetClaimsCbor = from_reader(CLAIMS.as_slice()).unwrap();
+    let encoded = encode_uccs(&claims).unwrap();
+    assert_eq!(encoded[..3], hex!("d90259"));
+    assert_eq!(encoded[3..], CLAIMS);
+    assert_eq!(decode_uccs(&encoded).unwrap(), claims);
+
+    let fixture = get_file_as_byte_vec(Path::new("tests/examples/uccs_1.cbor"));
+    assert_eq!(fixture, encoded);
+
+    // untagged claims sets and other tags are rejected
+    assert!(decode_uccs(&CLAIMS).is_err());
+    let mut other = encoded.clone();
+    other[2] = 0x5a;
+    assert!(decode_uccs(&other).is_err());
+}
+
+#[test]
+fn uccs_nested_test() {
+    let fixture = get_file_as_byte_vec(Path::new("tests/examples/uccs_1.cbor"));
+
+    // unprotected tokens are rejected unless explicitly allowed
+    let tree = NestedTokenVerifier::new(&NoKeys).verify_cbor(&fixture);
+    assert!(matches!(tree.status, NodeStatus::Failed(_)));
+    assert!(tree.claims.is_none());
+    assert!(!tree.is_trusted());
+    assert!(!tree.is_accepted());
+
+    // accepted UCCS are still not trusted
+    let tree = NestedTokenVerifier::new(&NoKeys)
+        .allow_uccs(true)
+        .verify_cbor(&fixture);
+    assert_eq!(tree.status, NodeStatus::Unprotected);
+    assert!(!tree.is_trusted());
+    assert!(tree.is_accepted());
+    let mut claims: ClaimsSetClaimsCbor = from_reader(CLAIMS.as_slice()).unwrap();
+    assert_eq!(tree.claims, Some(NodeClaims::Cbor(claims.clone())));
+
+    // a UCCS may carry nested UCCS and CWTs, which still require keys
+    let key = ed25519_dalek::SigningKey::from_bytes(&[1; 32]);
+    let cwt = sign_cwt(
+        &claims,
+        cose::sign::empty_header_map(),
+        CwtForm::CwtTagged,
+        &key,
+    )
+    .unwrap();
+    claims.submods = Some(SubmodsCbor::from(vec![
+        (
+            "tee".to_string(),
+            SubmoduleCbor::SelectorCbor(SelectorCbor::CborTokenInsideCborToken(fixture)),
+        ),
+        (
+            "signed".to_string(),
+            SubmoduleCbor::SelectorCbor(SelectorCbor::CborTokenInsideCborToken(cwt)),
+        ),
+    ]));
+    let token = encode_uccs(&claims).unwrap();
+    let tree = NestedTokenVerifier::new(&NoKeys)
+        .allow_uccs(true)
+        .verify_cbor(&token);
+    assert_eq!(tree.status, NodeStatus::Unprotected);
+    assert_eq!(tree.find("tee").unwrap().status, NodeStatus::Unprotected);
+    assert_eq!(tree.find("signed").unwrap().status, NodeStatus::NoKey);
+    assert!(!tree.is_trusted());
+    assert!(!tree.is_accepted());
+}
@@ -1,3 +1,6 @@
+use ciborium::de::from_reader;
+use eat::maps::ClaimsSetClaimsCbor;
+use hex_literal::hex;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
@@ -40,3 +43,15 @@ pub fn read_cbor(filename: &Option<String>) -> Vec<u8> {
     }
     vec![]
 }
+
+/// Claims set from the claims_set_claims_test in maps.rs
+#[allow(dead_code)]
+pub const CLAIMS: [u8; 128] = hex!("b0016941636d6520496e632e026772722d74726170036941636d6520496e632e04c10005c10006c1000746ffffffffffff0a4800000000000000000b5101deadbeefdeadbeefdeadbeefdeadbeef0c6941636d6520496e632e0d46ffffffffffff0e030ff5100111a201fb4028ae147ae147ae02fb404c63d70a3d70a413183c");
+
+/// Returns the [CLAIMS] claims set with its `swname` claim set to `sw_name`.
+#[allow(dead_code)]
+pub fn claims(sw_name: &str) -> ClaimsSetClaimsCbor {
+    let mut claims: ClaimsSetClaimsCbor = from_reader(CLAIMS.as_slice()).unwrap();
+    claims.sw_name = Some(sw_name.to_string());
+    claims
+}
@@ -0,0 +1,249 @@
+use ciborium::de::from_reader;
+use common::{BytesType, NonceType, TextOrInt, NumericDateCbor, UeidType};
+use eat::choices::Oemid;
+use eat::maps::*;
+use eat::validate::*;
+use hex_literal::hex;
+
+const NOW: i64 = 1_700_000_000;
+
+fn claims() -> ClaimsSetClaimsCbor {
+    // {1: "Acme Inc."}
+    let iss = hex!("a1016941636d6520496e632e");
+    let mut claims: ClaimsSetClaimsCbor = from_reader(iss.as_slice()).unwrap();
+    claims.aud = Some("verifier".to_string());
+    claims.iat = Some(NumericDateCbor::Untagged(NOW - 10));
+    claims.nbf = Some(NumericDateCbor::Untagged(NOW - 10));
+    claims.exp = Some(NumericDateCbor::Untagged(NOW + 300));
+    claims.nonce = Some(NonceType::One(BytesType::Bytes(vec![7; 16])));
+    claims.ueid = Some(UeidType::Ueid([vec![1], vec![0xab; 16]].concat()));
+    claims.oemid = Some(Oemid::Ieee(vec![1, 2, 3]));
+    claims
+}
+
+struct TestProfile;
+impl ClaimsProfile for TestProfile {
+    fn name(&self) -> String {
+        "test".to_string()
+    }
+    fn required_claims(&self) -> Vec<TextOrInt> {
+        vec![TextOrInt::Int(UEID_LABEL), TextOrInt::Int(SW_NAME_LABEL)]
+    }
+    fn forbidden_claims(&self) -> Vec<TextOrInt> {
+        vec![TextOrInt::Int(AUD_LABEL)]
+    }
+    fn check(&self, claims: &ClaimsSetClaimsCbor) -> Vec<String> {
+        match claims.secure_boot {
+            Some(true) => vec![],
+            _ => vec!["secure boot must be enabled".to_string()],
+        }
+    }
+}
+
+#[test]
+fn time_validation_test() {
+    let claims = claims();
+    let validator = ClaimsValidator::new();
+    let report = validator.validate(&claims, NOW).unwrap();
+    assert!(report.is_valid());
+    assert!(report.checked.contains(&"exp".to_string()));
+
+    let report = validator.validate(&claims, NOW + 301).unwrap();
+    assert_eq!(
+        report.failures,
+        vec![ValidationFailure::Expired {
+            exp: NOW + 300,
+            now: NOW + 301
+        }]
+    );
+    let report = validator.validate(&claims, NOW - 11).unwrap();
+    assert_eq!(report.failures.len(), 2);
+    assert!(matches!(
+        report.failures[0],
+        ValidationFailure::NotYetValid { .. }
+    ));
+    assert!(matches!(
+        report.failures[1],
+        ValidationFailure::IssuedInFuture { .. }
+    ));
+
+    // clock skew is allowed in both directions
+    let skewed = ClaimsValidator::new().clock_skew(30);
+    assert!(skewed.validate(&claims, NOW + 301).unwrap().is_valid());
+    assert!(skewed.validate(&claims, NOW - 11).unwrap().is_valid());
+    assert!(!skewed.validate(&claims, NOW + 331).unwrap().is_valid());
+
+    // maximum age is measured from iat
+    let aged = ClaimsValidator::new().max_age(60);
+    assert!(aged.validate(&claims, NOW + 50).unwrap().is_valid());
+    assert_eq!(
+        aged.validate(&claims, NOW + 51).unwrap().failures,
+        vec![ValidationFailure::TooOld {
+            iat: NOW - 10,
+            now: NOW + 51
+        }]
+    );
+
+    // tagged times are accepted
+    let mut tagged = claims.clone();
+    tagged.exp = Some(NumericDateCbor::T(ciborium::tag::Required(NOW - 1)));
+    assert!(!validator.validate(&tagged, NOW).unwrap().is_valid());
+}
+
+#[test]
+fn nonce_and_party_validation_test() {
+    let mut claims = claims();
+    let validator = ClaimsValidator::new()
+        .expected_nonce(&[7; 16])
+        .expected_audience("other")
+        .expected_audience("verifier")
+        .expected_issuer("Acme Inc.");
+    assert!(validator.validate(&claims, NOW).unwrap().is_valid());
+
+    let report = ClaimsValidator::new()
+        .expected_nonce(&[8; 16])
+        .expected_audience("other")
+        .expected_issuer("Mallory")
+        .validate(&claims, NOW)
+        .unwrap();
+    assert_eq!(
+        report.failures,
+        vec![
+            ValidationFailure::NonceMismatch(vec![8; 16]),
+            ValidationFailure::AudienceMismatch(Some("verifier".to_string())),
+            ValidationFailure::IssuerMismatch(Some("Acme Inc.".to_string())),
+        ]
+    );
+
+    // every expected nonce must be echoed when several are provided
+    claims.nonce = Some(NonceType::More(vec![
+        BytesType::Bytes(vec![7; 16]),
+        BytesType::Bytes(vec![9; 32]),
+    ]));
+    let both = ClaimsValidator::new()
+        .expected_nonce(&[7; 16])
+        .expected_nonce(&[9; 32]);
+    assert!(both.validate(&claims, NOW).unwrap().is_valid());
+    let report = both.expected_nonce(&[1; 8]).validate(&claims, NOW).unwrap();
+    assert_eq!(
+        report.failures,
+        vec![ValidationFailure::NonceMismatch(vec![1; 8])]
+    );
+
+    // nonces must be between 8 and 64 bytes
+    claims.nonce = Some(NonceType::One(BytesType::Bytes(vec![7; 7])));
+    assert_eq!(
+        ClaimsValidator::new()
+            .validate(&claims, NOW)
+            .unwrap()
+            .failures,
+        vec![ValidationFailure::InvalidNonceSize(7)]
+    );
+    claims.nonce = None;
+    assert_eq!(
+        validator.validate(&claims, NOW).unwrap().failures,
+        vec![ValidationFailure::MissingNonce]
+    );
+    claims.aud = None;
+    claims.iss = None;
+    let report = ClaimsValidator::new()
+        .expected_audience("verifier")
+        .expected_issuer("Acme Inc.")
+        .validate(&claims, NOW)
+        .unwrap();
+    assert_eq!(
+        report.failures,
+        vec![
+            ValidationFailure::AudienceMismatch(None),
+            ValidationFailure::IssuerMismatch(None)
+        ]
+    );
+}
+
+#[test]
+fn size_and_profile_validation_test() {
+    let mut claims = claims();
+    let validator = ClaimsValidator::new();
+    for (ueid, valid) in [
+        ([vec![1], vec![0; 16]].concat(), true),
+        ([vec![1], vec![0; 32]].concat(), true),
+        ([vec![1], vec![0; 20]].concat(), false),
+        ([vec![2], vec![0; 6]].concat(), true),
+        ([vec![2], vec![0; 8]].concat(), true),
+        ([vec![3], vec![0; 14]].concat(), true),
+        ([vec![3], vec![0; 8]].concat(), false),
+        ([vec![4], vec![0; 8]].concat(), false),
+        (vec![1; 5], false),
+        (vec![1; 34], false),
+    ] {
+        claims.ueid = Some(UeidType::Ueid(ueid));
+        let report = validator.validate(&claims, NOW).unwrap();
+        assert_eq!(report.is_valid(), valid);
+    }
+    claims.ueid = None;
+
+    claims.oemid = Some(Oemid::Random(vec![0; 15]));
+    claims.hardware_model = Some(vec![]);
+    let report = validator.validate(&claims, NOW).unwrap();
+    assert!(matches!(
+        report.failures[0],
+        ValidationFailure::InvalidOemid(_)
+    ));
+    assert_eq!(
+        report.failures[1],
+        ValidationFailure::InvalidHardwareModel(0)
+    );
+    claims.oemid = Some(Oemid::Pen(76543));
+    claims.hardware_model = Some(vec![1; 32]);
+    assert!(validator.validate(&claims, NOW).unwrap().is_valid());
+
+    // profile requirements
+    let validator = ClaimsValidator::new().profile(Box::new(TestProfile));
+    let report = validator.validate(&claims, NOW).unwrap();
+    assert!(report.checked.contains(&"test".to_string()));
+    assert_eq!(
+        report.failures,
+        vec![
+            ValidationFailure::MissingClaim {
+                profile: "test".to_string(),
+                label: TextOrInt::Int(UEID_LABEL)
+            },
+            ValidationFailure::MissingClaim {
+                profile: "test".to_string(),
+                label: TextOrInt::Int(SW_NAME_LABEL)
+            },
+            ValidationFailure::ForbiddenClaim {
+                profile: "test".to_string(),
+                label: TextOrInt::Int(AUD_LABEL)
+            },
+            ValidationFailure::Profile {
+                profile: "test".to_string(),
+                reason: "secure boot must be enabled".to_string()
+            },
+        ]
+    );
+    claims.ueid = Some(UeidType::Ueid([vec![2], vec![0; 6]].concat()));
+    claims.sw_name = Some("fw".to_string());
+    claims.aud = None;
+    claims.secure_boot = Some(true);
+    assert!(validator.validate(&claims, NOW).unwrap().is_valid());
+
+    // JSON claims sets are validated in the same way
+    let json: ClaimsSetClaims = (&claims).try_into().unwrap();
+    assert!(validator.validate_json(&json, NOW).unwrap().is_valid());
+    assert_eq!(
+        claim_labels(&claims).unwrap(),
+        vec![
+            TextOrInt::Int(ISS_LABEL),
+            TextOrInt::Int(EXP_LABEL),
+            TextOrInt::Int(NBF_LABEL),
+            TextOrInt::Int(IAT_LABEL),
+            TextOrInt::Int(NONCE_LABEL),
+            TextOrInt::Int(HARDWARE_MODEL_LABEL),
+            TextOrInt::Int(SECURE_BOOT_LABEL),
+            TextOrInt::Int(SW_NAME_LABEL),
+            TextOrInt::Int(UEID_LABEL),
+            TextOrInt::Int(OEMID_LABEL),
+        ]
+    );
+}